@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{PermissionSet, Permissions};
+
+// Permissions granted on the keys matching a pattern. A pattern is either
+// `*` for every key, a store prefix like `orders:*` for the `orders` store
+// and everything nested in it, or an exact key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AclRule {
+    pub pattern: String,
+    pub permissions: PermissionSet,
+}
+
+impl AclRule {
+    pub fn new(pattern: String, permissions: PermissionSet) -> AclRule {
+        AclRule {
+            pattern,
+            permissions,
+        }
+    }
+
+    pub fn is_valid_pattern(pattern: &str) -> bool {
+        let prefix = match pattern.strip_suffix(":*") {
+            Some(prefix) => prefix,
+            None if pattern == "*" => return true,
+            None => pattern,
+        };
+        !prefix.is_empty() && !prefix.contains('*')
+    }
+
+    pub fn matches(&self, key: &str) -> bool {
+        if self.pattern == "*" {
+            return true;
+        }
+        match self.pattern.strip_suffix(":*") {
+            Some(store) => {
+                key == store
+                    || key
+                        .strip_prefix(store)
+                        .is_some_and(|rest| rest.starts_with(':'))
+            }
+            None => key == self.pattern,
+        }
+    }
+
+    pub fn allows(&self, permission: Permissions, key: &str) -> bool {
+        self.permissions.contains(permission) && self.matches(key)
+    }
+}
+
+impl std::fmt::Display for AclRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}={}", self.pattern, self.permissions)
+    }
+}
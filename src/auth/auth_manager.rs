@@ -1,68 +1,59 @@
 use regex::Regex;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
 use crate::{
-    auth::{Permissions, User},
-    data::{Key, Store, StoreManager},
+    auth::{AuthStore, PermissionSet, Permissions, Role, User},
+    data::now_millis,
+    error::KvError,
     session::Session,
 };
 
 pub struct AuthManager {
-    store_access: Arc<Mutex<Store>>,
+    auth_store: Arc<Mutex<AuthStore>>,
+    // Passwords older than this have to be changed at the next login.
+    password_max_age: Option<Duration>,
 }
 
 impl AuthManager {
-    pub async fn new(store_access: Arc<Mutex<Store>>) -> Result<AuthManager, String> {
-        let mut auth_manager = AuthManager {
-            store_access: store_access.clone(),
-        };
-
-        auth_manager.setup_auth_store().await?;
-
-        Ok(auth_manager)
-    }
-
-    async fn setup_auth_store(&mut self) -> Result<(), String> {
-        let mut store = self.store_access.lock().await;
-
-        match store.get_store(Key::new("_auth".to_string())) {
-            Ok(_) => match store.get_store(Key::new("_auth:users".to_string())) {
-                Ok(_) => return Ok(()),
-                Err(_) => {
-                    store.set_store(Key::new("_auth:users".to_string()))?;
-                    Ok(())
-                }
-            },
-            Err(_) => {
-                store.set_store(Key::new("_auth".to_string()))?;
-                store.set_store(Key::new("_auth:users".to_string()))?;
-                Ok(())
-            }
+    pub fn new(
+        auth_store: Arc<Mutex<AuthStore>>,
+        password_max_age: Option<Duration>,
+    ) -> AuthManager {
+        AuthManager {
+            auth_store,
+            password_max_age,
         }
     }
 
-    fn validate_password(password: &str) -> Result<(), String> {
+    fn validate_password(password: &str) -> Result<(), KvError> {
         if password.len() < 8 {
-            return Err("Password too short!".to_string());
+            return Err(KvError::InvalidPassword("Password too short!".to_string()));
         }
 
         let lowercase_re = Regex::new(r"[a-z]").unwrap();
 
         if !lowercase_re.is_match(password) {
-            return Err("Password must contain a lowercase letter!".to_string());
+            return Err(KvError::InvalidPassword(
+                "Password must contain a lowercase letter!".to_string(),
+            ));
         }
 
         let uppercase_re = Regex::new(r"[A-Z]").unwrap();
 
         if !uppercase_re.is_match(password) {
-            return Err("Password must contain an uppercase letter!".to_string());
+            return Err(KvError::InvalidPassword(
+                "Password must contain an uppercase letter!".to_string(),
+            ));
         }
 
         let digit_re = Regex::new(r"\d").unwrap();
 
         if !digit_re.is_match(password) {
-            return Err("Password must contain a digit!".to_string());
+            return Err(KvError::InvalidPassword(
+                "Password must contain a digit!".to_string(),
+            ));
         }
 
         Ok(())
@@ -72,29 +63,21 @@ impl AuthManager {
         &mut self,
         username: String,
         password: String,
-        permission: u8,
-    ) -> Result<String, String> {
+        permission: PermissionSet,
+    ) -> Result<String, KvError> {
         Self::validate_password(&password)?;
 
-        User::create(
-            &username,
-            &password,
-            permission,
-            Arc::clone(&self.store_access),
-        )
-        .await?;
+        let user = User::new(username, password, permission)
+            .map_err(|_| KvError::Internal("Error creating user".to_string()))?;
+        self.auth_store.lock().await.add_user(user)?;
 
         Ok("OK".to_string())
     }
 
-    pub async fn delete_user(&mut self, username: String) -> Result<String, String> {
-        let user = User::from_store(&username, Arc::clone(&self.store_access)).await?;
-
-        let user_keys = user.get_user_keys();
+    pub async fn delete_user(&mut self, username: String) -> Result<String, KvError> {
+        self.auth_store.lock().await.remove_user(&username)?;
 
-        let mut store = self.store_access.lock().await;
-
-        store.del(user_keys.user_store_key)
+        Ok("OK".to_string())
     }
 
     pub async fn login_user(
@@ -102,53 +85,131 @@ impl AuthManager {
         username: String,
         password: String,
         mut session: Session,
-    ) -> Result<Session, String> {
+    ) -> Result<Session, KvError> {
         Self::validate_password(&password)?;
 
-        let user = match User::from_store(&username, Arc::clone(&self.store_access)).await {
-            Ok(user) => user,
-            Err(_) => return Err("Username or password is incorrect".to_string()),
+        let user = match self.get_user(username.clone()).await {
+            Some(user) => user,
+            None => return Err(KvError::AuthFailed),
         };
 
-        if user.verify_password(&password).map_err(|e| e.to_string())? {
-            Ok(session.set_authenticated(&username))
-        } else {
-            Err("Username or password is incorrect".to_string())
+        if !user
+            .verify_password(&password)
+            .map_err(|e| KvError::Internal(e.to_string()))?
+        {
+            return Err(KvError::AuthFailed);
+        }
+
+        // The session can only be used to change the password until it is.
+        let expired = self
+            .password_max_age
+            .is_some_and(|max_age| user.password_expired(max_age.as_millis() as u64, now_millis()));
+        Ok(session
+            .set_authenticated(&username)
+            .set_must_change_password(user.must_change_password || expired))
+    }
+
+    pub async fn change_password(
+        &mut self,
+        username: String,
+        old_password: String,
+        new_password: String,
+    ) -> Result<String, KvError> {
+        Self::validate_password(&new_password)?;
+
+        let mut auth_store = self.auth_store.lock().await;
+        let user = auth_store.get_user(&username).ok_or(KvError::AuthFailed)?;
+
+        if !user
+            .verify_password(&old_password)
+            .map_err(|e| KvError::Internal(e.to_string()))?
+        {
+            return Err(KvError::AuthFailed);
         }
+        if old_password == new_password {
+            return Err(KvError::InvalidPassword(
+                "New password must differ from the old one!".to_string(),
+            ));
+        }
+
+        let user = user
+            .with_password(&new_password, false)
+            .map_err(|_| KvError::Internal("Error changing password".to_string()))?;
+        auth_store.update_user(user)?;
+
+        Ok("OK".to_string())
+    }
+
+    pub async fn set_password(
+        &mut self,
+        username: String,
+        password: String,
+        must_change_password: bool,
+    ) -> Result<String, KvError> {
+        Self::validate_password(&password)?;
+
+        let mut auth_store = self.auth_store.lock().await;
+        let user = auth_store
+            .get_user(&username)
+            .ok_or_else(|| KvError::NotFound("User not found".to_string()))?
+            .with_password(&password, must_change_password)
+            .map_err(|_| KvError::Internal("Error changing password".to_string()))?;
+        auth_store.update_user(user)?;
+
+        Ok("OK".to_string())
     }
 
     pub async fn has_user(&self, username: String) -> bool {
-        User::from_store(&username, Arc::clone(&self.store_access))
-            .await
-            .is_ok()
+        self.auth_store.lock().await.has_user(&username)
     }
 
-    pub async fn check_permission(&self, username: String, permission: Permissions) -> bool {
-        let user = match User::from_store(&username, Arc::clone(&self.store_access)).await {
-            Ok(user) => user,
-            Err(_) => return false,
-        };
+    pub async fn check_permission(
+        &self,
+        username: String,
+        permission: Permissions,
+        keys: &[String],
+    ) -> bool {
+        match self.get_effective_user(username).await {
+            Some(user) => user.has_permission(permission, keys),
+            None => false,
+        }
+    }
 
-        user.permissions & (permission as u8) != 0
+    // Copy of the user holding the permissions and ACL rules of its roles too.
+    pub async fn get_effective_user(&self, username: String) -> Option<User> {
+        let auth_store = self.auth_store.lock().await;
+        let user = auth_store.get_user(&username)?;
+
+        // Effective permissions are the union of the user's and its roles'.
+        Some(
+            user.roles
+                .iter()
+                .filter_map(|role| auth_store.get_role(role))
+                .fold(user.clone(), |user, role| user.with_role(role)),
+        )
     }
 
     pub async fn get_user(&self, username: String) -> Option<User> {
-        match User::from_store(&username, Arc::clone(&self.store_access)).await {
-            Ok(user) => Some(user),
-            Err(_) => None,
-        }
+        self.auth_store.lock().await.get_user(&username).cloned()
     }
 
     pub async fn grant_permissions(
         &mut self,
         username: String,
-        permission: u8,
-    ) -> Result<String, String> {
-        let mut user = User::from_store(&username, Arc::clone(&self.store_access)).await?;
-
-        let user = user.grant_permission(permission);
-
-        user.save(Arc::clone(&self.store_access)).await?;
+        permission: PermissionSet,
+        pattern: Option<&str>,
+    ) -> Result<String, KvError> {
+        let mut auth_store = self.auth_store.lock().await;
+        let mut user = auth_store
+            .get_user(&username)
+            .cloned()
+            .ok_or_else(|| KvError::NotFound("User not found".to_string()))?;
+
+        let user = match pattern {
+            Some(pattern) => user.grant_scoped_permission(permission, pattern),
+            None => user.grant_permission(permission),
+        };
+        auth_store.update_user(user)?;
 
         Ok("OK".to_string())
     }
@@ -156,14 +217,81 @@ impl AuthManager {
     pub async fn revoke_permission(
         &mut self,
         username: String,
-        permission: u8,
-    ) -> Result<String, String> {
-        let mut user = User::from_store(&username, Arc::clone(&self.store_access)).await?;
+        permission: PermissionSet,
+        pattern: Option<&str>,
+    ) -> Result<String, KvError> {
+        let mut auth_store = self.auth_store.lock().await;
+        let mut user = auth_store
+            .get_user(&username)
+            .cloned()
+            .ok_or_else(|| KvError::NotFound("User not found".to_string()))?;
+
+        let user = match pattern {
+            Some(pattern) => user.revoke_scoped_permission(permission, pattern),
+            None => user.revoke_permission(permission),
+        };
+        auth_store.update_user(user)?;
+
+        Ok("OK".to_string())
+    }
+
+    pub async fn get_role(&self, name: String) -> Option<Role> {
+        self.auth_store.lock().await.get_role(&name).cloned()
+    }
+
+    pub async fn create_role(
+        &mut self,
+        name: String,
+        permissions: PermissionSet,
+        pattern: Option<&str>,
+    ) -> Result<String, KvError> {
+        let role = Role::new(name, permissions, pattern);
+        self.auth_store.lock().await.add_role(role)?;
 
-        let user = user.revoke_permission(permission);
+        Ok("OK".to_string())
+    }
+
+    pub async fn delete_role(&mut self, name: String) -> Result<String, KvError> {
+        self.auth_store.lock().await.remove_role(&name)?;
+
+        Ok("OK".to_string())
+    }
+
+    pub async fn assign_role(&mut self, username: String, role: String) -> Result<String, KvError> {
+        let mut auth_store = self.auth_store.lock().await;
+        if auth_store.get_role(&role).is_none() {
+            return Err(KvError::NotFound("Role not found".to_string()));
+        }
+        let user = auth_store
+            .get_user(&username)
+            .ok_or_else(|| KvError::NotFound("User not found".to_string()))?
+            .assign_role(&role);
+        auth_store.update_user(user)?;
 
-        user.save(Arc::clone(&self.store_access)).await?;
+        Ok("OK".to_string())
+    }
+
+    pub async fn unassign_role(
+        &mut self,
+        username: String,
+        role: String,
+    ) -> Result<String, KvError> {
+        let mut auth_store = self.auth_store.lock().await;
+        let user = auth_store
+            .get_user(&username)
+            .ok_or_else(|| KvError::NotFound("User not found".to_string()))?
+            .unassign_role(&role);
+        auth_store.update_user(user)?;
 
         Ok("OK".to_string())
     }
+
+    pub async fn list_roles(&self) -> Vec<String> {
+        self.auth_store
+            .lock()
+            .await
+            .roles()
+            .map(|role| role.to_string())
+            .collect()
+    }
 }
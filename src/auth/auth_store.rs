@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{Role, User},
+    data::Store,
+    error::KvError,
+};
+
+// Name of the store older versions kept their users in, as
+// `_auth:users:<name>:{username,password,permissions}`.
+const LEGACY_AUTH_STORE: &str = "_auth";
+
+// Users of the server. They are kept apart from the data clients can
+// address and persisted to a file of their own.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct AuthStore {
+    users: BTreeMap<String, User>,
+    #[serde(default)]
+    roles: BTreeMap<String, Role>,
+}
+
+impl AuthStore {
+    pub fn new() -> AuthStore {
+        AuthStore::default()
+    }
+
+    pub fn get_user(&self, username: &str) -> Option<&User> {
+        self.users.get(username)
+    }
+
+    pub fn has_user(&self, username: &str) -> bool {
+        self.users.contains_key(username)
+    }
+
+    pub fn add_user(&mut self, user: User) -> Result<(), KvError> {
+        if self.has_user(&user.username) {
+            return Err(KvError::AlreadyExists(format!(
+                "User already exists: {}",
+                user.username
+            )));
+        }
+        self.users.insert(user.username.clone(), user);
+        Ok(())
+    }
+
+    pub fn update_user(&mut self, user: User) -> Result<(), KvError> {
+        match self.users.get_mut(&user.username) {
+            Some(current) => {
+                *current = user;
+                Ok(())
+            }
+            None => Err(KvError::NotFound("User not found".to_string())),
+        }
+    }
+
+    pub fn remove_user(&mut self, username: &str) -> Result<User, KvError> {
+        self.users
+            .remove(username)
+            .ok_or_else(|| KvError::NotFound("User not found".to_string()))
+    }
+
+    pub fn get_role(&self, name: &str) -> Option<&Role> {
+        self.roles.get(name)
+    }
+
+    pub fn roles(&self) -> impl Iterator<Item = &Role> {
+        self.roles.values()
+    }
+
+    pub fn add_role(&mut self, role: Role) -> Result<(), KvError> {
+        if self.roles.contains_key(&role.name) {
+            return Err(KvError::AlreadyExists(format!(
+                "Role already exists: {}",
+                role.name
+            )));
+        }
+        self.roles.insert(role.name.clone(), role);
+        Ok(())
+    }
+
+    // Deleting a role also takes it away from the users it was assigned to.
+    pub fn remove_role(&mut self, name: &str) -> Result<Role, KvError> {
+        let role = self
+            .roles
+            .remove(name)
+            .ok_or_else(|| KvError::NotFound("Role not found".to_string()))?;
+        for user in self.users.values_mut() {
+            user.roles.retain(|role| role != name);
+        }
+        Ok(role)
+    }
+
+    // Moves the users of a snapshot written by an older version out of the
+    // data store. Users that already exist here are kept as they are.
+    pub fn migrate_legacy_users(&mut self, data: &mut Store) -> Result<(), KvError> {
+        let legacy = match data.stores.remove(LEGACY_AUTH_STORE) {
+            Some(legacy) => legacy,
+            None => return Ok(()),
+        };
+
+        let users = legacy.stores.get("users").map(|users| &users.stores);
+        for (username, user) in users.into_iter().flatten() {
+            let field = |name: &str| {
+                user.data
+                    .get(name)
+                    .map(|value| value.value().to_string())
+                    .ok_or_else(|| {
+                        KvError::Persistence(format!("Invalid legacy user: {}", username))
+                    })
+            };
+
+            let permissions = field("permissions")?
+                .parse()
+                .map_err(|_| KvError::Persistence(format!("Invalid legacy user: {}", username)))?;
+            let user = User {
+                username: username.clone(),
+                password: field("password")?,
+                permissions,
+                acl: Vec::new(),
+                roles: Vec::new(),
+                password_changed_at: None,
+                must_change_password: false,
+            };
+            self.users.entry(username.clone()).or_insert(user);
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
+mod acl;
 mod auth_manager;
+mod auth_store;
 mod permission;
+mod role;
 mod user;
 
+pub use acl::AclRule;
 pub use auth_manager::*;
+pub use auth_store::AuthStore;
 pub use permission::*;
+pub use role::Role;
 pub use user::*;
 
 #[cfg(test)]
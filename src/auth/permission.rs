@@ -1,3 +1,8 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
 // Permissions bit mask:
 // 0b00000001 - SET
 // 0b00000010 - GET
@@ -7,18 +12,41 @@
 // 0b00001000 | 0b00000001 = 0b00001001
 // 0b00001000 | 0b00000010 = 0b00001010
 // 0b00001000 | 0b00000100 = 0b00001100
+//
+// Only these four bits existed in the `u8` masks of older versions. They are
+// still accepted as numbers and converted with `PermissionSet::from_legacy`.
+const LEGACY_BITS: u8 = 0b00001111;
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Permissions {
     NONE = 0,
     SET = 1 << 0,
     GET = 1 << 1,
     DEL = 1 << 2,
     USER_ADMIN = 1 << 3,
+    STORE_CREATE = 1 << 4,
+    STORE_DELETE = 1 << 5,
+    LIST = 1 << 6,
+    SUBSCRIBE = 1 << 7,
+    ADMIN_CONFIG = 1 << 8,
+    BACKUP = 1 << 9,
 }
 
 impl Permissions {
+    pub const ALL: &'static [Permissions] = &[
+        Permissions::SET,
+        Permissions::GET,
+        Permissions::DEL,
+        Permissions::USER_ADMIN,
+        Permissions::STORE_CREATE,
+        Permissions::STORE_DELETE,
+        Permissions::LIST,
+        Permissions::SUBSCRIBE,
+        Permissions::ADMIN_CONFIG,
+        Permissions::BACKUP,
+    ];
+
     pub fn from_u8(value: u8) -> Vec<Permissions> {
         let mut permissions = Vec::new();
 
@@ -40,4 +68,216 @@ impl Permissions {
 
         permissions
     }
+
+    // Store creation, listing and deletion used to be covered by SET, GET
+    // and DEL.
+    fn legacy_implied(self) -> &'static [Permissions] {
+        match self {
+            Permissions::SET => &[Permissions::SET, Permissions::STORE_CREATE],
+            Permissions::GET => &[Permissions::GET, Permissions::LIST],
+            Permissions::DEL => &[Permissions::DEL, Permissions::STORE_DELETE],
+            Permissions::USER_ADMIN => &[Permissions::USER_ADMIN],
+            _ => &[],
+        }
+    }
+
+    // Permissions that apply to keys and can therefore be scoped by ACL
+    // rules.
+    pub fn is_key_permission(self) -> bool {
+        !matches!(
+            self,
+            Permissions::USER_ADMIN | Permissions::ADMIN_CONFIG | Permissions::BACKUP
+        )
+    }
+
+    fn bit(self) -> u64 {
+        self as u64
+    }
+}
+
+impl Display for Permissions {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Permissions::NONE => write!(f, "NONE"),
+            Permissions::SET => write!(f, "SET"),
+            Permissions::GET => write!(f, "GET"),
+            Permissions::DEL => write!(f, "DEL"),
+            Permissions::USER_ADMIN => write!(f, "USER_ADMIN"),
+            Permissions::STORE_CREATE => write!(f, "STORE_CREATE"),
+            Permissions::STORE_DELETE => write!(f, "STORE_DELETE"),
+            Permissions::LIST => write!(f, "LIST"),
+            Permissions::SUBSCRIBE => write!(f, "SUBSCRIBE"),
+            Permissions::ADMIN_CONFIG => write!(f, "ADMIN_CONFIG"),
+            Permissions::BACKUP => write!(f, "BACKUP"),
+        }
+    }
+}
+
+impl FromStr for Permissions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NONE" => Ok(Permissions::NONE),
+            "SET" => Ok(Permissions::SET),
+            "GET" => Ok(Permissions::GET),
+            "DEL" => Ok(Permissions::DEL),
+            "USER_ADMIN" => Ok(Permissions::USER_ADMIN),
+            "STORE_CREATE" => Ok(Permissions::STORE_CREATE),
+            "STORE_DELETE" => Ok(Permissions::STORE_DELETE),
+            "LIST" => Ok(Permissions::LIST),
+            "SUBSCRIBE" => Ok(Permissions::SUBSCRIBE),
+            "ADMIN_CONFIG" => Ok(Permissions::ADMIN_CONFIG),
+            "BACKUP" => Ok(Permissions::BACKUP),
+            _ => Err("Invalid permission".to_string()),
+        }
+    }
+}
+
+// Set of permissions held by a user, a role or an ACL rule. It is written
+// as a list of names, so adding permissions does not change the meaning of
+// what is already persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PermissionSet {
+    bits: u64,
+}
+
+impl PermissionSet {
+    pub fn new() -> PermissionSet {
+        PermissionSet::default()
+    }
+
+    pub fn all() -> PermissionSet {
+        Permissions::ALL.iter().copied().collect()
+    }
+
+    // Converts a `u8` mask written by an older version.
+    pub fn from_legacy(value: u8) -> PermissionSet {
+        // The admin user used to be created with every bit set.
+        if value == u8::MAX {
+            return PermissionSet::all();
+        }
+        Permissions::from_u8(value)
+            .into_iter()
+            .flat_map(|permission| permission.legacy_implied().iter().copied())
+            .collect()
+    }
+
+    // Like `from_legacy`, refusing bits no older version had.
+    pub fn parse_legacy(value: u8) -> Result<PermissionSet, String> {
+        if value != u8::MAX && value & !LEGACY_BITS != 0 {
+            return Err(format!("Invalid permission mask: {}", value));
+        }
+        Ok(PermissionSet::from_legacy(value))
+    }
+
+    pub fn contains(&self, permission: Permissions) -> bool {
+        self.bits & permission.bit() != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn union(self, other: PermissionSet) -> PermissionSet {
+        PermissionSet {
+            bits: self.bits | other.bits,
+        }
+    }
+
+    pub fn difference(self, other: PermissionSet) -> PermissionSet {
+        PermissionSet {
+            bits: self.bits & !other.bits,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Permissions> + '_ {
+        Permissions::ALL
+            .iter()
+            .copied()
+            .filter(|permission| self.contains(*permission))
+    }
+}
+
+impl From<Permissions> for PermissionSet {
+    fn from(permission: Permissions) -> PermissionSet {
+        PermissionSet {
+            bits: permission.bit(),
+        }
+    }
+}
+
+impl FromIterator<Permissions> for PermissionSet {
+    fn from_iter<I: IntoIterator<Item = Permissions>>(iter: I) -> PermissionSet {
+        iter.into_iter()
+            .fold(PermissionSet::new(), |set, permission| {
+                set.union(permission.into())
+            })
+    }
+}
+
+impl Display for PermissionSet {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "{}", Permissions::NONE);
+        }
+        let names = self
+            .iter()
+            .map(|permission| permission.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}", names.join(","))
+    }
+}
+
+// Either a legacy numeric mask or names separated by commas or spaces, but
+// not both.
+impl FromStr for PermissionSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let names = s
+            .split([',', ' '])
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<&str>>();
+
+        if let [value] = names.as_slice() {
+            if let Ok(value) = value.parse::<u8>() {
+                return PermissionSet::parse_legacy(value);
+            }
+        }
+        names
+            .iter()
+            .map(|name| {
+                Permissions::from_str(name).map_err(|_| format!("Invalid permission: {}", name))
+            })
+            .collect()
+    }
+}
+
+impl Serialize for PermissionSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(|permission| permission.to_string()))
+    }
+}
+
+impl<'de> Deserialize<'de> for PermissionSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StoredPermissions {
+            Legacy(u8),
+            Named(Vec<String>),
+        }
+
+        match StoredPermissions::deserialize(deserializer)? {
+            StoredPermissions::Legacy(value) => {
+                PermissionSet::parse_legacy(value).map_err(de::Error::custom)
+            }
+            StoredPermissions::Named(names) => names
+                .iter()
+                .map(|name| Permissions::from_str(name))
+                .collect::<Result<PermissionSet, String>>()
+                .map_err(de::Error::custom),
+        }
+    }
 }
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{AclRule, PermissionSet};
+
+// Named set of permissions that can be assigned to many users at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Role {
+    pub name: String,
+    pub permissions: PermissionSet,
+    #[serde(default)]
+    pub acl: Vec<AclRule>,
+}
+
+impl Role {
+    pub fn new(name: String, permissions: PermissionSet, pattern: Option<&str>) -> Role {
+        // Permissions given together with a pattern only apply to its keys.
+        match pattern {
+            Some(pattern) if !permissions.is_empty() => Role {
+                name,
+                permissions: PermissionSet::new(),
+                acl: vec![AclRule::new(pattern.to_string(), permissions)],
+            },
+            _ => Role {
+                name,
+                permissions,
+                acl: Vec::new(),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Role: {} Permissions: {}", self.name, self.permissions)?;
+        if !self.acl.is_empty() {
+            let rules = self
+                .acl
+                .iter()
+                .map(|rule| rule.to_string())
+                .collect::<Vec<String>>();
+            write!(f, " ACL: {}", rules.join(" "))?;
+        }
+        Ok(())
+    }
+}
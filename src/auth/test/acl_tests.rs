@@ -0,0 +1,76 @@
+use crate::auth::{AclRule, PermissionSet, Permissions, User};
+
+fn permissions(names: &str) -> PermissionSet {
+    names.parse().unwrap()
+}
+
+#[test]
+fn test_acl_rule_matches() {
+    let rule = AclRule::new("orders:*".to_string(), permissions("GET"));
+    assert!(rule.matches("orders"));
+    assert!(rule.matches("orders:1"));
+    assert!(rule.matches("orders:eu:1"));
+    assert!(!rule.matches("orders_archive:1"));
+    assert!(!rule.matches("customers:1"));
+
+    let rule = AclRule::new("orders:1".to_string(), permissions("GET"));
+    assert!(rule.matches("orders:1"));
+    assert!(!rule.matches("orders:10"));
+
+    assert!(AclRule::new("*".to_string(), permissions("GET")).matches("anything:at:all"));
+}
+
+#[test]
+fn test_acl_rule_is_valid_pattern() {
+    assert!(AclRule::is_valid_pattern("*"));
+    assert!(AclRule::is_valid_pattern("orders:*"));
+    assert!(AclRule::is_valid_pattern("orders:eu:*"));
+    assert!(AclRule::is_valid_pattern("orders:1"));
+    assert!(!AclRule::is_valid_pattern(""));
+    assert!(!AclRule::is_valid_pattern(":*"));
+    assert!(!AclRule::is_valid_pattern("orders*"));
+    assert!(!AclRule::is_valid_pattern("*:orders"));
+}
+
+#[test]
+fn test_user_has_scoped_permission() {
+    let user = User::new(
+        "alice".to_string(),
+        "Password4".to_string(),
+        PermissionSet::new(),
+    )
+    .unwrap()
+    .grant_scoped_permission(permissions("SET,GET"), "orders:*");
+
+    let keys = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+
+    assert!(user.has_permission(Permissions::GET, &keys(&["orders:1"])));
+    assert!(user.has_permission(Permissions::SET, &keys(&["orders:eu:1"])));
+    assert!(!user.has_permission(Permissions::DEL, &keys(&["orders:1"])));
+    assert!(!user.has_permission(Permissions::GET, &keys(&["orders:1", "customers:1"])));
+    assert!(!user.has_permission(Permissions::GET, &[]));
+
+    // Global permissions still cover every key.
+    let user = user.update_permissions(permissions("GET"));
+    assert!(user.has_permission(Permissions::GET, &keys(&["customers:1"])));
+
+    let user = user.revoke_scoped_permission(permissions("SET,GET"), "orders:*");
+    assert!(user.acl.is_empty());
+}
+
+#[test]
+fn test_user_to_string_with_acl() {
+    let user = User::new(
+        "alice".to_string(),
+        "Password4".to_string(),
+        PermissionSet::new(),
+    )
+    .unwrap()
+    .grant_scoped_permission(permissions("SET,GET"), "orders:*")
+    .grant_scoped_permission(permissions("GET"), "customers:*");
+
+    assert_eq!(
+        user.to_string(),
+        "User: alice Permissions: NONE ACL: orders:*=SET,GET customers:*=GET"
+    );
+}
@@ -1,4 +1,4 @@
-use crate::auth::{Permissions, User};
+use crate::auth::{PermissionSet, Permissions, User};
 
 #[test]
 fn test_permission_from_u8() {
@@ -70,7 +70,132 @@ fn test_permission_from_u8() {
 
 #[test]
 fn test_user_to_string() {
-    let user = User::new("user".to_string(), "password".to_string(), 0).unwrap();
+    let user = User::new(
+        "user".to_string(),
+        "password".to_string(),
+        PermissionSet::new(),
+    )
+    .unwrap();
 
-    assert_eq!(user.to_string(), "User: user Permissions: 0");
+    assert_eq!(user.to_string(), "User: user Permissions: NONE");
+
+    let user = user.update_permissions("GET,LIST".parse().unwrap());
+
+    assert_eq!(user.to_string(), "User: user Permissions: GET,LIST");
+}
+
+#[test]
+fn test_permission_set_from_legacy() {
+    assert!(PermissionSet::from_legacy(0).is_empty());
+    assert_eq!(
+        PermissionSet::from_legacy(1).to_string(),
+        "SET,STORE_CREATE"
+    );
+    assert_eq!(
+        PermissionSet::from_legacy(15).to_string(),
+        "SET,GET,DEL,USER_ADMIN,STORE_CREATE,STORE_DELETE,LIST"
+    );
+    assert_eq!(PermissionSet::from_legacy(255), PermissionSet::all());
+}
+
+#[test]
+fn test_permission_set_from_str() {
+    let permissions: PermissionSet = "BACKUP,GET SUBSCRIBE".parse().unwrap();
+    assert!(permissions.contains(Permissions::GET));
+    assert!(permissions.contains(Permissions::BACKUP));
+    assert!(!permissions.contains(Permissions::LIST));
+    assert_eq!(permissions.to_string(), "GET,SUBSCRIBE,BACKUP");
+
+    assert_eq!("NONE".parse::<PermissionSet>(), Ok(PermissionSet::new()));
+    assert_eq!(
+        "2".parse::<PermissionSet>().unwrap().to_string(),
+        "GET,LIST"
+    );
+    assert!("GET,WRITE".parse::<PermissionSet>().is_err());
+
+    assert_eq!("".parse::<PermissionSet>(), Ok(PermissionSet::new()));
+    assert_eq!(
+        "LIST STORE_CREATE"
+            .parse::<PermissionSet>()
+            .unwrap()
+            .to_string(),
+        "STORE_CREATE,LIST"
+    );
+    assert_eq!(
+        "SET GET DEL USER_ADMIN INVALID".parse::<PermissionSet>(),
+        Err("Invalid permission: INVALID".to_string())
+    );
+}
+
+#[test]
+fn test_permission_set_from_str_legacy_mask() {
+    assert_eq!("255".parse::<PermissionSet>(), Ok(PermissionSet::all()));
+    assert_eq!(
+        "15".parse::<PermissionSet>(),
+        Ok(PermissionSet::from_legacy(15))
+    );
+
+    // Bits that no older version had.
+    assert_eq!(
+        "16".parse::<PermissionSet>(),
+        Err("Invalid permission mask: 16".to_string())
+    );
+    assert!("254".parse::<PermissionSet>().is_err());
+    assert!(serde_json::from_str::<PermissionSet>("16").is_err());
+
+    // Masks can not be mixed with names.
+    assert_eq!(
+        "SET 3".parse::<PermissionSet>(),
+        Err("Invalid permission: 3".to_string())
+    );
+    assert!("3,GET".parse::<PermissionSet>().is_err());
+    assert!("1 2".parse::<PermissionSet>().is_err());
+}
+
+#[test]
+fn test_permission_set_serde() {
+    let permissions: PermissionSet = "SET,ADMIN_CONFIG".parse().unwrap();
+    let json = serde_json::to_string(&permissions).unwrap();
+    assert_eq!(json, r#"["SET","ADMIN_CONFIG"]"#);
+    assert_eq!(
+        serde_json::from_str::<PermissionSet>(&json).unwrap(),
+        permissions
+    );
+
+    // Users persisted by older versions have a numeric mask.
+    assert_eq!(
+        serde_json::from_str::<PermissionSet>("3")
+            .unwrap()
+            .to_string(),
+        "SET,GET,STORE_CREATE,LIST"
+    );
+    assert!(serde_json::from_str::<PermissionSet>(r#"["WRITE"]"#).is_err());
+}
+
+#[test]
+fn test_user_with_password() {
+    let user = User::new(
+        "user".to_string(),
+        "Password4".to_string(),
+        PermissionSet::new(),
+    )
+    .unwrap();
+    let changed_at = user.password_changed_at.unwrap();
+
+    assert!(!user.password_expired(1000, changed_at + 999));
+    assert!(user.password_expired(1000, changed_at + 1000));
+
+    let user = user.with_password("Password5", true).unwrap();
+
+    assert!(user.verify_password("Password5").unwrap());
+    assert!(!user.verify_password("Password4").unwrap());
+    assert!(user.must_change_password);
+    assert!(user.password_changed_at.unwrap() >= changed_at);
+
+    // Users migrated from older versions have no known change time.
+    let user = User {
+        password_changed_at: None,
+        ..user
+    };
+    assert!(!user.password_expired(0, changed_at));
 }
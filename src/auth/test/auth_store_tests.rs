@@ -0,0 +1,143 @@
+use crate::{
+    auth::{AuthStore, PermissionSet, Permissions, Role, User},
+    data::{DataTypes, Key, Store, StoreManager},
+    error::KvError,
+};
+
+fn legacy_store(username: &str, password: &str, permissions: u8) -> Store {
+    let mut store = Store::new(".".to_string());
+    for name in [
+        "_auth".to_string(),
+        "_auth:users".to_string(),
+        format!("_auth:users:{}", username),
+    ] {
+        store.set_store(Key::new(name)).unwrap();
+    }
+    for (field, value) in [
+        ("username", username.to_string()),
+        ("password", password.to_string()),
+        ("permissions", permissions.to_string()),
+    ] {
+        store
+            .set(
+                Key::new(format!("_auth:users:{}:{}", username, field)),
+                value,
+                DataTypes::STRING,
+            )
+            .unwrap();
+    }
+    store
+}
+
+#[test]
+fn test_auth_store_add_and_remove_user() {
+    let mut auth = AuthStore::new();
+    let user = User::new(
+        "alice".to_string(),
+        "Password4".to_string(),
+        PermissionSet::new(),
+    )
+    .unwrap();
+
+    auth.add_user(user.clone()).unwrap();
+    assert!(auth.has_user("alice"));
+    assert!(matches!(
+        auth.add_user(user),
+        Err(KvError::AlreadyExists(_))
+    ));
+
+    auth.remove_user("alice").unwrap();
+    assert!(!auth.has_user("alice"));
+    assert!(matches!(
+        auth.remove_user("alice"),
+        Err(KvError::NotFound(_))
+    ));
+}
+
+#[test]
+fn test_auth_store_migrate_legacy_users() {
+    let hash = User::new(
+        "bob".to_string(),
+        "Password4".to_string(),
+        PermissionSet::new(),
+    )
+    .unwrap()
+    .password;
+    let mut store = legacy_store("bob", &hash, 6);
+    store
+        .set(
+            Key::new("orders".to_string()),
+            "1".to_string(),
+            DataTypes::STRING,
+        )
+        .unwrap();
+
+    let mut auth = AuthStore::new();
+    auth.migrate_legacy_users(&mut store).unwrap();
+
+    let user = auth.get_user("bob").unwrap();
+    // Numeric permissions of older versions keep covering store commands.
+    assert_eq!(user.permissions.to_string(), "GET,DEL,STORE_DELETE,LIST");
+    assert!(user.verify_password("Password4").unwrap());
+
+    // The legacy store is gone while the rest of the data is untouched.
+    assert!(!store.stores.contains_key("_auth"));
+    assert_eq!(
+        store.get(Key::new("orders".to_string())),
+        Ok("1".to_string())
+    );
+}
+
+#[test]
+fn test_auth_store_migrate_invalid_legacy_user() {
+    let mut store = legacy_store("bob", "hash", 6);
+    store
+        .set(
+            Key::new("_auth:users:bob:permissions".to_string()),
+            "all".to_string(),
+            DataTypes::STRING,
+        )
+        .unwrap();
+
+    let mut auth = AuthStore::new();
+    assert!(matches!(
+        auth.migrate_legacy_users(&mut store),
+        Err(KvError::Persistence(_))
+    ));
+}
+
+#[test]
+fn test_auth_store_remove_role_unassigns_it() {
+    let mut auth = AuthStore::new();
+    let user = User::new(
+        "alice".to_string(),
+        "Password4".to_string(),
+        PermissionSet::new(),
+    )
+    .unwrap()
+    .assign_role("reader");
+    auth.add_user(user).unwrap();
+    auth.add_role(Role::new(
+        "reader".to_string(),
+        Permissions::GET.into(),
+        None,
+    ))
+    .unwrap();
+
+    assert!(matches!(
+        auth.add_role(Role::new(
+            "reader".to_string(),
+            Permissions::SET.into(),
+            None
+        )),
+        Err(KvError::AlreadyExists(_))
+    ));
+
+    auth.remove_role("reader").unwrap();
+    assert!(auth.get_role("reader").is_none());
+    assert!(auth.get_user("alice").unwrap().roles.is_empty());
+    assert!(matches!(
+        auth.remove_role("reader"),
+        Err(KvError::NotFound(_))
+    ));
+}
@@ -1 +1,3 @@
+mod acl_tests;
 mod auth_manager_tests;
+mod auth_store_tests;
@@ -1,105 +1,75 @@
-use std::sync::Arc;
-
 use argon2::{
     password_hash::{Error, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
-use tokio::sync::Mutex;
+use serde::{Deserialize, Serialize};
 
-use crate::data::{DataTypes, Key, Store, StoreManager};
+use crate::{
+    auth::{AclRule, PermissionSet, Permissions, Role},
+    data::now_millis,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub username: String,
     pub password: String,
-    pub permissions: u8,
-}
-
-pub struct UserKeys {
-    pub user_store_key: Key,
-    pub username_key: Key,
-    pub password_key: Key,
-    pub permissions_key: Key,
+    pub permissions: PermissionSet,
+    #[serde(default)]
+    pub acl: Vec<AclRule>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    // Milliseconds since the epoch. Unknown for users created by older
+    // versions, whose passwords therefore never expire.
+    #[serde(default)]
+    pub password_changed_at: Option<u64>,
+    #[serde(default)]
+    pub must_change_password: bool,
 }
 
 impl User {
-    pub fn new(username: String, password: String, permissions: u8) -> Result<User, Error> {
+    pub fn new(
+        username: String,
+        password: String,
+        permissions: PermissionSet,
+    ) -> Result<User, Error> {
         let hash = User::hash_password(&password)?;
         Ok(User {
             username,
             password: hash,
             permissions,
+            acl: Vec::new(),
+            roles: Vec::new(),
+            password_changed_at: Some(now_millis()),
+            must_change_password: false,
         })
     }
 
-    pub async fn from_store(username: &str, store: Arc<Mutex<Store>>) -> Result<User, String> {
-        let user_key = Key::new(format!("_auth:users:{}", username));
-
-        let mut store = store.lock().await;
-
-        let user = store.get_store(user_key)?;
-
-        let username = user.get(Key::new("username".to_string()))?;
-        let password = user.get(Key::new("password".to_string()))?;
-        let permissions = user.get(Key::new("permissions".to_string()))?;
-
-        Ok(User {
-            username,
-            password,
-            permissions: permissions.parse::<u8>().unwrap(),
-        })
-    }
-
-    pub async fn create(
-        username: &str,
-        password: &str,
-        permissions: u8,
-        store: Arc<Mutex<Store>>,
-    ) -> Result<User, String> {
-        let user = match User::new(username.to_string(), password.to_string(), permissions) {
-            Ok(user) => user,
-            Err(_) => return Err("Error creating user".to_string()),
-        };
-
-        {
-            let mut store = store.lock().await;
-
-            let user_keys = user.get_user_keys();
-
-            store.set_store(user_keys.user_store_key.clone())?;
+    pub fn to_string(&self) -> String {
+        let mut result = format!("User: {} Permissions: {}", self.username, self.permissions);
+        if !self.acl.is_empty() {
+            let rules = self
+                .acl
+                .iter()
+                .map(|rule| rule.to_string())
+                .collect::<Vec<String>>();
+            result.push_str(&format!(" ACL: {}", rules.join(" ")));
         }
-
-        user.save(store).await?;
-
-        Ok(user)
-    }
-
-    pub async fn save(&self, store: Arc<Mutex<Store>>) -> Result<(), String> {
-        let user_keys = self.get_user_keys();
-
-        let mut store = store.lock().await;
-
-        store.set(
-            user_keys.username_key,
-            self.username.clone(),
-            DataTypes::STRING,
-        )?;
-        store.set(
-            user_keys.password_key,
-            self.password.clone(),
-            DataTypes::STRING,
-        )?;
-        store.set(
-            user_keys.permissions_key,
-            self.permissions.to_string(),
-            DataTypes::STRING,
-        )?;
-
-        Ok(())
+        if !self.roles.is_empty() {
+            result.push_str(&format!(" Roles: {}", self.roles.join(",")));
+        }
+        result
     }
 
-    pub fn to_string(&self) -> String {
-        format!("User: {} Permissions: {}", self.username, self.permissions)
+    // Global permissions apply to every key. Otherwise each of the keys has
+    // to be covered by an ACL rule granting the permission.
+    pub fn has_permission(&self, permission: Permissions, keys: &[String]) -> bool {
+        if self.permissions.contains(permission) {
+            return true;
+        }
+        !keys.is_empty()
+            && keys
+                .iter()
+                .all(|key| self.acl.iter().any(|rule| rule.allows(permission, key)))
     }
 
     fn hash_password(password: &str) -> Result<String, Error> {
@@ -112,6 +82,24 @@ impl User {
         Ok(hash)
     }
 
+    // Copy of the user with a new password. `must_change_password` makes the
+    // user change it again at the next login, e.g. after an admin reset it.
+    pub fn with_password(&self, password: &str, must_change_password: bool) -> Result<User, Error> {
+        Ok(User {
+            password: User::hash_password(password)?,
+            password_changed_at: Some(now_millis()),
+            must_change_password,
+            ..self.clone()
+        })
+    }
+
+    pub fn password_expired(&self, max_age_millis: u64, now: u64) -> bool {
+        match self.password_changed_at {
+            Some(changed_at) => now.saturating_sub(changed_at) >= max_age_millis,
+            None => false,
+        }
+    }
+
     pub fn verify_password(&self, password: &str) -> Result<bool, Error> {
         let argon2 = Argon2::default();
         let parsed_hash = PasswordHash::new(&self.password)?;
@@ -122,36 +110,82 @@ impl User {
         }
     }
 
-    pub fn grant_permission(&mut self, permission: u8) -> User {
+    pub fn grant_permission(&mut self, permission: PermissionSet) -> User {
         User {
-            username: self.username.clone(),
-            password: self.password.clone(),
-            permissions: self.permissions | permission,
+            permissions: self.permissions.union(permission),
+            ..self.clone()
         }
     }
 
-    pub fn revoke_permission(&mut self, permission: u8) -> User {
+    pub fn revoke_permission(&mut self, permission: PermissionSet) -> User {
         User {
-            username: self.username.clone(),
-            password: self.password.clone(),
-            permissions: self.permissions & !permission,
+            permissions: self.permissions.difference(permission),
+            ..self.clone()
         }
     }
 
-    pub fn update_permissions(&self, permissions: u8) -> User {
+    pub fn update_permissions(&self, permissions: PermissionSet) -> User {
         User {
-            username: self.username.clone(),
-            password: self.password.clone(),
             permissions,
+            ..self.clone()
         }
     }
 
-    pub fn get_user_keys(&self) -> UserKeys {
-        UserKeys {
-            user_store_key: Key::new(format!("_auth:users:{}", self.username)),
-            username_key: Key::new(format!("_auth:users:{}:username", self.username)),
-            password_key: Key::new(format!("_auth:users:{}:password", self.username)),
-            permissions_key: Key::new(format!("_auth:users:{}:permissions", self.username)),
+    // Adds the permissions of a role to the user's own ones.
+    pub fn with_role(&self, role: &Role) -> User {
+        let mut acl = self.acl.clone();
+        acl.extend(role.acl.iter().cloned());
+        User {
+            permissions: self.permissions.union(role.permissions),
+            acl,
+            ..self.clone()
+        }
+    }
+
+    pub fn assign_role(&self, role: &str) -> User {
+        let mut roles = self.roles.clone();
+        if !roles.iter().any(|name| name == role) {
+            roles.push(role.to_string());
+        }
+        User {
+            roles,
+            ..self.clone()
+        }
+    }
+
+    pub fn unassign_role(&self, role: &str) -> User {
+        let mut roles = self.roles.clone();
+        roles.retain(|name| name != role);
+        User {
+            roles,
+            ..self.clone()
+        }
+    }
+
+    pub fn grant_scoped_permission(&self, permission: PermissionSet, pattern: &str) -> User {
+        let mut acl = self.acl.clone();
+        match acl.iter_mut().find(|rule| rule.pattern == pattern) {
+            Some(rule) => rule.permissions = rule.permissions.union(permission),
+            None if !permission.is_empty() => {
+                acl.push(AclRule::new(pattern.to_string(), permission))
+            }
+            None => {}
+        }
+        User {
+            acl,
+            ..self.clone()
+        }
+    }
+
+    pub fn revoke_scoped_permission(&self, permission: PermissionSet, pattern: &str) -> User {
+        let mut acl = self.acl.clone();
+        for rule in acl.iter_mut().filter(|rule| rule.pattern == pattern) {
+            rule.permissions = rule.permissions.difference(permission);
+        }
+        acl.retain(|rule| !rule.permissions.is_empty());
+        User {
+            acl,
+            ..self.clone()
         }
     }
 }
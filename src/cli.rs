@@ -0,0 +1,555 @@
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use clap::Parser;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use tokio::runtime::{Handle, Runtime};
+use tokio::sync::Mutex;
+
+use kvstore::client::Client;
+use kvstore::commands::{tokenize, CommandNames};
+use kvstore::error::KvError;
+
+const HISTORY_FILE: &str = ".kvstore_cli_history";
+
+// Interactive shell for the Key-Value Store
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    // Server address
+    #[clap(short = 'H', long = "host", default_value = "127.0.0.1")]
+    host: String,
+
+    // Server port
+    #[clap(short = 'p', long = "port", default_value_t = 4000)]
+    port: u16,
+
+    // Username to authenticate with
+    #[clap(short = 'u', long = "user")]
+    user: Option<String>,
+
+    // Password for `--user`, prompted for when omitted
+    #[clap(long = "password")]
+    password: Option<String>,
+
+    // Script file with one command per line
+    #[clap(short = 'f', long = "file")]
+    file: Option<String>,
+
+    // Command to run instead of starting the shell
+    command: Vec<String>,
+}
+
+// Commands whose first argument is a key or store path.
+fn takes_key(command: &str) -> bool {
+    matches!(
+        command.to_uppercase().as_str(),
+        "SET"
+            | "GET"
+            | "DEL"
+            | "CREATE_STORE"
+            | "LIST_KEYS"
+            | "EXPIRE"
+            | "TTL"
+            | "PERSIST"
+            | "CAS"
+            | "INCR"
+            | "DECR"
+            | "INCRBY"
+            | "INCRBYFLOAT"
+            | "TOGGLE"
+            | "LPUSH"
+            | "RPUSH"
+            | "LPOP"
+            | "RPOP"
+            | "LRANGE"
+            | "LLEN"
+            | "LINDEX"
+            | "LREM"
+            | "BLPOP"
+            | "SADD"
+            | "SREM"
+            | "SISMEMBER"
+            | "SMEMBERS"
+            | "SCARD"
+            | "SUNION"
+            | "SINTER"
+            | "SDIFF"
+            | "ZADD"
+            | "ZINCRBY"
+            | "ZSCORE"
+            | "ZRANK"
+            | "ZRANGE"
+            | "ZRANGEBYSCORE"
+            | "ZREM"
+            | "JSON.GET"
+            | "JSON.SET"
+            | "JSON.DEL"
+            | "JSON.ARRAPPEND"
+    )
+}
+
+fn complete_command(word: &str) -> Vec<String> {
+    let word = word.to_uppercase();
+    CommandNames::ALL
+        .iter()
+        .map(|command| command.to_string())
+        .filter(|command| command.starts_with(&word))
+        .collect()
+}
+
+// Splits a partially typed key into the store to list, the part of the word
+// to keep and the partial name to complete, e.g. `users:jo` becomes
+// (`users`, `users:`, `jo`).
+fn split_path(word: &str) -> (String, String, String) {
+    match word.rfind(':') {
+        Some(index) => (
+            word[..index].to_string(),
+            word[..=index].to_string(),
+            word[index + 1..].to_string(),
+        ),
+        None => (".".to_string(), String::new(), word.to_string()),
+    }
+}
+
+fn format_reply(command: &str, payload: &str) -> String {
+    match command.to_uppercase().as_str() {
+        "GET" => format!("{:?}", payload),
+        "LIST_KEYS" | "LIST_ROLES" | "LRANGE" | "SMEMBERS" | "SUNION" | "SINTER" | "SDIFF"
+        | "ZRANGE" | "ZRANGEBYSCORE" => {
+            if payload.is_empty() {
+                return "(empty list)".to_string();
+            }
+            payload
+                .lines()
+                .enumerate()
+                .map(|(index, key)| format!("{}) {}", index + 1, key))
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+        _ => payload.to_string(),
+    }
+}
+
+fn format_error(error: &KvError) -> String {
+    format!("(error) {} {}", error.code(), error)
+}
+
+// Reads commands from a script, one per line. Empty lines and lines starting
+// with `#` are skipped and a trailing `;` is optional.
+fn parse_script(script: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut commands = Vec::new();
+
+    for (index, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parts = tokenize(line.trim_end_matches(';'))
+            .map_err(|e| format!("line {}: {}", index + 1, e))?;
+        if !parts.is_empty() {
+            commands.push(parts);
+        }
+    }
+
+    Ok(commands)
+}
+
+async fn run_command(client: &Mutex<Client>, parts: &[String]) -> Result<String, KvError> {
+    let (name, args) = match parts.split_first() {
+        Some(parts) => parts,
+        None => return Err(KvError::BadArgs("No command".to_string())),
+    };
+    let args = args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>();
+
+    // AUTH and PASSWORD go through the client, which keeps the credentials so
+    // it can re-authenticate after a reconnect.
+    let mut client = client.lock().await;
+    let name = name.to_uppercase();
+    let payload = match (name.as_str(), args.as_slice()) {
+        ("AUTH", [username, password]) => client
+            .auth(username, password)
+            .await
+            .map(|_| "OK".to_string()),
+        ("PASSWORD", [old_password, new_password]) => client
+            .change_password(old_password, new_password)
+            .await
+            .map(|_| "OK".to_string()),
+        _ => client.execute(&name, &args).await,
+    }?;
+
+    Ok(format_reply(&name, &payload))
+}
+
+fn run_batch(runtime: &Runtime, client: &Mutex<Client>, commands: Vec<Vec<String>>) -> ExitCode {
+    for parts in commands {
+        match runtime.block_on(run_command(client, &parts)) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("{}", format_error(&e));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+struct CliHelper {
+    client: Arc<Mutex<Client>>,
+    runtime: Handle,
+}
+
+impl CliHelper {
+    fn complete_key(&self, word: &str) -> Vec<String> {
+        let (store, keep, partial) = split_path(word);
+
+        let keys = self
+            .runtime
+            .block_on(async { self.client.lock().await.list_keys(&store).await });
+
+        keys.unwrap_or_default()
+            .into_iter()
+            .filter(|key| key.starts_with(&partial))
+            .map(|key| format!("{}{}", keep, key))
+            .collect()
+    }
+}
+
+impl Completer for CliHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line
+            .rfind(char::is_whitespace)
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let word = &line[start..];
+        let previous = line[..start].split_whitespace().collect::<Vec<&str>>();
+
+        let candidates = match previous.as_slice() {
+            [] => complete_command(word),
+            [command] if takes_key(command) => self.complete_key(word),
+            _ => Vec::new(),
+        };
+
+        Ok((
+            start,
+            candidates
+                .into_iter()
+                .map(|candidate| Pair {
+                    display: candidate.clone(),
+                    replacement: candidate,
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl Hinter for CliHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CliHelper {}
+
+impl Validator for CliHelper {}
+
+impl Helper for CliHelper {}
+
+fn history_path() -> Option<String> {
+    std::env::var("HOME")
+        .ok()
+        .map(|home| format!("{}/{}", home, HISTORY_FILE))
+}
+
+// Passwords never end up in the history.
+fn history_entry(line: &str, parts: &[String]) -> String {
+    let passwords = match parts[0].to_uppercase().as_str() {
+        "PASSWORD" => 1..3,
+        "AUTH" | "SET_PASSWORD" | "CREATE_USER" => 2..3,
+        _ => return line.to_string(),
+    };
+    parts
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !passwords.contains(i))
+        .map(|(_, part)| part.as_str())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn run_repl(runtime: &Runtime, client: Arc<Mutex<Client>>, address: &str) -> ExitCode {
+    let mut editor = match Editor::<CliHelper, DefaultHistory>::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("failed to start shell; err = {:?}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    editor.set_helper(Some(CliHelper {
+        client: Arc::clone(&client),
+        runtime: runtime.handle().clone(),
+    }));
+
+    let history = history_path();
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    let prompt = format!("{}> ", address);
+
+    loop {
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("failed to read line; err = {:?}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+
+        let line = line.trim().trim_end_matches(';');
+        if line.is_empty() {
+            continue;
+        }
+        if line.eq_ignore_ascii_case("quit") || line.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        let mut parts = match tokenize(line) {
+            Ok(parts) => parts,
+            Err(e) => {
+                eprintln!("(error) {}", e);
+                continue;
+            }
+        };
+
+        if parts[0].eq_ignore_ascii_case("AUTH") && parts.len() == 2 {
+            match rpassword::prompt_password("Password: ") {
+                Ok(password) => parts.push(password),
+                Err(e) => {
+                    eprintln!("failed to read password; err = {:?}", e);
+                    continue;
+                }
+            }
+        }
+        let _ = editor.add_history_entry(history_entry(line, &parts));
+
+        match runtime.block_on(run_command(&client, &parts)) {
+            Ok(output) => println!("{}", output),
+            Err(e) => println!("{}", format_error(&e)),
+        }
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let runtime = match Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("failed to start runtime; err = {:?}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let address = format!("{}:{}", args.host, args.port);
+    let client = Arc::new(Mutex::new(Client::new(&address)));
+
+    if let Some(user) = &args.user {
+        let password = match &args.password {
+            Some(password) => password.clone(),
+            None => match rpassword::prompt_password("Password: ") {
+                Ok(password) => password,
+                Err(e) => {
+                    eprintln!("failed to read password; err = {:?}", e);
+                    return ExitCode::FAILURE;
+                }
+            },
+        };
+
+        let auth = vec!["AUTH".to_string(), user.clone(), password];
+        if let Err(e) = runtime.block_on(run_command(&client, &auth)) {
+            eprintln!("{}", format_error(&e));
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if !args.command.is_empty() {
+        return run_batch(&runtime, &client, vec![args.command]);
+    }
+
+    if let Some(file) = &args.file {
+        let commands = match std::fs::read_to_string(file)
+            .map_err(|e| e.to_string())
+            .and_then(|script| parse_script(&script))
+        {
+            Ok(commands) => commands,
+            Err(e) => {
+                eprintln!("failed to read script {}; err = {}", file, e);
+                return ExitCode::FAILURE;
+            }
+        };
+
+        return run_batch(&runtime, &client, commands);
+    }
+
+    run_repl(&runtime, client, &address)
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::task::JoinHandle;
+
+    #[test]
+    fn test_complete_command() {
+        assert_eq!(complete_command("ge"), vec!["GET", "GET_USER"]);
+        assert_eq!(complete_command("LIST"), vec!["LIST_ROLES", "LIST_KEYS"]);
+        assert!(complete_command("UNKNOWN").is_empty());
+        assert_eq!(complete_command("").len(), CommandNames::ALL.len());
+    }
+
+    #[test]
+    fn test_split_path() {
+        assert_eq!(
+            split_path("users:jo"),
+            ("users".to_string(), "users:".to_string(), "jo".to_string())
+        );
+        assert_eq!(
+            split_path("us"),
+            (".".to_string(), "".to_string(), "us".to_string())
+        );
+        assert_eq!(
+            split_path("users:john:"),
+            (
+                "users:john".to_string(),
+                "users:john:".to_string(),
+                "".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_reply() {
+        assert_eq!(format_reply("SET", "OK"), "OK");
+        assert_eq!(
+            format_reply("get", "hello \"world\""),
+            r#""hello \"world\"""#
+        );
+        assert_eq!(format_reply("LIST_KEYS", "a\nb"), "1) a\n2) b");
+        assert_eq!(format_reply("LIST_KEYS", ""), "(empty list)");
+        assert_eq!(
+            format_error(&KvError::key_not_found()),
+            "(error) NOT_FOUND Key not found"
+        );
+    }
+
+    #[test]
+    fn test_history_entry() {
+        let entry = |line: &str| history_entry(line, &tokenize(line).unwrap());
+
+        assert_eq!(entry("GET key"), "GET key");
+        assert_eq!(entry("auth admin Password4"), "auth admin");
+        assert_eq!(entry("PASSWORD Password4 Password5"), "PASSWORD");
+        assert_eq!(
+            entry("SET_PASSWORD bob Password5 MUST_CHANGE"),
+            "SET_PASSWORD bob MUST_CHANGE"
+        );
+        assert_eq!(
+            entry("CREATE_USER bob Password4 GET SET"),
+            "CREATE_USER bob GET SET"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_command_reconnects_after_password() {
+        use kvstore::{
+            config::Config, persistence::Persistence, shutdown::ShutdownHandle,
+            start_server_with_shutdown,
+        };
+
+        async fn start_server(port: u16, file_path: &str) -> (ShutdownHandle, JoinHandle<()>) {
+            let mut config = Config::new();
+            config.add_persistence_config(Persistence::new_json_file(file_path.to_string()));
+            config.add_server_config("127.0.0.1".to_string(), port);
+
+            let shutdown = ShutdownHandle::new();
+            let server_shutdown = shutdown.clone();
+            let handle = tokio::spawn(async move {
+                start_server_with_shutdown(config, server_shutdown)
+                    .await
+                    .unwrap();
+            });
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            (shutdown, handle)
+        }
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let file_path = file.path().to_str().unwrap().to_string();
+        let parts = |line: &str| tokenize(line).unwrap();
+
+        let (shutdown, handle) = start_server(port, &file_path).await;
+
+        let client = Mutex::new(Client::new(&format!("127.0.0.1:{}", port)));
+        for line in [
+            "AUTH admin Password4",
+            "SET key value",
+            "PASSWORD Password4 Password5",
+        ] {
+            run_command(&client, &parts(line)).await.unwrap();
+        }
+
+        shutdown.shutdown();
+        handle.await.unwrap();
+        let (shutdown, handle) = start_server(port, &file_path).await;
+
+        // The reconnect authenticates with the new password.
+        assert_eq!(
+            run_command(&client, &parts("GET key")).await,
+            Ok("\"value\"".to_string())
+        );
+
+        shutdown.shutdown();
+        handle.await.unwrap();
+        std::fs::remove_file(format!("{}.auth", file_path)).unwrap();
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let script = "# setup\nSET key \"hello world\";\n\n  GET key\n";
+
+        assert_eq!(
+            parse_script(script).unwrap(),
+            vec![vec!["SET", "key", "hello world"], vec!["GET", "key"]]
+        );
+        assert!(parse_script("SET key \"unterminated").is_err());
+    }
+}
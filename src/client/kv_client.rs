@@ -0,0 +1,737 @@
+use std::io::{Error, ErrorKind};
+use std::str::FromStr;
+use std::time::Duration;
+
+use bytes::{BufMut, BytesMut};
+use futures::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+use crate::auth::Permissions;
+use crate::data::{Binary, DataTypes, Encoding};
+use crate::error::KvError;
+use crate::handler::{decode_reply, Reply, COMMAND_TERMINATOR};
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+// Writes `;` terminated commands and reads the server's length-prefixed
+// replies.
+struct ClientCodec;
+
+impl Decoder for ClientCodec {
+    type Item = String;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<String>, Error> {
+        decode_reply(src)
+    }
+}
+
+impl Encoder<String> for ClientCodec {
+    type Error = Error;
+
+    fn encode(&mut self, command: String, dst: &mut BytesMut) -> Result<(), Error> {
+        dst.reserve(command.len() + 1);
+        dst.put_slice(command.as_bytes());
+        dst.put_u8(COMMAND_TERMINATOR);
+        Ok(())
+    }
+}
+
+type Connection = Framed<TcpStream, ClientCodec>;
+
+// Quotes an argument when the server's tokenizer would otherwise split it or
+// read it as something else.
+fn quote_arg(arg: &str) -> String {
+    let needs_quotes = arg.is_empty()
+        || arg.starts_with("x\"")
+        || arg
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '\\' || c == ';');
+
+    if !needs_quotes {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\0' => quoted.push_str("\\0"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn format_command(name: &str, args: &[&str]) -> String {
+    let mut command = name.to_string();
+    for arg in args {
+        command.push(' ');
+        command.push_str(&quote_arg(arg));
+    }
+    command
+}
+
+fn connection_error(error: Error) -> KvError {
+    KvError::Connection(error.to_string())
+}
+
+async fn round_trip(connection: &mut Connection, command: String) -> Result<String, KvError> {
+    connection.send(command).await.map_err(connection_error)?;
+
+    match connection.next().await {
+        Some(Ok(reply)) => Reply::from_str(&reply)?.into(),
+        Some(Err(e)) => Err(connection_error(e)),
+        None => Err(KvError::Connection("Connection closed".to_string())),
+    }
+}
+
+// Async client for the native protocol. Lost connections are re-established
+// (and re-authenticated) on the next request. Requests are only retried when
+// the connection failed before the command was written; once it was sent, a
+// lost reply is returned as an error, as the command may have been applied.
+pub struct Client {
+    address: String,
+    connection: Option<Connection>,
+    credentials: Option<(String, String)>,
+    max_retries: u32,
+    retry_delay: Duration,
+}
+
+impl Client {
+    pub fn new(address: &str) -> Client {
+        Client {
+            address: address.to_string(),
+            connection: None,
+            credentials: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+        }
+    }
+
+    pub async fn connect(address: &str) -> Result<Client, KvError> {
+        let mut client = Client::new(address);
+        client.connection().await?;
+        Ok(client)
+    }
+
+    pub fn set_retry_policy(&mut self, max_retries: u32, retry_delay: Duration) {
+        self.max_retries = max_retries;
+        self.retry_delay = retry_delay;
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    async fn connection(&mut self) -> Result<&mut Connection, KvError> {
+        if self.connection.is_none() {
+            let socket = TcpStream::connect(&self.address)
+                .await
+                .map_err(connection_error)?;
+            let mut connection = Framed::new(socket, ClientCodec);
+
+            if let Some((username, password)) = &self.credentials {
+                round_trip(
+                    &mut connection,
+                    format_command("AUTH", &[username, password]),
+                )
+                .await?;
+            }
+
+            self.connection = Some(connection);
+        }
+
+        Ok(self.connection.as_mut().unwrap())
+    }
+
+    // The server may have closed an idle connection, e.g. when it restarted.
+    // That is noticed before sending, while the command can still be retried.
+    fn drop_closed_connection(&mut self) {
+        let closed = match &self.connection {
+            Some(connection) => match connection.get_ref().try_read(&mut [0u8; 1]) {
+                Err(e) => e.kind() != ErrorKind::WouldBlock,
+                // End of stream, or data that nobody asked for.
+                Ok(_) => true,
+            },
+            None => false,
+        };
+        if closed {
+            self.connection = None;
+        }
+    }
+
+    // Errors of this step happened before the command was written, so the
+    // server did not apply it.
+    async fn try_send(&mut self, command: &str) -> Result<(), KvError> {
+        self.drop_closed_connection();
+        let connection = self.connection().await?;
+
+        if let Err(e) = connection.send(command.to_string()).await {
+            self.connection = None;
+            return Err(connection_error(e));
+        }
+        Ok(())
+    }
+
+    async fn read_reply(&mut self) -> Result<String, KvError> {
+        let connection = self
+            .connection
+            .as_mut()
+            .ok_or_else(|| KvError::Connection("Connection closed".to_string()))?;
+        let result = match connection.next().await {
+            Some(Ok(reply)) => Reply::from_str(&reply).and_then(Into::into),
+            Some(Err(e)) => Err(connection_error(e)),
+            None => Err(KvError::Connection("Connection closed".to_string())),
+        };
+
+        if let Err(KvError::Connection(_)) = result {
+            self.connection = None;
+        }
+
+        result
+    }
+
+    // Sends a raw command and returns the payload of its reply.
+    pub async fn execute(&mut self, name: &str, args: &[&str]) -> Result<String, KvError> {
+        let command = format_command(name, args);
+        let mut attempts = 0;
+
+        loop {
+            match self.try_send(&command).await {
+                Err(KvError::Connection(_)) if attempts < self.max_retries => {
+                    attempts += 1;
+                    tokio::time::sleep(self.retry_delay * attempts).await;
+                }
+                Err(e) => return Err(e),
+                Ok(()) => return self.read_reply().await,
+            }
+        }
+    }
+
+    async fn execute_ack(&mut self, name: &str, args: &[&str]) -> Result<(), KvError> {
+        self.execute(name, args).await.map(|_| ())
+    }
+
+    pub async fn auth(&mut self, username: &str, password: &str) -> Result<(), KvError> {
+        self.execute_ack("AUTH", &[username, password]).await?;
+        self.credentials = Some((username.to_string(), password.to_string()));
+        Ok(())
+    }
+
+    pub async fn get(&mut self, key: &str) -> Result<String, KvError> {
+        self.execute("GET", &[key]).await
+    }
+
+    pub async fn set(
+        &mut self,
+        key: &str,
+        value: &str,
+        data_type: DataTypes,
+    ) -> Result<(), KvError> {
+        self.execute_ack("SET", &[key, value, &data_type.to_string()])
+            .await
+    }
+
+    // Stores raw bytes as a BYTES value. They are sent in base64 so they
+    // survive the text protocol.
+    pub async fn set_bytes(&mut self, key: &str, bytes: &[u8]) -> Result<(), KvError> {
+        let value = Binary::new(bytes.to_vec()).encode(Encoding::BASE64);
+        self.execute_ack("SET", &[key, &value, &DataTypes::BYTES.to_string()])
+            .await
+    }
+
+    pub async fn get_bytes(&mut self, key: &str) -> Result<Vec<u8>, KvError> {
+        let value = self.execute("GET", &[key]).await?;
+        Binary::decode(&value, Encoding::BASE64)
+            .map(|binary| binary.as_slice().to_vec())
+            .map_err(KvError::Protocol)
+    }
+
+    // Returns the value together with the version to pass to `cas`.
+    pub async fn get_with_version(&mut self, key: &str) -> Result<(String, u64), KvError> {
+        let reply = self.execute("GET", &[key, "WITHVERSION"]).await?;
+
+        reply
+            .split_once('\n')
+            .and_then(|(version, value)| Some((value.to_string(), version.parse().ok()?)))
+            .ok_or_else(|| KvError::Protocol(format!("Invalid versioned value: {}", reply)))
+    }
+
+    // Replaces the value only if it is still at `expected_version` (0 for a
+    // key that must not exist yet) and returns the new version.
+    pub async fn cas(
+        &mut self,
+        key: &str,
+        expected_version: u64,
+        value: &str,
+        data_type: DataTypes,
+    ) -> Result<u64, KvError> {
+        let version = self
+            .execute(
+                "CAS",
+                &[
+                    key,
+                    &expected_version.to_string(),
+                    value,
+                    &data_type.to_string(),
+                ],
+            )
+            .await?;
+
+        version
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid version: {}", version)))
+    }
+
+    // Adds `delta` to an INT value, starting from 0 for a missing key.
+    pub async fn incr_by(&mut self, key: &str, delta: i64) -> Result<i64, KvError> {
+        let value = self.execute("INCRBY", &[key, &delta.to_string()]).await?;
+
+        value
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid integer: {}", value)))
+    }
+
+    pub async fn incr_by_float(&mut self, key: &str, delta: f64) -> Result<f64, KvError> {
+        let value = self
+            .execute("INCRBYFLOAT", &[key, &delta.to_string()])
+            .await?;
+
+        value
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid float: {}", value)))
+    }
+
+    pub async fn toggle(&mut self, key: &str) -> Result<bool, KvError> {
+        let value = self.execute("TOGGLE", &[key]).await?;
+
+        value
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid boolean: {}", value)))
+    }
+
+    // Appends `values` to the list at `key` and returns its new length.
+    pub async fn rpush(&mut self, key: &str, values: &[&str]) -> Result<usize, KvError> {
+        self.push("RPUSH", key, values).await
+    }
+
+    pub async fn lpush(&mut self, key: &str, values: &[&str]) -> Result<usize, KvError> {
+        self.push("LPUSH", key, values).await
+    }
+
+    async fn push(&mut self, command: &str, key: &str, values: &[&str]) -> Result<usize, KvError> {
+        let mut args = vec![key];
+        args.extend_from_slice(values);
+
+        let length = self.execute(command, &args).await?;
+        length
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid length: {}", length)))
+    }
+
+    pub async fn lpop(&mut self, key: &str) -> Result<String, KvError> {
+        self.execute("LPOP", &[key]).await
+    }
+
+    pub async fn rpop(&mut self, key: &str) -> Result<String, KvError> {
+        self.execute("RPOP", &[key]).await
+    }
+
+    pub async fn lrange(
+        &mut self,
+        key: &str,
+        start: i64,
+        stop: i64,
+    ) -> Result<Vec<String>, KvError> {
+        let items = self
+            .execute("LRANGE", &[key, &start.to_string(), &stop.to_string()])
+            .await?;
+        Ok(items.lines().map(|item| item.to_string()).collect())
+    }
+
+    // Waits up to `timeout` seconds (0 for no limit) for an element in one of
+    // the lists and returns the key it was popped from with the element.
+    pub async fn blpop(
+        &mut self,
+        keys: &[&str],
+        timeout: u64,
+    ) -> Result<(String, String), KvError> {
+        let timeout = timeout.to_string();
+        let mut args = keys.to_vec();
+        args.push(&timeout);
+
+        let reply = self.execute("BLPOP", &args).await?;
+        reply
+            .split_once('\n')
+            .map(|(key, item)| (key.to_string(), item.to_string()))
+            .ok_or_else(|| KvError::Protocol(format!("Invalid BLPOP reply: {}", reply)))
+    }
+
+    // Adds `members` to the set at `key` and returns how many were new.
+    pub async fn sadd(&mut self, key: &str, members: &[&str]) -> Result<usize, KvError> {
+        self.change_members("SADD", key, members).await
+    }
+
+    pub async fn srem(&mut self, key: &str, members: &[&str]) -> Result<usize, KvError> {
+        self.change_members("SREM", key, members).await
+    }
+
+    async fn change_members(
+        &mut self,
+        command: &str,
+        key: &str,
+        members: &[&str],
+    ) -> Result<usize, KvError> {
+        let mut args = vec![key];
+        args.extend_from_slice(members);
+
+        let count = self.execute(command, &args).await?;
+        count
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid count: {}", count)))
+    }
+
+    pub async fn sismember(&mut self, key: &str, member: &str) -> Result<bool, KvError> {
+        let reply = self.execute("SISMEMBER", &[key, member]).await?;
+        reply
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid boolean: {}", reply)))
+    }
+
+    pub async fn smembers(&mut self, key: &str) -> Result<Vec<String>, KvError> {
+        let members = self.execute("SMEMBERS", &[key]).await?;
+        Ok(members.lines().map(|member| member.to_string()).collect())
+    }
+
+    // Sets the scores of the members and returns how many were new.
+    pub async fn zadd(&mut self, key: &str, members: &[(f64, &str)]) -> Result<usize, KvError> {
+        let scores = members
+            .iter()
+            .map(|(score, _)| score.to_string())
+            .collect::<Vec<String>>();
+
+        let mut args = vec![key];
+        for ((_, member), score) in members.iter().zip(&scores) {
+            args.push(score);
+            args.push(member);
+        }
+
+        let added = self.execute("ZADD", &args).await?;
+        added
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid count: {}", added)))
+    }
+
+    pub async fn zincrby(&mut self, key: &str, delta: f64, member: &str) -> Result<f64, KvError> {
+        let score = self
+            .execute("ZINCRBY", &[key, &delta.to_string(), member])
+            .await?;
+        score
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid score: {}", score)))
+    }
+
+    pub async fn zscore(&mut self, key: &str, member: &str) -> Result<f64, KvError> {
+        let score = self.execute("ZSCORE", &[key, member]).await?;
+        score
+            .parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid score: {}", score)))
+    }
+
+    pub async fn zrank(&mut self, key: &str, member: &str) -> Result<usize, KvError> {
+        let rank = self.execute("ZRANK", &[key, member]).await?;
+        rank.parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid rank: {}", rank)))
+    }
+
+    // Members from rank `start` to `stop` in ascending score order.
+    pub async fn zrange(
+        &mut self,
+        key: &str,
+        start: i64,
+        stop: i64,
+    ) -> Result<Vec<String>, KvError> {
+        let members = self
+            .execute("ZRANGE", &[key, &start.to_string(), &stop.to_string()])
+            .await?;
+        Ok(members.lines().map(|member| member.to_string()).collect())
+    }
+
+    // Serialized JSON of the node at `path` of the document at `key`.
+    pub async fn json_get(&mut self, key: &str, path: &str) -> Result<String, KvError> {
+        self.execute("JSON.GET", &[key, path]).await
+    }
+
+    pub async fn json_set(&mut self, key: &str, path: &str, json: &str) -> Result<(), KvError> {
+        self.execute_ack("JSON.SET", &[key, path, json]).await
+    }
+
+    // Returns whether there was a node to delete.
+    pub async fn json_del(&mut self, key: &str, path: &str) -> Result<bool, KvError> {
+        Ok(self.execute("JSON.DEL", &[key, path]).await? == "1")
+    }
+
+    // Appends JSON values to the array at `path`, returning its new length.
+    pub async fn json_arrappend(
+        &mut self,
+        key: &str,
+        path: &str,
+        values: &[&str],
+    ) -> Result<usize, KvError> {
+        let args = [&[key, path], values].concat();
+        let len = self.execute("JSON.ARRAPPEND", &args).await?;
+        len.parse()
+            .map_err(|_| KvError::Protocol(format!("Invalid length: {}", len)))
+    }
+
+    pub async fn del(&mut self, key: &str) -> Result<(), KvError> {
+        self.execute_ack("DEL", &[key]).await
+    }
+
+    pub async fn create_store(&mut self, path: &str) -> Result<(), KvError> {
+        self.execute_ack("CREATE_STORE", &[path]).await
+    }
+
+    pub async fn list_keys(&mut self, path: &str) -> Result<Vec<String>, KvError> {
+        let keys = self.execute("LIST_KEYS", &[path]).await?;
+        Ok(keys.lines().map(|key| key.to_string()).collect())
+    }
+
+    pub async fn create_user(
+        &mut self,
+        username: &str,
+        password: &str,
+        permissions: &[Permissions],
+    ) -> Result<(), KvError> {
+        let permissions = permissions
+            .iter()
+            .map(|permission| permission.to_string())
+            .collect::<Vec<String>>();
+
+        let mut args = vec![username, password];
+        args.extend(permissions.iter().map(|permission| permission.as_str()));
+
+        self.execute_ack("CREATE_USER", &args).await
+    }
+
+    pub async fn get_user(&mut self, username: &str) -> Result<String, KvError> {
+        self.execute("GET_USER", &[username]).await
+    }
+
+    pub async fn delete_user(&mut self, username: &str) -> Result<(), KvError> {
+        self.execute_ack("DELETE_USER", &[username]).await
+    }
+
+    // Changes the password of the authenticated user. Reconnects
+    // authenticate with the new password from then on.
+    pub async fn change_password(
+        &mut self,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), KvError> {
+        self.execute_ack("PASSWORD", &[old_password, new_password])
+            .await?;
+        if let Some((_, password)) = &mut self.credentials {
+            *password = new_password.to_string();
+        }
+        Ok(())
+    }
+
+    pub async fn set_password(
+        &mut self,
+        username: &str,
+        password: &str,
+        must_change: bool,
+    ) -> Result<(), KvError> {
+        let mut args = vec![username, password];
+        if must_change {
+            args.push("MUST_CHANGE");
+        }
+        self.execute_ack("SET_PASSWORD", &args).await
+    }
+
+    pub async fn grant(
+        &mut self,
+        username: &str,
+        permissions: &[Permissions],
+    ) -> Result<(), KvError> {
+        self.change_permissions("GRANT", username, permissions, None)
+            .await
+    }
+
+    // Grants the permissions on the keys matching `pattern` only.
+    pub async fn grant_on(
+        &mut self,
+        username: &str,
+        permissions: &[Permissions],
+        pattern: &str,
+    ) -> Result<(), KvError> {
+        self.change_permissions("GRANT", username, permissions, Some(pattern))
+            .await
+    }
+
+    pub async fn revoke(
+        &mut self,
+        username: &str,
+        permissions: &[Permissions],
+    ) -> Result<(), KvError> {
+        self.change_permissions("REVOKE", username, permissions, None)
+            .await
+    }
+
+    pub async fn revoke_on(
+        &mut self,
+        username: &str,
+        permissions: &[Permissions],
+        pattern: &str,
+    ) -> Result<(), KvError> {
+        self.change_permissions("REVOKE", username, permissions, Some(pattern))
+            .await
+    }
+
+    pub async fn create_role(
+        &mut self,
+        name: &str,
+        permissions: &[Permissions],
+    ) -> Result<(), KvError> {
+        self.change_permissions("CREATE_ROLE", name, permissions, None)
+            .await
+    }
+
+    pub async fn delete_role(&mut self, name: &str) -> Result<(), KvError> {
+        self.execute_ack("DELETE_ROLE", &[name]).await
+    }
+
+    pub async fn assign_role(&mut self, username: &str, role: &str) -> Result<(), KvError> {
+        self.execute_ack("ASSIGN_ROLE", &[username, role]).await
+    }
+
+    pub async fn unassign_role(&mut self, username: &str, role: &str) -> Result<(), KvError> {
+        self.execute_ack("UNASSIGN_ROLE", &[username, role]).await
+    }
+
+    pub async fn list_roles(&mut self) -> Result<Vec<String>, KvError> {
+        let roles = self.execute("LIST_ROLES", &[]).await?;
+        Ok(roles.lines().map(|role| role.to_string()).collect())
+    }
+
+    async fn change_permissions(
+        &mut self,
+        name: &str,
+        username: &str,
+        permissions: &[Permissions],
+        pattern: Option<&str>,
+    ) -> Result<(), KvError> {
+        let permissions = permissions
+            .iter()
+            .map(|permission| permission.to_string())
+            .collect::<Vec<String>>();
+
+        let mut args = vec![username];
+        args.extend(permissions.iter().map(|permission| permission.as_str()));
+        if let Some(pattern) = pattern {
+            args.extend(["ON", pattern]);
+        }
+
+        self.execute_ack(name, &args).await
+    }
+}
+
+#[cfg(test)]
+mod kv_client_tests {
+    use super::*;
+    use crate::commands::tokenize;
+    use tokio::io::AsyncReadExt;
+
+    #[test]
+    fn test_quote_arg() {
+        assert_eq!(quote_arg("value"), "value");
+        assert_eq!(quote_arg(""), "\"\"");
+        assert_eq!(quote_arg("hello world"), "\"hello world\"");
+        assert_eq!(quote_arg("a;b"), "\"a;b\"");
+        assert_eq!(quote_arg("say \"hi\""), r#""say \"hi\"""#);
+        assert_eq!(quote_arg("x\"41\""), r#""x\"41\"""#);
+        assert_eq!(quote_arg("max"), "max");
+    }
+
+    #[test]
+    fn test_format_command_round_trip() {
+        let args = ["key", "line\nbreak; \"quoted\" \\", "", "x\"41\""];
+        let command = format_command("SET", &args);
+
+        let mut tokens = tokenize(&command).unwrap();
+        assert_eq!(tokens.remove(0), "SET");
+        assert_eq!(tokens, args);
+    }
+
+    #[test]
+    fn test_client_codec() {
+        let mut codec = ClientCodec;
+        let mut buf = BytesMut::new();
+
+        codec.encode("GET key".to_string(), &mut buf).unwrap();
+        assert_eq!(&buf[..], b"GET key;");
+
+        let mut buf = BytesMut::from("8\nOK value");
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some("OK value".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lost_reply_is_not_retried() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        // Reads every command it gets and closes the connection without a
+        // reply.
+        let server = tokio::spawn(async move {
+            let mut commands = 0;
+            while let Ok(Ok((mut socket, _))) =
+                tokio::time::timeout(Duration::from_millis(300), listener.accept()).await
+            {
+                let mut byte = [0u8; 1];
+                while socket.read_exact(&mut byte).await.is_ok() {
+                    if byte[0] == COMMAND_TERMINATOR {
+                        commands += 1;
+                        break;
+                    }
+                }
+            }
+            commands
+        });
+
+        let mut client = Client::new(&address);
+        client.set_retry_policy(3, Duration::from_millis(1));
+
+        assert!(matches!(
+            client.incr_by("counter", 1).await,
+            Err(KvError::Connection(_))
+        ));
+
+        // The command may have been applied, so it was not sent again.
+        assert_eq!(server.await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_connection_error() {
+        let mut client = Client::new("127.0.0.1:1");
+        client.set_retry_policy(1, Duration::from_millis(1));
+
+        match client.get("key").await {
+            Err(KvError::Connection(_)) => {}
+            result => panic!("Expected connection error, got {:?}", result),
+        }
+        assert!(!client.is_connected());
+    }
+}
@@ -0,0 +1,5 @@
+mod kv_client;
+mod pool;
+
+pub use kv_client::*;
+pub use pool::*;
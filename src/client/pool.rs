@@ -0,0 +1,93 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::error::KvError;
+
+use super::Client;
+
+// Hands out up to `max_size` authenticated clients and keeps the ones that
+// are still connected for reuse once they are dropped.
+pub struct Pool {
+    address: String,
+    credentials: Option<(String, String)>,
+    idle: Arc<Mutex<Vec<Client>>>,
+    permits: Arc<Semaphore>,
+}
+
+pub struct PooledClient {
+    client: Option<Client>,
+    idle: Arc<Mutex<Vec<Client>>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Pool {
+    pub fn new(address: &str, max_size: usize) -> Pool {
+        Pool {
+            address: address.to_string(),
+            credentials: None,
+            idle: Arc::new(Mutex::new(Vec::new())),
+            permits: Arc::new(Semaphore::new(max_size)),
+        }
+    }
+
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Pool {
+        self.credentials = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    // Waits until a client is available.
+    pub async fn get(&self) -> Result<PooledClient, KvError> {
+        let permit = Arc::clone(&self.permits)
+            .acquire_owned()
+            .await
+            .map_err(|_| KvError::Connection("Pool closed".to_string()))?;
+
+        let idle = self.idle.lock().unwrap().pop();
+        let client = match idle {
+            Some(client) => client,
+            None => {
+                let mut client = Client::connect(&self.address).await?;
+                if let Some((username, password)) = &self.credentials {
+                    client.auth(username, password).await?;
+                }
+                client
+            }
+        };
+
+        Ok(PooledClient {
+            client: Some(client),
+            idle: Arc::clone(&self.idle),
+            _permit: permit,
+        })
+    }
+
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().unwrap().len()
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        self.client.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        self.client.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            if client.is_connected() {
+                self.idle.lock().unwrap().push(client);
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::io::{Error, ErrorKind};
 use std::str::FromStr;
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum CommandNames {
     SET,
     GET,
@@ -14,14 +14,140 @@ pub enum CommandNames {
     GET_USER,
     CREATE_USER,
     DELETE_USER,
+    PASSWORD,
+    SET_PASSWORD,
 
     // Authorization commands
     GRANT,
     REVOKE,
+    CREATE_ROLE,
+    DELETE_ROLE,
+    ASSIGN_ROLE,
+    UNASSIGN_ROLE,
+    LIST_ROLES,
 
     // Store management commands
     CREATE_STORE,
     LIST_KEYS,
+
+    // Optimistic concurrency
+    CAS,
+
+    // Numeric commands
+    INCR,
+    DECR,
+    INCRBY,
+    INCRBYFLOAT,
+    TOGGLE,
+
+    // List commands
+    LPUSH,
+    RPUSH,
+    LPOP,
+    RPOP,
+    LRANGE,
+    LLEN,
+    LINDEX,
+    LREM,
+    BLPOP,
+
+    // Set commands
+    SADD,
+    SREM,
+    SISMEMBER,
+    SMEMBERS,
+    SCARD,
+    SUNION,
+    SINTER,
+    SDIFF,
+
+    // Sorted set commands
+    ZADD,
+    ZINCRBY,
+    ZSCORE,
+    ZRANK,
+    ZRANGE,
+    ZRANGEBYSCORE,
+    ZREM,
+
+    // JSON document commands
+    JSON_GET,
+    JSON_SET,
+    JSON_DEL,
+    JSON_ARRAPPEND,
+
+    // Expiration commands
+    EXPIRE,
+    TTL,
+    PERSIST,
+
+    // Transaction commands
+    MULTI,
+    EXEC,
+    DISCARD,
+}
+
+impl CommandNames {
+    pub const ALL: &'static [CommandNames] = &[
+        CommandNames::SET,
+        CommandNames::GET,
+        CommandNames::DEL,
+        CommandNames::AUTH,
+        CommandNames::GET_USER,
+        CommandNames::CREATE_USER,
+        CommandNames::DELETE_USER,
+        CommandNames::PASSWORD,
+        CommandNames::SET_PASSWORD,
+        CommandNames::GRANT,
+        CommandNames::REVOKE,
+        CommandNames::CREATE_ROLE,
+        CommandNames::DELETE_ROLE,
+        CommandNames::ASSIGN_ROLE,
+        CommandNames::UNASSIGN_ROLE,
+        CommandNames::LIST_ROLES,
+        CommandNames::CREATE_STORE,
+        CommandNames::LIST_KEYS,
+        CommandNames::CAS,
+        CommandNames::INCR,
+        CommandNames::DECR,
+        CommandNames::INCRBY,
+        CommandNames::INCRBYFLOAT,
+        CommandNames::TOGGLE,
+        CommandNames::LPUSH,
+        CommandNames::RPUSH,
+        CommandNames::LPOP,
+        CommandNames::RPOP,
+        CommandNames::LRANGE,
+        CommandNames::LLEN,
+        CommandNames::LINDEX,
+        CommandNames::LREM,
+        CommandNames::BLPOP,
+        CommandNames::SADD,
+        CommandNames::SREM,
+        CommandNames::SISMEMBER,
+        CommandNames::SMEMBERS,
+        CommandNames::SCARD,
+        CommandNames::SUNION,
+        CommandNames::SINTER,
+        CommandNames::SDIFF,
+        CommandNames::ZADD,
+        CommandNames::ZINCRBY,
+        CommandNames::ZSCORE,
+        CommandNames::ZRANK,
+        CommandNames::ZRANGE,
+        CommandNames::ZRANGEBYSCORE,
+        CommandNames::ZREM,
+        CommandNames::JSON_GET,
+        CommandNames::JSON_SET,
+        CommandNames::JSON_DEL,
+        CommandNames::JSON_ARRAPPEND,
+        CommandNames::EXPIRE,
+        CommandNames::TTL,
+        CommandNames::PERSIST,
+        CommandNames::MULTI,
+        CommandNames::EXEC,
+        CommandNames::DISCARD,
+    ];
 }
 
 impl Display for CommandNames {
@@ -34,10 +160,57 @@ impl Display for CommandNames {
             CommandNames::GET_USER => write!(f, "GET_USER"),
             CommandNames::CREATE_USER => write!(f, "CREATE_USER"),
             CommandNames::DELETE_USER => write!(f, "DELETE_USER"),
+            CommandNames::PASSWORD => write!(f, "PASSWORD"),
+            CommandNames::SET_PASSWORD => write!(f, "SET_PASSWORD"),
             CommandNames::GRANT => write!(f, "GRANT"),
             CommandNames::REVOKE => write!(f, "REVOKE"),
+            CommandNames::CREATE_ROLE => write!(f, "CREATE_ROLE"),
+            CommandNames::DELETE_ROLE => write!(f, "DELETE_ROLE"),
+            CommandNames::ASSIGN_ROLE => write!(f, "ASSIGN_ROLE"),
+            CommandNames::UNASSIGN_ROLE => write!(f, "UNASSIGN_ROLE"),
+            CommandNames::LIST_ROLES => write!(f, "LIST_ROLES"),
             CommandNames::CREATE_STORE => write!(f, "CREATE_STORE"),
             CommandNames::LIST_KEYS => write!(f, "LIST_KEYS"),
+            CommandNames::CAS => write!(f, "CAS"),
+            CommandNames::INCR => write!(f, "INCR"),
+            CommandNames::DECR => write!(f, "DECR"),
+            CommandNames::INCRBY => write!(f, "INCRBY"),
+            CommandNames::INCRBYFLOAT => write!(f, "INCRBYFLOAT"),
+            CommandNames::TOGGLE => write!(f, "TOGGLE"),
+            CommandNames::LPUSH => write!(f, "LPUSH"),
+            CommandNames::RPUSH => write!(f, "RPUSH"),
+            CommandNames::LPOP => write!(f, "LPOP"),
+            CommandNames::RPOP => write!(f, "RPOP"),
+            CommandNames::LRANGE => write!(f, "LRANGE"),
+            CommandNames::LLEN => write!(f, "LLEN"),
+            CommandNames::LINDEX => write!(f, "LINDEX"),
+            CommandNames::LREM => write!(f, "LREM"),
+            CommandNames::BLPOP => write!(f, "BLPOP"),
+            CommandNames::SADD => write!(f, "SADD"),
+            CommandNames::SREM => write!(f, "SREM"),
+            CommandNames::SISMEMBER => write!(f, "SISMEMBER"),
+            CommandNames::SMEMBERS => write!(f, "SMEMBERS"),
+            CommandNames::SCARD => write!(f, "SCARD"),
+            CommandNames::SUNION => write!(f, "SUNION"),
+            CommandNames::SINTER => write!(f, "SINTER"),
+            CommandNames::SDIFF => write!(f, "SDIFF"),
+            CommandNames::ZADD => write!(f, "ZADD"),
+            CommandNames::ZINCRBY => write!(f, "ZINCRBY"),
+            CommandNames::ZSCORE => write!(f, "ZSCORE"),
+            CommandNames::ZRANK => write!(f, "ZRANK"),
+            CommandNames::ZRANGE => write!(f, "ZRANGE"),
+            CommandNames::ZRANGEBYSCORE => write!(f, "ZRANGEBYSCORE"),
+            CommandNames::ZREM => write!(f, "ZREM"),
+            CommandNames::JSON_GET => write!(f, "JSON.GET"),
+            CommandNames::JSON_SET => write!(f, "JSON.SET"),
+            CommandNames::JSON_DEL => write!(f, "JSON.DEL"),
+            CommandNames::JSON_ARRAPPEND => write!(f, "JSON.ARRAPPEND"),
+            CommandNames::EXPIRE => write!(f, "EXPIRE"),
+            CommandNames::TTL => write!(f, "TTL"),
+            CommandNames::PERSIST => write!(f, "PERSIST"),
+            CommandNames::MULTI => write!(f, "MULTI"),
+            CommandNames::EXEC => write!(f, "EXEC"),
+            CommandNames::DISCARD => write!(f, "DISCARD"),
         }
     }
 }
@@ -54,11 +227,58 @@ impl FromStr for CommandNames {
             "GET_USER" => Ok(CommandNames::GET_USER),
             "CREATE_USER" => Ok(CommandNames::CREATE_USER),
             "DELETE_USER" => Ok(CommandNames::DELETE_USER),
+            "PASSWORD" => Ok(CommandNames::PASSWORD),
+            "SET_PASSWORD" => Ok(CommandNames::SET_PASSWORD),
             "GRANT" => Ok(CommandNames::GRANT),
             "REVOKE" => Ok(CommandNames::REVOKE),
+            "CREATE_ROLE" => Ok(CommandNames::CREATE_ROLE),
+            "DELETE_ROLE" => Ok(CommandNames::DELETE_ROLE),
+            "ASSIGN_ROLE" => Ok(CommandNames::ASSIGN_ROLE),
+            "UNASSIGN_ROLE" => Ok(CommandNames::UNASSIGN_ROLE),
+            "LIST_ROLES" => Ok(CommandNames::LIST_ROLES),
             "CREATE_STORE" => Ok(CommandNames::CREATE_STORE),
             "LIST_KEYS" => Ok(CommandNames::LIST_KEYS),
-            _ => Err(Error::new(ErrorKind::InvalidInput, "Invalid command;")),
+            "CAS" => Ok(CommandNames::CAS),
+            "INCR" => Ok(CommandNames::INCR),
+            "DECR" => Ok(CommandNames::DECR),
+            "INCRBY" => Ok(CommandNames::INCRBY),
+            "INCRBYFLOAT" => Ok(CommandNames::INCRBYFLOAT),
+            "TOGGLE" => Ok(CommandNames::TOGGLE),
+            "LPUSH" => Ok(CommandNames::LPUSH),
+            "RPUSH" => Ok(CommandNames::RPUSH),
+            "LPOP" => Ok(CommandNames::LPOP),
+            "RPOP" => Ok(CommandNames::RPOP),
+            "LRANGE" => Ok(CommandNames::LRANGE),
+            "LLEN" => Ok(CommandNames::LLEN),
+            "LINDEX" => Ok(CommandNames::LINDEX),
+            "LREM" => Ok(CommandNames::LREM),
+            "BLPOP" => Ok(CommandNames::BLPOP),
+            "SADD" => Ok(CommandNames::SADD),
+            "SREM" => Ok(CommandNames::SREM),
+            "SISMEMBER" => Ok(CommandNames::SISMEMBER),
+            "SMEMBERS" => Ok(CommandNames::SMEMBERS),
+            "SCARD" => Ok(CommandNames::SCARD),
+            "SUNION" => Ok(CommandNames::SUNION),
+            "SINTER" => Ok(CommandNames::SINTER),
+            "SDIFF" => Ok(CommandNames::SDIFF),
+            "ZADD" => Ok(CommandNames::ZADD),
+            "ZINCRBY" => Ok(CommandNames::ZINCRBY),
+            "ZSCORE" => Ok(CommandNames::ZSCORE),
+            "ZRANK" => Ok(CommandNames::ZRANK),
+            "ZRANGE" => Ok(CommandNames::ZRANGE),
+            "ZRANGEBYSCORE" => Ok(CommandNames::ZRANGEBYSCORE),
+            "ZREM" => Ok(CommandNames::ZREM),
+            "JSON.GET" => Ok(CommandNames::JSON_GET),
+            "JSON.SET" => Ok(CommandNames::JSON_SET),
+            "JSON.DEL" => Ok(CommandNames::JSON_DEL),
+            "JSON.ARRAPPEND" => Ok(CommandNames::JSON_ARRAPPEND),
+            "EXPIRE" => Ok(CommandNames::EXPIRE),
+            "TTL" => Ok(CommandNames::TTL),
+            "PERSIST" => Ok(CommandNames::PERSIST),
+            "MULTI" => Ok(CommandNames::MULTI),
+            "EXEC" => Ok(CommandNames::EXEC),
+            "DISCARD" => Ok(CommandNames::DISCARD),
+            _ => Err(Error::new(ErrorKind::InvalidInput, "Invalid command")),
         }
     }
 }
@@ -74,4 +294,14 @@ mod command_names_tests {
         assert_eq!(CommandNames::from_str("DEL").unwrap(), CommandNames::DEL);
         assert!(CommandNames::from_str("INVALID").is_err());
     }
+
+    #[test]
+    fn test_all_commands_round_trip() {
+        for command in CommandNames::ALL {
+            assert_eq!(
+                &CommandNames::from_str(&command.to_string()).unwrap(),
+                command
+            );
+        }
+    }
 }
@@ -1,52 +1,67 @@
-use std::io::Error;
+use std::io::{Error, ErrorKind};
 use std::str::FromStr;
 
-use super::parser::{parse_line, parse_permissions};
-use super::validator::validate_args;
+use super::parser::parse_line;
+use super::validator::{acl_scope_position, is_set_option, validate_args};
 use super::CommandNames;
 
+use crate::auth::PermissionSet;
 use crate::data::DataTypes;
 
+fn parse_permission_args(args: &str) -> Result<PermissionSet, Error> {
+    PermissionSet::from_str(args).map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Command {
     pub name: CommandNames,
     pub args: Vec<String>,
 }
 
 impl Command {
-    fn new(name: CommandNames, args: Vec<String>) -> Command {
+    fn new(name: CommandNames, args: Vec<String>) -> Result<Command, Error> {
         match name {
             CommandNames::CREATE_USER => {
                 return Command::new_create_user_command(name, args);
             }
-            CommandNames::GRANT | CommandNames::REVOKE => {
+            CommandNames::GRANT | CommandNames::REVOKE | CommandNames::CREATE_ROLE => {
                 return Command::new_auth_command(name, args);
             }
             CommandNames::SET => {
-                return Command::new_set_command(name, args);
+                return Ok(Command::new_set_command(name, args));
             }
-            _ => Command { name, args },
+            CommandNames::CAS => Ok(Command::new_cas_command(name, args)),
+            _ => Ok(Command { name, args }),
         }
     }
 
-    fn new_create_user_command(name: CommandNames, args: Vec<String>) -> Command {
+    fn new_create_user_command(name: CommandNames, args: Vec<String>) -> Result<Command, Error> {
         let password = args[1].clone();
         let other_args = args[2..].join(" ");
 
-        let permissions = parse_permissions(&other_args);
+        let permissions = parse_permission_args(&other_args)?;
 
         let args = vec![args[0].clone(), password, permissions.to_string()];
 
-        Command { name, args }
+        Ok(Command { name, args })
     }
 
-    fn new_auth_command(name: CommandNames, args: Vec<String>) -> Command {
-        let other_args = args[1..].join(" ");
+    fn new_auth_command(name: CommandNames, args: Vec<String>) -> Result<Command, Error> {
+        // `ON <pattern>` scopes the permissions to the matching keys.
+        let scope = acl_scope_position(&args);
+        let other_args = args[1..scope.unwrap_or(args.len())].join(" ");
 
-        let permissions = parse_permissions(&other_args);
+        let permissions = parse_permission_args(&other_args)?;
 
-        let args = vec![args[0].clone(), permissions.to_string()];
+        let mut new_args = vec![args[0].clone(), permissions.to_string()];
+        if let Some(position) = scope {
+            new_args.push(args[position + 1].clone());
+        }
 
-        Command { name, args }
+        Ok(Command {
+            name,
+            args: new_args,
+        })
     }
 
     fn new_set_command(name: CommandNames, args: Vec<String>) -> Command {
@@ -54,17 +69,38 @@ impl Command {
 
         let value = args[1].clone();
 
-        let data_type = if args.len() == 2 {
-            DataTypes::STRING.to_string()
-        } else {
-            args[2].clone()
+        // The data type is optional, so options may directly follow the value.
+        let (data_type, options) = match args.get(2) {
+            Some(arg) if !is_set_option(arg) => (arg.clone(), &args[3..]),
+            _ => (DataTypes::STRING.to_string(), &args[2..]),
         };
 
+        let mut set_args = vec![key, value, data_type];
+        set_args.extend_from_slice(options);
+
         Command {
             name,
-            args: vec![key, value, data_type],
+            args: set_args,
         }
     }
+
+    fn new_cas_command(name: CommandNames, mut args: Vec<String>) -> Command {
+        if args.len() == 3 {
+            args.push(DataTypes::STRING.to_string());
+        }
+
+        Command { name, args }
+    }
+}
+
+impl Command {
+    // Builds a command from an already split name and arguments, e.g. when
+    // they arrive as separate elements of a RESP array.
+    pub fn from_parts(name: &str, args: Vec<String>) -> Result<Command, Error> {
+        let name = CommandNames::from_str(&name.to_uppercase())?;
+        validate_args(&name, args.clone())?;
+        Command::new(name, args)
+    }
 }
 
 impl FromStr for Command {
@@ -73,7 +109,7 @@ impl FromStr for Command {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (name, args) = parse_line(s.to_string())?;
         validate_args(&name, args.clone())?;
-        Ok(Command::new(name, args))
+        Command::new(name, args)
     }
 }
 
@@ -86,4 +122,16 @@ mod command_tests {
         assert!(Command::from_str("GET key").is_ok());
         assert!(Command::from_str("GET key value").is_err());
     }
+
+    #[test]
+    fn test_command_from_parts() {
+        let command =
+            Command::from_parts("set", vec!["key".to_string(), "a b".to_string()]).unwrap();
+
+        assert_eq!(command.name, CommandNames::SET);
+        assert_eq!(command.args, vec!["key", "a b", "STRING"]);
+
+        assert!(Command::from_parts("GET", vec![]).is_err());
+        assert!(Command::from_parts("UNKNOWN", vec![]).is_err());
+    }
 }
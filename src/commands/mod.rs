@@ -5,6 +5,7 @@ mod validator;
 
 pub use command_names::*;
 pub use commands::*;
+pub use parser::tokenize;
 
 #[cfg(test)]
 mod validator_tests;
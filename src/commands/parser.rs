@@ -3,59 +3,119 @@ use std::str::FromStr;
 
 use super::CommandNames;
 
-pub fn parse_permissions(args: &str) -> u8 {
-    let mut permissions = 0;
-    for permission in args.split(' ') {
-        match parse_permission_num(permission) {
-            Ok(perm) => return perm,
-            Err(_) => {
-                let parsed_permissions = parse_permissions_str(permissions, permission);
-                match parsed_permissions {
-                    Ok(perm) => {
-                        permissions = perm;
+fn parse_escape(c: char) -> Result<char, Error> {
+    match c {
+        'n' => Ok('\n'),
+        'r' => Ok('\r'),
+        't' => Ok('\t'),
+        '0' => Ok('\0'),
+        '\\' => Ok('\\'),
+        '"' => Ok('"'),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid escape sequence: \\{}", c),
+        )),
+    }
+}
+
+fn parse_hex_literal(hex: &str) -> Result<String, Error> {
+    let invalid = || Error::new(ErrorKind::InvalidInput, "Invalid hex literal");
+
+    if !hex.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(hex.get(i..i + 2).ok_or_else(invalid)?, 16).map_err(|_| invalid())
+        })
+        .collect::<Result<Vec<u8>, Error>>()?;
+
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+// Splits a command line into arguments. Arguments are separated by whitespace
+// and can be wrapped in double quotes to include whitespace, `;` or escaped
+// characters (`\"`, `\\`, `\n`, `\r`, `\t`, `\0`). Hex literals such as
+// `x"68656c6c6f"` are decoded into their bytes.
+pub fn tokenize(line: &str) -> Result<Vec<String>, Error> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut has_token = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                has_token = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => token.push(parse_escape(escaped)?),
+                            None => {
+                                return Err(Error::new(
+                                    ErrorKind::InvalidInput,
+                                    "Unterminated quoted string",
+                                ))
+                            }
+                        },
+                        Some(c) => token.push(c),
+                        None => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidInput,
+                                "Unterminated quoted string",
+                            ))
+                        }
                     }
-                    Err(_) => {
-                        break;
+                }
+            }
+            'x' if !has_token && chars.peek() == Some(&'"') => {
+                chars.next();
+                let mut hex = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => hex.push(c),
+                        None => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidInput,
+                                "Unterminated hex literal",
+                            ))
+                        }
                     }
                 }
+                token.push_str(&parse_hex_literal(&hex)?);
+                has_token = true;
+            }
+            c if c.is_whitespace() => {
+                if has_token {
+                    tokens.push(std::mem::take(&mut token));
+                    has_token = false;
+                }
+            }
+            c => {
+                token.push(c);
+                has_token = true;
             }
         }
     }
-    permissions
-}
 
-fn parse_permission_num(permissions: &str) -> Result<u8, Error> {
-    match permissions.parse::<u8>() {
-        Ok(perm) => Ok(perm),
-        Err(_) => Err(Error::new(ErrorKind::InvalidInput, "Invalid permission")),
+    if has_token {
+        tokens.push(token);
     }
-}
 
-fn parse_permissions_str(current_permissions: u8, permission: &str) -> Result<u8, Error> {
-    match permission {
-        "SET" => Ok(current_permissions | 1 << 0),
-        "GET" => Ok(current_permissions | 1 << 1),
-        "DEL" => Ok(current_permissions | 1 << 2),
-        "USER_ADMIN" => Ok(current_permissions | 1 << 3),
-
-        _ => return Err(Error::new(ErrorKind::InvalidInput, "Invalid permission")),
-    }
+    Ok(tokens)
 }
 
 pub fn parse_line(line: String) -> Result<(CommandNames, Vec<String>), Error> {
-    let parts: Vec<&str> = line.trim().splitn(2, ' ').collect();
-    if parts.len() < 1 {
+    let mut args = tokenize(&line)?;
+    if args.is_empty() {
         return Err(Error::new(ErrorKind::InvalidInput, "No command"));
     }
-    if parts.len() < 2 {
-        let cmd = CommandNames::from_str(parts[0])?;
-        return Ok((cmd, Vec::new()));
-    }
-
-    let command = parts[0].to_string();
-    let args: Vec<String> = parts[1].trim().split(' ').map(|s| s.to_string()).collect();
 
-    let cmd = CommandNames::from_str(&command)?;
+    let cmd = CommandNames::from_str(&args.remove(0))?;
     Ok((cmd, args))
 }
 
@@ -88,27 +148,80 @@ mod parser_tests {
     }
 
     #[test]
-    fn test_parse_permissions() {
-        let permissions = parse_permissions("");
-        assert_eq!(permissions, 0);
-
-        let permissions = parse_permissions("SET");
-        assert_eq!(permissions, 1);
-
-        let permissions = parse_permissions("SET GET");
-        assert_eq!(permissions, 3);
+    fn test_parse_line_extra_whitespace() {
+        assert_eq!(
+            parse_line("  SET   key\tvalue  ".to_string()).unwrap(),
+            (
+                CommandNames::SET,
+                vec!["key".to_string(), "value".to_string()]
+            )
+        );
+    }
 
-        let permissions = parse_permissions("SET GET DEL");
+    #[test]
+    fn test_tokenize_quoted() {
+        assert_eq!(
+            tokenize("SET key \"hello world\"").unwrap(),
+            vec!["SET", "key", "hello world"]
+        );
+        assert_eq!(
+            tokenize("SET key \"a;b\"").unwrap(),
+            vec!["SET", "key", "a;b"]
+        );
+        assert_eq!(tokenize("SET key \"\"").unwrap(), vec!["SET", "key", ""]);
+        assert_eq!(
+            tokenize("SET \"my key\" value").unwrap(),
+            vec!["SET", "my key", "value"]
+        );
+    }
 
-        assert_eq!(permissions, 7);
+    #[test]
+    fn test_tokenize_escapes() {
+        assert_eq!(
+            tokenize(r#"SET key "say \"hi\"""#).unwrap(),
+            vec!["SET", "key", "say \"hi\""]
+        );
+        assert_eq!(
+            tokenize(r#"SET key "back\\slash""#).unwrap(),
+            vec!["SET", "key", "back\\slash"]
+        );
+        assert_eq!(
+            tokenize(r#"SET key "line\nbreak\ttab""#).unwrap(),
+            vec!["SET", "key", "line\nbreak\ttab"]
+        );
+        assert_eq!(
+            tokenize(r#"SET key "trailing;""#).unwrap(),
+            vec!["SET", "key", "trailing;"]
+        );
+        assert!(tokenize(r#"SET key "bad\q""#).is_err());
+    }
 
-        let permissions = parse_permissions("SET GET DEL USER_ADMIN");
-        assert_eq!(permissions, 15);
+    #[test]
+    fn test_tokenize_unterminated() {
+        assert!(tokenize("SET key \"value").is_err());
+        assert!(tokenize("SET key \"value\\").is_err());
+        assert!(tokenize("SET key x\"6869").is_err());
+    }
 
-        let permissions = parse_permissions("SET GET DEL USER_ADMIN INVALID");
-        assert_eq!(permissions, 15);
+    #[test]
+    fn test_tokenize_hex_literal() {
+        assert_eq!(
+            tokenize(r#"SET key x"68656c6c6f20776f726c64""#).unwrap(),
+            vec!["SET", "key", "hello world"]
+        );
+        assert_eq!(
+            tokenize(r#"SET key x"3b""#).unwrap(),
+            vec!["SET", "key", ";"]
+        );
+        assert_eq!(tokenize(r#"SET key x"""#).unwrap(), vec!["SET", "key", ""]);
+        assert!(tokenize(r#"SET key x"6""#).is_err());
+        assert!(tokenize(r#"SET key x"zz""#).is_err());
+        assert!(tokenize(r#"SET key x"ff""#).is_err());
+    }
 
-        let permissions = parse_permissions("255");
-        assert_eq!(permissions, 255);
+    #[test]
+    fn test_tokenize_plain_x_token() {
+        assert_eq!(tokenize("SET x value").unwrap(), vec!["SET", "x", "value"]);
+        assert_eq!(tokenize("SET key max").unwrap(), vec!["SET", "key", "max"]);
     }
 }
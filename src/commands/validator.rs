@@ -1,9 +1,9 @@
 use super::CommandNames;
-use crate::data::DataTypes;
-use std::{
-    io::{Error, ErrorKind},
-    str::FromStr,
+use crate::auth::AclRule;
+use crate::data::{
+    parse_score, Binary, DataTypes, Encoding, JsonPath, List, RangeOptions, ScoreBound,
 };
+use std::io::{Error, ErrorKind};
 
 pub fn validate_args(name: &CommandNames, args: Vec<String>) -> Result<(), Error> {
     match name {
@@ -14,13 +14,98 @@ pub fn validate_args(name: &CommandNames, args: Vec<String>) -> Result<(), Error
         CommandNames::GET_USER => validate_get_user_args(args),
         CommandNames::CREATE_USER => validate_create_user_args(args),
         CommandNames::DELETE_USER => validate_delete_user_args(args),
+        CommandNames::PASSWORD => validate_password_args(args),
+        CommandNames::SET_PASSWORD => validate_set_password_args(args),
         CommandNames::GRANT => validate_grant_args(args),
         CommandNames::REVOKE => validate_revoke_args(args),
+        CommandNames::CREATE_ROLE => validate_create_role_args(args),
+        CommandNames::DELETE_ROLE => validate_delete_role_args(args),
+        CommandNames::ASSIGN_ROLE | CommandNames::UNASSIGN_ROLE => {
+            validate_role_assignment_args(args)
+        }
+        CommandNames::LIST_ROLES => validate_list_roles_args(args),
         CommandNames::CREATE_STORE => validate_create_store_args(args),
         CommandNames::LIST_KEYS => validate_list_keys_args(args),
+        CommandNames::CAS => validate_cas_args(args),
+        CommandNames::INCR | CommandNames::DECR | CommandNames::TOGGLE => {
+            validate_single_key_args(args)
+        }
+        CommandNames::INCRBY => validate_incr_by_args(args),
+        CommandNames::INCRBYFLOAT => validate_incr_by_float_args(args),
+        CommandNames::LPUSH | CommandNames::RPUSH => validate_key_with_values_args(args),
+        CommandNames::LPOP | CommandNames::RPOP | CommandNames::LLEN => {
+            validate_single_key_args(args)
+        }
+        CommandNames::LRANGE => validate_lrange_args(args),
+        CommandNames::LINDEX => validate_lindex_args(args),
+        CommandNames::LREM => validate_lrem_args(args),
+        CommandNames::BLPOP => validate_blpop_args(args),
+        CommandNames::SADD | CommandNames::SREM => validate_key_with_values_args(args),
+        CommandNames::SISMEMBER => validate_key_and_member_args(args),
+        CommandNames::SMEMBERS | CommandNames::SCARD => validate_single_key_args(args),
+        CommandNames::SUNION | CommandNames::SINTER | CommandNames::SDIFF => {
+            validate_set_algebra_args(args)
+        }
+        CommandNames::ZADD => validate_zadd_args(args),
+        CommandNames::ZINCRBY => validate_zincrby_args(args),
+        CommandNames::ZSCORE | CommandNames::ZRANK => validate_key_and_member_args(args),
+        CommandNames::ZRANGE => validate_zrange_args(args),
+        CommandNames::ZRANGEBYSCORE => validate_zrange_by_score_args(args),
+        CommandNames::ZREM => validate_key_with_values_args(args),
+        CommandNames::JSON_GET | CommandNames::JSON_DEL => validate_json_path_args(args),
+        CommandNames::JSON_SET => validate_json_set_args(args),
+        CommandNames::JSON_ARRAPPEND => validate_json_arrappend_args(args),
+        CommandNames::EXPIRE => validate_expire_args(args),
+        CommandNames::TTL => validate_ttl_args(args),
+        CommandNames::PERSIST => validate_persist_args(args),
+        CommandNames::MULTI | CommandNames::EXEC | CommandNames::DISCARD => {
+            validate_transaction_args(args)
+        }
     }
 }
 
+// Options that can follow the value (and optional data type) of a SET.
+pub fn is_set_option(arg: &str) -> bool {
+    matches!(arg, "EX" | "NX" | "XX" | "ENCODING")
+}
+
+fn validate_encoding(encoding: Option<&String>) -> Result<Encoding, Error> {
+    encoding
+        .map(String::as_str)
+        .unwrap_or_default()
+        .parse()
+        .map_err(|e: String| Error::new(ErrorKind::InvalidInput, e))
+}
+
+// Returns the encoding of the value if an ENCODING option was given.
+fn validate_set_options(options: &[String]) -> Result<Option<Encoding>, Error> {
+    let mut options = options.iter();
+    let mut has_condition = false;
+    let mut encoding = None;
+
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "EX" => match options.next().map(|seconds| seconds.parse::<u64>()) {
+                Some(Ok(_)) => {}
+                _ => return Err(Error::new(ErrorKind::InvalidInput, "Invalid expire time")),
+            },
+            "NX" | "XX" => {
+                if has_condition {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "NX and XX can not be combined",
+                    ));
+                }
+                has_condition = true;
+            }
+            "ENCODING" => encoding = Some(validate_encoding(options.next())?),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Invalid SET option")),
+        }
+    }
+
+    Ok(encoding)
+}
+
 fn validate_set_args(args: Vec<String>) -> Result<(), Error> {
     if args.len() < 2 {
         return Err(Error::new(
@@ -28,28 +113,54 @@ fn validate_set_args(args: Vec<String>) -> Result<(), Error> {
             "Invalid number of arguments",
         ));
     }
-    if args.len() >= 3 {
-        match DataTypes::from_str(&args[2]) {
-            Ok(data_type) => {
-                if data_type == DataTypes::STORE {
-                    return Err(Error::new(
-                        ErrorKind::InvalidInput,
-                        "Invalid data type. To create STORE use CREATE_STORE command",
-                    ));
-                }
-                match data_type.validate_data(&args[1]) {
-                    Ok(_) => {}
-                    Err(e) => return Err(Error::new(ErrorKind::InvalidInput, e)),
-                }
-            }
-            Err(e) => return Err(Error::new(ErrorKind::InvalidInput, e)),
+    if args.len() >= 3 && is_set_option(&args[2]) {
+        return match validate_set_options(&args[2..])? {
+            // Values without a data type are strings.
+            Some(encoding) => validate_binary_value(&args[1], "STRING", encoding),
+            None => Ok(()),
         };
     }
+    if args.len() >= 3 {
+        match validate_set_options(&args[3..])? {
+            Some(encoding) => validate_binary_value(&args[1], &args[2], encoding)?,
+            None => validate_typed_value(&args[1], &args[2])?,
+        }
+    }
     return Ok(());
 }
 
+// Checks a value against a type name as accepted by SET, e.g. `INT` or
+// `LIST<INT>`.
+fn validate_typed_value(value: &str, type_name: &str) -> Result<(), Error> {
+    let (data_type, element_type) = DataTypes::parse_with_element_type(type_name)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    if data_type == DataTypes::STORE {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid data type. To create STORE use CREATE_STORE command",
+        ));
+    }
+
+    let result = match element_type {
+        Some(element_type) => List::parse(value, element_type).map(|_| ()),
+        None => data_type.validate_data(value),
+    };
+    result.map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+}
+
+fn validate_binary_value(value: &str, type_name: &str, encoding: Encoding) -> Result<(), Error> {
+    if type_name != DataTypes::BYTES.to_string() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "ENCODING can only be used with BYTES values",
+        ));
+    }
+    Binary::decode(value, encoding).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    Ok(())
+}
+
 fn validate_get_args(args: Vec<String>) -> Result<(), Error> {
-    if args.len() != 1 {
+    if args.is_empty() {
         return Err(Error::new(
             ErrorKind::InvalidInput,
             "Invalid number of arguments",
@@ -61,6 +172,23 @@ fn validate_get_args(args: Vec<String>) -> Result<(), Error> {
             format!("Forbidden key name! {}", args[0]),
         ));
     }
+
+    // GET accepts WITHVERSION and ENCODING for BYTES values.
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "WITHVERSION" => {}
+            "ENCODING" => {
+                validate_encoding(options.next())?;
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Invalid number of arguments",
+                ))
+            }
+        }
+    }
     Ok(())
 }
 
@@ -114,6 +242,33 @@ fn validate_delete_user_args(args: Vec<String>) -> Result<(), Error> {
     Ok(())
 }
 
+fn validate_password_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+// SET_PASSWORD user password [MUST_CHANGE]
+fn validate_set_password_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if args.len() == 3 && args[2] != "MUST_CHANGE" {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid SET_PASSWORD option",
+        ));
+    }
+    Ok(())
+}
+
 fn validate_grant_args(args: Vec<String>) -> Result<(), Error> {
     if args.len() < 2 {
         return Err(Error::new(
@@ -121,7 +276,7 @@ fn validate_grant_args(args: Vec<String>) -> Result<(), Error> {
             "Invalid number of arguments",
         ));
     }
-    Ok(())
+    validate_acl_scope(&args)
 }
 
 fn validate_revoke_args(args: Vec<String>) -> Result<(), Error> {
@@ -131,6 +286,84 @@ fn validate_revoke_args(args: Vec<String>) -> Result<(), Error> {
             "Invalid number of arguments",
         ));
     }
+    validate_acl_scope(&args)
+}
+
+fn validate_create_role_args(args: Vec<String>) -> Result<(), Error> {
+    if args.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    validate_acl_scope(&args)
+}
+
+fn validate_delete_role_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_role_assignment_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_list_roles_args(args: Vec<String>) -> Result<(), Error> {
+    if !args.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+// Position of the `ON` that scopes permissions to keys. It can only be the
+// second to last argument, so a user, role or pattern named `ON` is not
+// taken for it.
+pub fn acl_scope_position(args: &[String]) -> Option<usize> {
+    let position = args.len().checked_sub(2)?;
+    (position > 0 && args[position] == "ON").then_some(position)
+}
+
+// Permissions can be followed by `ON <pattern>` to scope them to keys.
+fn validate_acl_scope(args: &[String]) -> Result<(), Error> {
+    let scope = acl_scope_position(args);
+    let permissions = &args[1..scope.unwrap_or(args.len())];
+    // An `ON` anywhere else is a misplaced scope, not a permission.
+    if permissions.iter().any(|arg| arg == "ON") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    let position = match scope {
+        Some(position) => position,
+        None => return Ok(()),
+    };
+    if position < 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if !AclRule::is_valid_pattern(&args[position + 1]) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid ACL pattern: {}", args[position + 1]),
+        ));
+    }
     Ok(())
 }
 
@@ -159,3 +392,287 @@ fn validate_list_keys_args(args: Vec<String>) -> Result<(), Error> {
     }
     Ok(())
 }
+
+fn validate_expire_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if args[1].parse::<u64>().is_err() {
+        return Err(Error::new(ErrorKind::InvalidInput, "Invalid expire time"));
+    }
+    Ok(())
+}
+
+fn validate_ttl_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_persist_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_transaction_args(args: Vec<String>) -> Result<(), Error> {
+    if !args.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_cas_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 3 && args.len() != 4 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if args[1].parse::<u64>().is_err() {
+        return Err(Error::new(ErrorKind::InvalidInput, "Invalid version"));
+    }
+    if args.len() == 4 {
+        validate_typed_value(&args[2], &args[3])?;
+    }
+    Ok(())
+}
+
+fn validate_single_key_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_incr_by_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if args[1].parse::<i64>().is_err() {
+        return Err(Error::new(ErrorKind::InvalidInput, "Invalid increment"));
+    }
+    Ok(())
+}
+
+fn validate_incr_by_float_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    match args[1].parse::<f64>() {
+        Ok(increment) if increment.is_finite() => Ok(()),
+        _ => Err(Error::new(ErrorKind::InvalidInput, "Invalid increment")),
+    }
+}
+
+fn validate_key_with_values_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() < 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_lrange_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if args[1].parse::<i64>().is_err() || args[2].parse::<i64>().is_err() {
+        return Err(Error::new(ErrorKind::InvalidInput, "Invalid index"));
+    }
+    Ok(())
+}
+
+fn validate_lindex_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if args[1].parse::<i64>().is_err() {
+        return Err(Error::new(ErrorKind::InvalidInput, "Invalid index"));
+    }
+    Ok(())
+}
+
+fn validate_lrem_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if args[1].parse::<i64>().is_err() {
+        return Err(Error::new(ErrorKind::InvalidInput, "Invalid count"));
+    }
+    Ok(())
+}
+
+fn validate_blpop_args(args: Vec<String>) -> Result<(), Error> {
+    // BLPOP key [key ...] timeout
+    if args.len() < 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if args[args.len() - 1].parse::<u64>().is_err() {
+        return Err(Error::new(ErrorKind::InvalidInput, "Invalid timeout"));
+    }
+    Ok(())
+}
+
+fn validate_key_and_member_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_set_algebra_args(args: Vec<String>) -> Result<(), Error> {
+    if args.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_zadd_args(args: Vec<String>) -> Result<(), Error> {
+    // ZADD key score member [score member ...]
+    if args.len() < 3 || args.len().is_multiple_of(2) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    for score in args[1..].iter().step_by(2) {
+        parse_score(score).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    }
+    Ok(())
+}
+
+fn validate_zincrby_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    parse_score(&args[1]).map_err(|_| Error::new(ErrorKind::InvalidInput, "Invalid increment"))?;
+    Ok(())
+}
+
+fn validate_zrange_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() < 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    if args[1].parse::<i64>().is_err() || args[2].parse::<i64>().is_err() {
+        return Err(Error::new(ErrorKind::InvalidInput, "Invalid index"));
+    }
+    RangeOptions::parse(&args[3..], false).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    Ok(())
+}
+
+fn validate_zrange_by_score_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() < 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    for bound in &args[1..3] {
+        bound
+            .parse::<ScoreBound>()
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    }
+    RangeOptions::parse(&args[3..], true).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    Ok(())
+}
+
+fn validate_json_path(path: &str) -> Result<(), Error> {
+    path.parse::<JsonPath>()
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    Ok(())
+}
+
+fn validate_json_values(values: &[String]) -> Result<(), Error> {
+    for value in values {
+        DataTypes::JSON
+            .validate_data(value)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    }
+    Ok(())
+}
+
+// JSON.GET and JSON.DEL take a key and an optional path, `$` by default.
+fn validate_json_path_args(args: Vec<String>) -> Result<(), Error> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    match args.get(1) {
+        Some(path) => validate_json_path(path),
+        None => Ok(()),
+    }
+}
+
+fn validate_json_set_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() != 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    validate_json_path(&args[1])?;
+    validate_json_values(&args[2..])
+}
+
+fn validate_json_arrappend_args(args: Vec<String>) -> Result<(), Error> {
+    if args.len() < 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Invalid number of arguments",
+        ));
+    }
+    validate_json_path(&args[1])?;
+    validate_json_values(&args[2..])
+}
@@ -136,7 +136,7 @@ fn test_validate_create_user_args() {
     let command = Command::from_str("CREATE_USER username password").unwrap();
 
     assert_eq!(command.name, CommandNames::CREATE_USER);
-    assert_eq!(command.args, vec!["username", "password", "0"]);
+    assert_eq!(command.args, vec!["username", "password", "NONE"]);
 
     match Command::from_str("CREATE_USER") {
         Ok(_) => panic!("Expected error"),
@@ -147,7 +147,10 @@ fn test_validate_create_user_args() {
         Command::from_str("CREATE_USER username1 password1 USER_ADMIN SET GET DEL").unwrap();
 
     assert_eq!(command.name, CommandNames::CREATE_USER);
-    assert_eq!(command.args, vec!["username1", "password1", "15"]);
+    assert_eq!(
+        command.args,
+        vec!["username1", "password1", "SET,GET,DEL,USER_ADMIN"]
+    );
 }
 
 #[test]
@@ -173,7 +176,7 @@ fn test_validate_grant_args() {
     let command = Command::from_str("GRANT username SET").unwrap();
 
     assert_eq!(command.name, CommandNames::GRANT);
-    assert_eq!(command.args, vec!["username", "1"]);
+    assert_eq!(command.args, vec!["username", "SET"]);
 
     match Command::from_str("GRANT") {
         Ok(_) => panic!("Expected error"),
@@ -183,7 +186,7 @@ fn test_validate_grant_args() {
     let command = Command::from_str("GRANT username1 SET GET DEL USER_ADMIN").unwrap();
 
     assert_eq!(command.name, CommandNames::GRANT);
-    assert_eq!(command.args, vec!["username1", "15"]);
+    assert_eq!(command.args, vec!["username1", "SET,GET,DEL,USER_ADMIN"]);
 }
 
 #[test]
@@ -191,7 +194,7 @@ fn test_validate_revoke_args() {
     let command = Command::from_str("REVOKE username SET").unwrap();
 
     assert_eq!(command.name, CommandNames::REVOKE);
-    assert_eq!(command.args, vec!["username", "1"]);
+    assert_eq!(command.args, vec!["username", "SET"]);
 
     match Command::from_str("REVOKE") {
         Ok(_) => panic!("Expected error"),
@@ -201,7 +204,7 @@ fn test_validate_revoke_args() {
     let command = Command::from_str("REVOKE username1 SET GET DEL USER_ADMIN").unwrap();
 
     assert_eq!(command.name, CommandNames::REVOKE);
-    assert_eq!(command.args, vec!["username1", "15"]);
+    assert_eq!(command.args, vec!["username1", "SET,GET,DEL,USER_ADMIN"]);
 }
 
 #[test]
@@ -231,3 +234,378 @@ fn test_validate_create_store_args() {
     assert_eq!(command.name, CommandNames::CREATE_STORE);
     assert_eq!(command.args, vec!["store1:store2"]);
 }
+
+#[test]
+fn test_validate_quoted_args() {
+    let command = Command::from_str(r#"SET key "hello world""#).unwrap();
+
+    assert_eq!(command.name, CommandNames::SET);
+    assert_eq!(command.args, vec!["key", "hello world", "STRING"]);
+
+    let command = Command::from_str(r#"SET key "a;b" STRING"#).unwrap();
+
+    assert_eq!(command.args, vec!["key", "a;b", "STRING"]);
+
+    let command = Command::from_str(r#"GET "key with spaces""#).unwrap();
+
+    assert_eq!(command.name, CommandNames::GET);
+    assert_eq!(command.args, vec!["key with spaces"]);
+
+    match Command::from_str(r#"SET key "1 2" INT"#) {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid data type"),
+    }
+
+    match Command::from_str(r#"SET key "value"#) {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Unterminated quoted string"),
+    }
+}
+
+#[test]
+fn test_validate_set_expire_option() {
+    let command = Command::from_str("SET key value EX 10").unwrap();
+
+    assert_eq!(command.args, vec!["key", "value", "STRING", "EX", "10"]);
+
+    let command = Command::from_str("SET key 42 INT EX 10").unwrap();
+
+    assert_eq!(command.args, vec!["key", "42", "INT", "EX", "10"]);
+
+    match Command::from_str("SET key value EX") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid expire time"),
+    }
+
+    match Command::from_str("SET key value STRING EX -1") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid expire time"),
+    }
+
+    match Command::from_str("SET key value STRING PX 10") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid SET option"),
+    }
+}
+
+#[test]
+fn test_validate_expire_args() {
+    let command = Command::from_str("EXPIRE key 10").unwrap();
+
+    assert_eq!(command.name, CommandNames::EXPIRE);
+    assert_eq!(command.args, vec!["key", "10"]);
+
+    match Command::from_str("EXPIRE key") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid number of arguments"),
+    }
+
+    match Command::from_str("EXPIRE key soon") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid expire time"),
+    }
+
+    assert!(Command::from_str("TTL key").is_ok());
+    assert!(Command::from_str("TTL").is_err());
+    assert!(Command::from_str("PERSIST key").is_ok());
+    assert!(Command::from_str("PERSIST key1 key2").is_err());
+}
+
+#[test]
+fn test_validate_transaction_args() {
+    assert_eq!(
+        Command::from_str("MULTI").unwrap().name,
+        CommandNames::MULTI
+    );
+    assert_eq!(Command::from_str("EXEC").unwrap().name, CommandNames::EXEC);
+    assert_eq!(
+        Command::from_str("DISCARD").unwrap().name,
+        CommandNames::DISCARD
+    );
+
+    match Command::from_str("EXEC now") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid number of arguments"),
+    }
+}
+
+#[test]
+fn test_validate_versioned_args() {
+    let command = Command::from_str("GET key WITHVERSION").unwrap();
+
+    assert_eq!(command.args, vec!["key", "WITHVERSION"]);
+
+    match Command::from_str("GET key WITHTTL") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid number of arguments"),
+    }
+
+    let command = Command::from_str("CAS key 3 value").unwrap();
+
+    assert_eq!(command.name, CommandNames::CAS);
+    assert_eq!(command.args, vec!["key", "3", "value", "STRING"]);
+
+    let command = Command::from_str("CAS key 0 42 INT").unwrap();
+
+    assert_eq!(command.args, vec!["key", "0", "42", "INT"]);
+
+    match Command::from_str("CAS key latest value") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid version"),
+    }
+
+    match Command::from_str("CAS key 1 value INT") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid data type"),
+    }
+
+    let command = Command::from_str("SET key value NX").unwrap();
+
+    assert_eq!(command.args, vec!["key", "value", "STRING", "NX"]);
+
+    let command = Command::from_str("SET key 1 INT XX EX 10").unwrap();
+
+    assert_eq!(command.args, vec!["key", "1", "INT", "XX", "EX", "10"]);
+
+    match Command::from_str("SET key value NX XX") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "NX and XX can not be combined"),
+    }
+}
+
+#[test]
+fn test_validate_numeric_args() {
+    let command = Command::from_str("INCRBY counter -5").unwrap();
+
+    assert_eq!(command.name, CommandNames::INCRBY);
+    assert_eq!(command.args, vec!["counter", "-5"]);
+
+    assert!(Command::from_str("INCR counter").is_ok());
+    assert!(Command::from_str("DECR counter 1").is_err());
+    assert!(Command::from_str("TOGGLE").is_err());
+    assert!(Command::from_str("INCRBYFLOAT price 0.5").is_ok());
+
+    match Command::from_str("INCRBY counter 1.5") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid increment"),
+    }
+
+    match Command::from_str("INCRBYFLOAT price NaN") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid increment"),
+    }
+}
+
+#[test]
+fn test_validate_list_args() {
+    let command = Command::from_str("RPUSH list a b").unwrap();
+
+    assert_eq!(command.name, CommandNames::RPUSH);
+    assert_eq!(command.args, vec!["list", "a", "b"]);
+
+    assert!(Command::from_str("LPUSH list").is_err());
+    assert!(Command::from_str("LPOP list").is_ok());
+    assert!(Command::from_str("LLEN list other").is_err());
+    assert!(Command::from_str("LRANGE list 0 -1").is_ok());
+    assert!(Command::from_str("LREM list 0 a").is_ok());
+
+    match Command::from_str("LINDEX list first") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid index"),
+    }
+
+    match Command::from_str("BLPOP list soon") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid timeout"),
+    }
+
+    let command = Command::from_str(r#"SET scores "[1, 2]" LIST<INT>"#).unwrap();
+
+    assert_eq!(command.args, vec!["scores", "[1, 2]", "LIST<INT>"]);
+
+    assert!(Command::from_str(r#"SET scores "[1, \"a\"]" LIST<INT>"#).is_err());
+    assert!(Command::from_str("SET scores 1 LIST").is_err());
+}
+
+#[test]
+fn test_validate_set_type_args() {
+    let command = Command::from_str("SADD tags a b").unwrap();
+
+    assert_eq!(command.name, CommandNames::SADD);
+    assert_eq!(command.args, vec!["tags", "a", "b"]);
+
+    assert!(Command::from_str("SADD tags").is_err());
+    assert!(Command::from_str("SISMEMBER tags").is_err());
+    assert!(Command::from_str("SCARD tags").is_ok());
+    assert!(Command::from_str("SUNION a:tags b:tags c:tags").is_ok());
+    assert!(Command::from_str("SDIFF").is_err());
+
+    assert!(Command::from_str(r#"SET tags "[\"a\"]" SET"#).is_ok());
+    assert!(Command::from_str("SET tags a SET").is_err());
+}
+
+#[test]
+fn test_validate_sorted_set_args() {
+    let command = Command::from_str("ZADD board 1.5 alice 2 bob").unwrap();
+
+    assert_eq!(command.name, CommandNames::ZADD);
+    assert_eq!(command.args, vec!["board", "1.5", "alice", "2", "bob"]);
+
+    assert!(Command::from_str("ZADD board 1").is_err());
+    assert!(Command::from_str("ZADD board 1 alice 2").is_err());
+
+    match Command::from_str("ZADD board high alice") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid score"),
+    }
+
+    match Command::from_str("ZINCRBY board inf alice") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid increment"),
+    }
+
+    assert!(Command::from_str("ZRANGE board 0 -1 WITHSCORES").is_ok());
+    assert!(Command::from_str("ZRANGE board 0 -1 LIMIT 0 1").is_err());
+    assert!(Command::from_str("ZRANGEBYSCORE board (1 +inf LIMIT 0 10").is_ok());
+    assert!(Command::from_str("ZRANGEBYSCORE board low high").is_err());
+
+    match Command::from_str("ZRANGEBYSCORE board 0 1 LIMIT 1") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid LIMIT"),
+    }
+}
+
+#[test]
+fn test_validate_json_args() {
+    let command = Command::from_str("JSON.SET user $.age 30").unwrap();
+
+    assert_eq!(command.name, CommandNames::JSON_SET);
+    assert_eq!(command.args, vec!["user", "$.age", "30"]);
+
+    assert!(Command::from_str("JSON.GET user").is_ok());
+    assert!(Command::from_str("JSON.DEL user $.tags[-1]").is_ok());
+    assert!(Command::from_str("JSON.SET user $.age").is_err());
+    assert!(Command::from_str("JSON.ARRAPPEND user $.tags").is_err());
+
+    match Command::from_str("JSON.GET user name") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid JSON path: name"),
+    }
+
+    match Command::from_str("JSON.ARRAPPEND user $.tags 1 {a}") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid JSON document"),
+    }
+}
+
+#[test]
+fn test_validate_bytes_args() {
+    let command = Command::from_str("SET blob 00ff BYTES ENCODING HEX").unwrap();
+
+    assert_eq!(command.name, CommandNames::SET);
+    assert_eq!(
+        command.args,
+        vec!["blob", "00ff", "BYTES", "ENCODING", "HEX"]
+    );
+
+    assert!(Command::from_str("SET blob AP8Q BYTES").is_ok());
+    assert!(Command::from_str("GET blob ENCODING HEX WITHVERSION").is_ok());
+    assert!(Command::from_str("GET blob ENCODING").is_err());
+
+    match Command::from_str("SET blob 0f0 BYTES ENCODING HEX") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid hex value"),
+    }
+
+    match Command::from_str("SET blob %% BYTES") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid base64 value"),
+    }
+
+    match Command::from_str("SET blob 00ff ENCODING HEX") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "ENCODING can only be used with BYTES values"),
+    }
+
+    match Command::from_str("SET blob 00ff BYTES ENCODING RAW") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid encoding"),
+    }
+}
+
+#[test]
+fn test_validate_grant_scope_args() {
+    let command = Command::from_str("GRANT alice GET,SET ON orders:*").unwrap();
+    assert_eq!(command.name, CommandNames::GRANT);
+    assert_eq!(command.args, vec!["alice", "SET,GET", "orders:*"]);
+
+    let command = Command::from_str("REVOKE alice DEL ON orders:1").unwrap();
+    assert_eq!(command.args, vec!["alice", "DEL", "orders:1"]);
+
+    assert!(Command::from_str("GRANT alice GET ON *").is_ok());
+    assert!(Command::from_str("GRANT alice GET ON").is_err());
+    assert!(Command::from_str("GRANT alice ON orders:*").is_err());
+    assert!(Command::from_str("GRANT alice GET ON orders:* customers:*").is_err());
+
+    assert!(Command::from_str("GRANT alice GET ON ON orders:*").is_err());
+
+    // A user or pattern named `ON` is not taken for the scope.
+    let command = Command::from_str("GRANT ON GET").unwrap();
+    assert_eq!(command.args, vec!["ON", "GET"]);
+
+    let command = Command::from_str("GRANT ON GET ON ON").unwrap();
+    assert_eq!(command.args, vec!["ON", "GET", "ON"]);
+
+    match Command::from_str("GRANT alice GET ON orders*") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid ACL pattern: orders*"),
+    }
+}
+
+#[test]
+fn test_validate_unknown_permission() {
+    match Command::from_str("GRANT bob GET WRTIE") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid permission: WRTIE"),
+    }
+    assert!(Command::from_str("REVOKE bob GET,WRTIE ON orders:*").is_err());
+    assert!(Command::from_str("CREATE_ROLE reader GETS").is_err());
+    assert!(Command::from_str("CREATE_USER bob secret GET WRTIE").is_err());
+    assert!(Command::from_str("GRANT bob SET 3").is_err());
+    assert!(Command::from_str("CREATE_USER bob secret 16").is_err());
+}
+
+#[test]
+fn test_validate_role_args() {
+    let command = Command::from_str("CREATE_ROLE reader GET,DEL").unwrap();
+    assert_eq!(command.args, vec!["reader", "GET,DEL"]);
+
+    let command = Command::from_str("CREATE_ROLE reader GET ON orders:*").unwrap();
+    assert_eq!(command.args, vec!["reader", "GET", "orders:*"]);
+
+    assert!(Command::from_str("CREATE_ROLE").is_err());
+    assert!(Command::from_str("CREATE_ROLE reader GET ON").is_err());
+    assert!(Command::from_str("DELETE_ROLE").is_err());
+    assert!(Command::from_str("ASSIGN_ROLE alice").is_err());
+    assert!(Command::from_str("UNASSIGN_ROLE alice reader extra").is_err());
+    assert!(Command::from_str("LIST_ROLES").is_ok());
+    assert!(Command::from_str("LIST_ROLES extra").is_err());
+}
+
+#[test]
+fn test_validate_password_args() {
+    assert!(Command::from_str("PASSWORD Password4 Password5").is_ok());
+    assert!(Command::from_str("PASSWORD Password4").is_err());
+
+    let command = Command::from_str("SET_PASSWORD alice Password5 MUST_CHANGE").unwrap();
+    assert_eq!(command.name, CommandNames::SET_PASSWORD);
+    assert_eq!(command.args, vec!["alice", "Password5", "MUST_CHANGE"]);
+
+    assert!(Command::from_str("SET_PASSWORD alice Password5").is_ok());
+    assert!(Command::from_str("SET_PASSWORD alice").is_err());
+
+    match Command::from_str("SET_PASSWORD alice Password5 LATER") {
+        Ok(_) => panic!("Expected error"),
+        Err(e) => assert_eq!(e.to_string(), "Invalid SET_PASSWORD option"),
+    }
+}
@@ -1,13 +1,63 @@
 use std::fs;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
 use crate::persistence::Persistence;
 
+fn default_max_frame_size() -> usize {
+    1024 * 1024
+}
+
+fn default_shutdown_timeout() -> u64 {
+    10
+}
+
+fn default_socket_permissions() -> u32 {
+    0o660
+}
+
+fn default_token_ttl() -> u64 {
+    60 * 60
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+    #[serde(default)]
+    pub client_ca_path: Option<String>,
+    // Refuses plaintext clients. The HTTP gateway does not support TLS, so
+    // it can not be enabled together with this; without it the gateway keeps
+    // serving plaintext HTTP.
+    #[serde(default)]
+    pub tls_only: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UnixSocketConfig {
+    pub path: String,
+    #[serde(default = "default_socket_permissions")]
+    pub permissions: u32,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ServerConfig {
     pub address: String,
     pub port: u16,
+    #[serde(default = "default_max_frame_size")]
+    pub max_frame_size: usize,
+    #[serde(default)]
+    pub resp_port: Option<u16>,
+    #[serde(default)]
+    pub http_port: Option<u16>,
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub unix_socket: Option<UnixSocketConfig>,
+    // Seconds to wait for connections to finish when shutting down.
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
 }
 
 impl Default for ServerConfig {
@@ -15,6 +65,12 @@ impl Default for ServerConfig {
         ServerConfig {
             address: "127.0.0.1".to_string(),
             port: 4000,
+            max_frame_size: default_max_frame_size(),
+            resp_port: None,
+            http_port: None,
+            tls: None,
+            unix_socket: None,
+            shutdown_timeout: default_shutdown_timeout(),
         }
     }
 }
@@ -34,11 +90,32 @@ impl Default for AdminConfig {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct AuthConfig {
+    // Seconds after which users have to change their password.
+    #[serde(default)]
+    pub password_max_age: Option<u64>,
+    // Seconds a token of the HTTP gateway stays valid.
+    #[serde(default = "default_token_ttl")]
+    pub token_ttl: u64,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            password_max_age: None,
+            token_ttl: default_token_ttl(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
     pub persistence: Persistence,
     pub admin: AdminConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
 }
 
 impl Config {
@@ -47,21 +124,55 @@ impl Config {
             server: ServerConfig::default(),
             persistence: Persistence::new_in_memory(),
             admin: AdminConfig::default(),
+            auth: AuthConfig::default(),
         }
     }
 
     pub fn add_server_config(&mut self, address: String, port: u16) {
-        self.server = ServerConfig { address, port };
+        self.server.address = address;
+        self.server.port = port;
+    }
+
+    pub fn add_max_frame_size(&mut self, max_frame_size: usize) {
+        self.server.max_frame_size = max_frame_size;
+    }
+
+    pub fn add_shutdown_timeout(&mut self, shutdown_timeout: u64) {
+        self.server.shutdown_timeout = shutdown_timeout;
     }
 
     pub fn add_persistence_config(&mut self, persistence: Persistence) {
         self.persistence = persistence;
     }
 
+    pub fn add_resp_config(&mut self, port: u16) {
+        self.server.resp_port = Some(port);
+    }
+
+    pub fn add_http_config(&mut self, port: u16) {
+        self.server.http_port = Some(port);
+    }
+
+    pub fn add_tls_config(&mut self, tls: TlsConfig) {
+        self.server.tls = Some(tls);
+    }
+
+    pub fn add_unix_socket_config(&mut self, path: String, permissions: u32) {
+        self.server.unix_socket = Some(UnixSocketConfig { path, permissions });
+    }
+
     pub fn add_admin_config(&mut self, username: String, password: String) {
         self.admin = AdminConfig { username, password };
     }
 
+    pub fn add_password_max_age(&mut self, password_max_age: u64) {
+        self.auth.password_max_age = Some(password_max_age);
+    }
+
+    pub fn add_token_ttl(&mut self, token_ttl: u64) {
+        self.auth.token_ttl = token_ttl;
+    }
+
     pub fn load(path: String) -> Self {
         let config_yaml = match fs::read_to_string(path.clone()) {
             Ok(content) => content,
@@ -85,7 +196,43 @@ impl Config {
         (self.admin.username.clone(), self.admin.password.clone())
     }
 
+    pub fn get_password_max_age(&self) -> Option<Duration> {
+        self.auth.password_max_age.map(Duration::from_secs)
+    }
+
+    pub fn get_token_ttl(&self) -> Duration {
+        Duration::from_secs(self.auth.token_ttl)
+    }
+
     pub fn get_server_address(&self) -> String {
         format!("{}:{}", self.server.address, self.server.port)
     }
+
+    pub fn get_resp_address(&self) -> Option<String> {
+        self.server
+            .resp_port
+            .map(|port| format!("{}:{}", self.server.address, port))
+    }
+
+    pub fn get_http_address(&self) -> Option<String> {
+        self.server
+            .http_port
+            .map(|port| format!("{}:{}", self.server.address, port))
+    }
+
+    pub fn get_tls_config(&self) -> Option<&TlsConfig> {
+        self.server.tls.as_ref()
+    }
+
+    pub fn get_unix_socket_config(&self) -> Option<&UnixSocketConfig> {
+        self.server.unix_socket.as_ref()
+    }
+
+    pub fn get_shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.server.shutdown_timeout)
+    }
+
+    pub fn get_max_frame_size(&self) -> usize {
+        self.server.max_frame_size
+    }
 }
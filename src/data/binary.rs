@@ -0,0 +1,140 @@
+use std::{fmt::Display, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+// Text encoding of a BYTES value in commands and replies.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    BASE64,
+    HEX,
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "BASE64" => Ok(Encoding::BASE64),
+            "HEX" => Ok(Encoding::HEX),
+            _ => Err("Invalid encoding".to_string()),
+        }
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Encoding::BASE64 => write!(f, "BASE64"),
+            Encoding::HEX => write!(f, "HEX"),
+        }
+    }
+}
+
+// Raw bytes of a BYTES value. They are persisted as a base64 string, which
+// is far more compact than the JSON array of numbers serde would write.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Binary {
+    bytes: Vec<u8>,
+}
+
+impl TryFrom<String> for Binary {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Binary, String> {
+        Binary::decode(&value, Encoding::BASE64)
+    }
+}
+
+impl From<Binary> for String {
+    fn from(binary: Binary) -> String {
+        binary.encode(Encoding::BASE64)
+    }
+}
+
+impl Binary {
+    pub fn new(bytes: Vec<u8>) -> Binary {
+        Binary { bytes }
+    }
+
+    pub fn decode(value: &str, encoding: Encoding) -> Result<Binary, String> {
+        let bytes = match encoding {
+            Encoding::BASE64 => STANDARD
+                .decode(value)
+                .map_err(|_| "Invalid base64 value".to_string())?,
+            Encoding::HEX => decode_hex(value).ok_or_else(|| "Invalid hex value".to_string())?,
+        };
+        Ok(Binary { bytes })
+    }
+
+    pub fn encode(&self, encoding: Encoding) -> String {
+        match encoding {
+            Encoding::BASE64 => STANDARD.encode(&self.bytes),
+            Encoding::HEX => self.bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+impl Display for Binary {
+    // Binary values are shown in base64, the encoding SET accepts by default.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.encode(Encoding::BASE64))
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.is_ascii() || !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod binary_tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_and_encode() {
+        let binary = Binary::decode("00ff10", Encoding::HEX).unwrap();
+        assert_eq!(binary.as_slice(), &[0x00, 0xff, 0x10]);
+        assert_eq!(binary.encode(Encoding::BASE64), "AP8Q");
+        assert_eq!(
+            Binary::decode("AP8Q", Encoding::BASE64)
+                .unwrap()
+                .encode(Encoding::HEX),
+            "00ff10"
+        );
+
+        assert!(Binary::decode("0f0", Encoding::HEX).is_err());
+        assert!(Binary::decode("zz", Encoding::HEX).is_err());
+        assert!(Binary::decode("not base64!", Encoding::BASE64).is_err());
+    }
+
+    #[test]
+    fn test_persisted_format() {
+        let binary = Binary::new(vec![0, 159, 146, 150]);
+
+        let json = serde_json::to_string(&binary).unwrap();
+        assert_eq!(json, r#""AJ+Slg==""#);
+
+        let loaded: Binary = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, binary);
+        assert!(serde_json::from_str::<Binary>(r#""%%""#).is_err());
+    }
+}
@@ -1,257 +1,1220 @@
 use tokio::sync::Mutex;
 
 use super::{
+    binary::{Binary, Encoding},
     data_type::DataTypes,
+    data_value::DataValue,
+    expiry::{expires_in, now_millis, remaining_seconds},
+    json_path::JsonPath,
     key::Key,
+    list::List,
+    payload::Payload,
+    set::Set,
+    sorted_set::{parse_score, RangeOptions, ScoreBound, SortedSet},
     store::{Store, StoreManager},
 };
 use crate::{
-    auth::{AuthManager, Permissions},
+    auth::{AuthManager, AuthStore, PermissionSet, Permissions},
     commands::{Command, CommandNames},
     config::Config,
+    error::KvError,
     persistence::{Persistence, PersistenceType},
     session::Session,
+    shutdown::ShutdownHandle,
 };
-use std::{str::FromStr, sync::Arc};
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// Namespaces that belong to the server. Keys in them can not be read or
+// written by clients.
+const RESERVED_NAMESPACES: [&str; 1] = ["_auth"];
+
+// How often a blocked BLPOP checks its lists for new elements.
+const BLPOP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Options that can follow the value of a SET.
+#[derive(Default)]
+struct SetOptions {
+    expires_at: Option<u64>,
+    // NX: only set the key if it does not exist yet.
+    if_absent: bool,
+    // XX: only set the key if it already exists.
+    if_present: bool,
+    // ENCODING: how the value of a BYTES is given, base64 by default.
+    encoding: Option<Encoding>,
+}
+
+// Options that can follow the key of a GET.
+#[derive(Default)]
+struct GetOptions {
+    with_version: bool,
+    // ENCODING: how a BYTES value is returned, base64 by default.
+    encoding: Option<Encoding>,
+}
 
 pub struct DataManager {
     pub data: Arc<Mutex<Store>>,
+    pub auth: Arc<Mutex<AuthStore>>,
     auth_manager: AuthManager,
     pub persistence: Persistence,
+    shutdown: ShutdownHandle,
 }
 
 impl DataManager {
     pub async fn new(
         data: Arc<Mutex<Store>>,
+        auth: Arc<Mutex<AuthStore>>,
         config: Arc<Mutex<Config>>,
-    ) -> Result<DataManager, String> {
-        let mut auth_manager = AuthManager::new(Arc::clone(&data)).await.unwrap();
+    ) -> Result<DataManager, KvError> {
+        let password_max_age = config.lock().await.get_password_max_age();
+        let mut auth_manager = AuthManager::new(Arc::clone(&auth), password_max_age);
 
         let (admin_username, admin_password) = config.lock().await.get_admin_user();
 
-        match auth_manager
-            .create_user(admin_username, admin_password, 255)
-            .await
-        {
-            Ok(_) => {}
-            Err(e) => println!("Error creating admin user: {}", e),
+        // Hashing the password is slow, so it is skipped when the admin
+        // already exists.
+        if !auth_manager.has_user(admin_username.clone()).await {
+            match auth_manager
+                .create_user(admin_username, admin_password, PermissionSet::all())
+                .await
+            {
+                Ok(_) | Err(KvError::AlreadyExists(_)) => {}
+                Err(e) => println!("Error creating admin user: {}", e),
+            }
         }
 
         let persistence = config.lock().await.persistence.clone();
         Ok(DataManager {
             data: data.clone(),
+            auth,
             auth_manager,
             persistence,
+            shutdown: ShutdownHandle::new(),
         })
     }
 
-    pub async fn save_to_file(&self) -> Result<(), String> {
+    // Lets blocking commands give up when the server shuts down.
+    pub fn with_shutdown(mut self, shutdown: ShutdownHandle) -> DataManager {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub async fn save_to_file(&self) -> Result<(), KvError> {
         let data = &self.data.lock().await;
         if self.persistence.get_type() == PersistenceType::InMemory {
             return Ok(());
         }
-        match self.persistence.save_store(data) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e),
-        }
+        self.persistence.save_store(data)?;
+        self.persistence.save_auth(&*self.auth.lock().await)
     }
 
+    // Like `execute`, with the payload in the text form of the line protocol.
     pub async fn handle_command(
         &mut self,
         cmd: Command,
         session: Session,
-    ) -> Result<(String, Session), String> {
-        match cmd.name {
-            CommandNames::SET => {
-                self.check_auth(&session, Permissions::SET).await?;
-                let key = Key::new(cmd.args[0].clone());
-                let value = cmd.args[1].clone();
-                let data_type = DataTypes::from_str(&cmd.args[2])?;
-                let result = self.set(key, value, data_type).await;
-                match result {
-                    Ok(_) => Ok(("OK".to_string(), session)),
-                    Err(e) => Err(e),
-                }
+    ) -> Result<(String, Session), KvError> {
+        self.execute(cmd, session)
+            .await
+            .map(|(payload, session)| (payload.to_string(), session))
+    }
+
+    pub async fn execute(
+        &mut self,
+        cmd: Command,
+        mut session: Session,
+    ) -> Result<(Payload, Session), KvError> {
+        if session.in_transaction()
+            && !matches!(
+                cmd.name,
+                CommandNames::MULTI | CommandNames::EXEC | CommandNames::DISCARD
+            )
+        {
+            if Self::data_permission(&cmd.name).is_none() {
+                return Err(KvError::BadArgs(format!(
+                    "{} can not be used in a transaction",
+                    cmd.name
+                )));
             }
-            CommandNames::GET => {
-                self.check_auth(&session, Permissions::GET).await?;
-                let key = Key::new(cmd.args[0].clone());
-                let result = self.get(key).await;
-                match result {
-                    Ok(value) => Ok((value, session)),
-                    Err(e) => Err(e),
-                }
+            session.queue_command(cmd);
+            return Ok(("QUEUED".into(), session));
+        }
+
+        match cmd.name {
+            CommandNames::SET
+            | CommandNames::GET
+            | CommandNames::DEL
+            | CommandNames::CREATE_STORE
+            | CommandNames::LIST_KEYS
+            | CommandNames::CAS
+            | CommandNames::INCR
+            | CommandNames::DECR
+            | CommandNames::INCRBY
+            | CommandNames::INCRBYFLOAT
+            | CommandNames::TOGGLE
+            | CommandNames::LPUSH
+            | CommandNames::RPUSH
+            | CommandNames::LPOP
+            | CommandNames::RPOP
+            | CommandNames::LRANGE
+            | CommandNames::LLEN
+            | CommandNames::LINDEX
+            | CommandNames::LREM
+            | CommandNames::SADD
+            | CommandNames::SREM
+            | CommandNames::SISMEMBER
+            | CommandNames::SMEMBERS
+            | CommandNames::SCARD
+            | CommandNames::SUNION
+            | CommandNames::SINTER
+            | CommandNames::SDIFF
+            | CommandNames::ZADD
+            | CommandNames::ZINCRBY
+            | CommandNames::ZSCORE
+            | CommandNames::ZRANK
+            | CommandNames::ZRANGE
+            | CommandNames::ZRANGEBYSCORE
+            | CommandNames::ZREM
+            | CommandNames::JSON_GET
+            | CommandNames::JSON_SET
+            | CommandNames::JSON_DEL
+            | CommandNames::JSON_ARRAPPEND
+            | CommandNames::EXPIRE
+            | CommandNames::TTL
+            | CommandNames::PERSIST => {
+                // Permissions are checked under the same lock as the write,
+                // as the one needed by DEL depends on what the key holds.
+                let mut data = self.data.lock().await;
+                self.check_command_auth(&mut data, &cmd, &session).await?;
+
+                let result = Self::apply_command(&mut data, &cmd)?;
+                Ok((result, session))
             }
-            CommandNames::DEL => {
-                self.check_auth(&session, Permissions::DEL).await?;
-                let key = Key::new(cmd.args[0].clone());
-                let result = self.del(key).await;
-                match result {
-                    Ok(_) => Ok(("OK".to_string(), session)),
-                    Err(e) => Err(e),
-                }
+            CommandNames::BLPOP => {
+                self.check_auth(&session, Permissions::DEL, Self::command_keys(&cmd))
+                    .await?;
+
+                let result = self.blpop(&cmd).await?;
+                Ok((result, session))
             }
             CommandNames::AUTH => {
                 let user_name = cmd.args[0].clone();
                 let password = cmd.args[1].clone();
                 let result = self.auth(user_name, password, session).await;
                 match result {
-                    Ok(session) => Ok(("OK".to_string(), session)),
+                    Ok(session) => Ok(("OK".into(), session)),
                     Err(e) => Err(e),
                 }
             }
             CommandNames::GET_USER => {
-                self.check_auth(&session, Permissions::USER_ADMIN).await?;
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
 
                 let username = cmd.args[0].clone();
                 match self.auth_manager.get_user(username).await {
-                    Some(user) => Ok((user.to_string(), session)),
-                    None => Err("User not found".to_string()),
+                    Some(user) => Ok((user.to_string().into(), session)),
+                    None => Err(KvError::NotFound("User not found".to_string())),
                 }
             }
             CommandNames::CREATE_USER => {
-                self.check_auth(&session, Permissions::USER_ADMIN).await?;
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
                 let user_name = cmd.args[0].clone();
                 let password = cmd.args[1].clone();
-                let permissions = u8::from_str(&cmd.args[2]).unwrap();
+                let permissions = Self::parse_permissions_arg(&cmd.args[2])?;
 
-                let permissions_to_set = Permissions::from_u8(permissions.clone());
+                let permissions_to_set = permissions.iter();
                 for p in permissions_to_set {
-                    self.check_permission(&session, p).await?;
+                    self.check_permission(&session, p, &[]).await?;
                 }
 
                 let result = self.create_user(user_name, password, permissions).await;
                 match result {
-                    Ok(_) => Ok(("OK".to_string(), session)),
+                    Ok(_) => Ok(("OK".into(), session)),
                     Err(e) => Err(e),
                 }
             }
             CommandNames::DELETE_USER => {
-                self.check_auth(&session, Permissions::USER_ADMIN).await?;
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
                 let user_name = cmd.args[0].clone();
                 let result = self.delete_user(user_name).await;
                 match result {
-                    Ok(_) => Ok(("OK".to_string(), session)),
+                    Ok(_) => Ok(("OK".into(), session)),
                     Err(e) => Err(e),
                 }
             }
+            CommandNames::PASSWORD => {
+                // Works while a password change is pending, so the flag of
+                // the session is not checked here.
+                if !session.is_authenticated
+                    || !self.auth_manager.has_user(session.username.clone()).await
+                {
+                    return Err(KvError::NotAuthenticated);
+                }
+
+                let old_password = cmd.args[0].clone();
+                let new_password = cmd.args[1].clone();
+                self.auth_manager
+                    .change_password(session.username.clone(), old_password, new_password)
+                    .await?;
+
+                Ok(("OK".into(), session.set_must_change_password(false)))
+            }
+            CommandNames::SET_PASSWORD => {
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
+
+                let username = cmd.args[0].clone();
+
+                // Resetting the password of a user hands over everything it
+                // may do, so the caller has to hold all of it already.
+                if let Some(user) = self.auth_manager.get_effective_user(username.clone()).await {
+                    for permission in user.permissions.iter() {
+                        self.check_permission(&session, permission, &[]).await?;
+                    }
+                    for rule in &user.acl {
+                        for permission in rule.permissions.iter() {
+                            self.check_permission(
+                                &session,
+                                permission,
+                                std::slice::from_ref(&rule.pattern),
+                            )
+                            .await?;
+                        }
+                    }
+                }
+
+                let password = cmd.args[1].clone();
+                let must_change_password = cmd.args.len() == 3;
+                self.auth_manager
+                    .set_password(username, password, must_change_password)
+                    .await?;
+
+                Ok(("OK".into(), session))
+            }
             CommandNames::GRANT => {
-                self.check_auth(&session, Permissions::USER_ADMIN).await?;
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
                 // also check for other permissions here!
 
                 let username = cmd.args[0].clone();
-                let permissions = u8::from_str(&cmd.args[1]).unwrap();
+                let permissions = Self::parse_permissions_arg(&cmd.args[1])?;
+                let scope = Self::parse_acl_scope(&cmd.args[2..], permissions)?;
 
-                let permissions_to_set = Permissions::from_u8(permissions.clone());
+                // Only keys the granting user can access can be granted.
+                let permissions_to_set = permissions.iter();
                 for p in permissions_to_set {
-                    self.check_permission(&session, p).await?;
+                    self.check_permission(&session, p, &cmd.args[2..]).await?;
                 }
 
                 self.auth_manager
-                    .grant_permissions(username, permissions)
+                    .grant_permissions(username, permissions, scope)
                     .await?;
 
-                Ok(("OK".to_string(), session))
+                Ok(("OK".into(), session))
             }
             CommandNames::REVOKE => {
-                self.check_auth(&session, Permissions::USER_ADMIN).await?;
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
 
                 let username = cmd.args[0].clone();
-                let permission = u8::from_str(&cmd.args[1]).unwrap();
+                let permission = Self::parse_permissions_arg(&cmd.args[1])?;
+                let scope = Self::parse_acl_scope(&cmd.args[2..], permission)?;
 
-                let permissions_to_revoke = Permissions::from_u8(permission.clone());
+                let permissions_to_revoke = permission.iter();
                 for p in permissions_to_revoke {
-                    self.check_permission(&session, p).await?;
+                    self.check_permission(&session, p, &cmd.args[2..]).await?;
                 }
 
                 self.auth_manager
-                    .revoke_permission(username, permission)
+                    .revoke_permission(username, permission, scope)
                     .await?;
 
-                Ok(("OK".to_string(), session))
+                Ok(("OK".into(), session))
             }
-            CommandNames::CREATE_STORE => {
-                self.check_auth(&session, Permissions::SET).await?;
-                let store_name = Key::new(cmd.args[0].clone());
+            CommandNames::CREATE_ROLE => {
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
 
-                let result = self.create_store(store_name).await;
-                match result {
-                    Ok(_) => Ok(("OK".to_string(), session)),
-                    Err(e) => Err(e),
+                let name = cmd.args[0].clone();
+                let permissions = Self::parse_permissions_arg(&cmd.args[1])?;
+                let scope = Self::parse_acl_scope(&cmd.args[2..], permissions)?;
+
+                for p in permissions.iter() {
+                    self.check_permission(&session, p, &cmd.args[2..]).await?;
                 }
+
+                self.auth_manager
+                    .create_role(name, permissions, scope)
+                    .await?;
+
+                Ok(("OK".into(), session))
             }
-            CommandNames::LIST_KEYS => {
-                self.check_auth(&session, Permissions::GET).await?;
+            CommandNames::DELETE_ROLE => {
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
+
+                self.auth_manager.delete_role(cmd.args[0].clone()).await?;
+
+                Ok(("OK".into(), session))
+            }
+            CommandNames::ASSIGN_ROLE => {
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
+
+                let username = cmd.args[0].clone();
+                let name = cmd.args[1].clone();
+
+                // Assigning a role grants its permissions, so the same rules
+                // as for GRANT apply.
+                let role = self
+                    .auth_manager
+                    .get_role(name.clone())
+                    .await
+                    .ok_or_else(|| KvError::NotFound("Role not found".to_string()))?;
+                for p in role.permissions.iter() {
+                    self.check_permission(&session, p, &[]).await?;
+                }
+                for rule in &role.acl {
+                    for p in rule.permissions.iter() {
+                        self.check_permission(&session, p, std::slice::from_ref(&rule.pattern))
+                            .await?;
+                    }
+                }
+
+                self.auth_manager.assign_role(username, name).await?;
+
+                Ok(("OK".into(), session))
+            }
+            CommandNames::UNASSIGN_ROLE => {
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
+
+                let username = cmd.args[0].clone();
+                let name = cmd.args[1].clone();
+                self.auth_manager.unassign_role(username, name).await?;
+
+                Ok(("OK".into(), session))
+            }
+            CommandNames::LIST_ROLES => {
+                self.check_auth(&session, Permissions::USER_ADMIN, &[])
+                    .await?;
+
+                Ok((Payload::list(self.auth_manager.list_roles().await), session))
+            }
+            CommandNames::MULTI => {
+                if session.in_transaction() {
+                    return Err(KvError::BadArgs(
+                        "MULTI calls can not be nested".to_string(),
+                    ));
+                }
+                session.begin_transaction();
+                Ok(("OK".into(), session))
+            }
+            CommandNames::EXEC => {
+                let transaction = session
+                    .end_transaction()
+                    .ok_or_else(|| KvError::BadArgs("EXEC without MULTI".to_string()))?;
+                if transaction.failed {
+                    return Err(KvError::BadArgs(
+                        "Transaction discarded because of previous errors".to_string(),
+                    ));
+                }
+
+                let result = self.exec(&transaction.commands, &session).await?;
+                Ok((result, session))
+            }
+            CommandNames::DISCARD => {
+                session
+                    .end_transaction()
+                    .ok_or_else(|| KvError::BadArgs("DISCARD without MULTI".to_string()))?;
+                Ok(("OK".into(), session))
+            }
+        }
+    }
+
+    // Permission needed by the commands that only read or write the store.
+    // These are the commands that can be queued in a transaction.
+    fn data_permission(name: &CommandNames) -> Option<Permissions> {
+        match name {
+            CommandNames::SET
+            | CommandNames::CAS
+            | CommandNames::INCR
+            | CommandNames::DECR
+            | CommandNames::INCRBY
+            | CommandNames::INCRBYFLOAT
+            | CommandNames::TOGGLE
+            | CommandNames::LPUSH
+            | CommandNames::RPUSH
+            | CommandNames::SADD
+            | CommandNames::ZADD
+            | CommandNames::ZINCRBY
+            | CommandNames::JSON_SET
+            | CommandNames::JSON_ARRAPPEND
+            | CommandNames::EXPIRE
+            | CommandNames::PERSIST => Some(Permissions::SET),
+            CommandNames::CREATE_STORE => Some(Permissions::STORE_CREATE),
+            CommandNames::LIST_KEYS => Some(Permissions::LIST),
+            CommandNames::GET
+            | CommandNames::TTL
+            | CommandNames::LRANGE
+            | CommandNames::LLEN
+            | CommandNames::LINDEX
+            | CommandNames::SISMEMBER
+            | CommandNames::SMEMBERS
+            | CommandNames::SCARD
+            | CommandNames::SUNION
+            | CommandNames::SINTER
+            | CommandNames::SDIFF
+            | CommandNames::ZSCORE
+            | CommandNames::ZRANK
+            | CommandNames::ZRANGE
+            | CommandNames::ZRANGEBYSCORE
+            | CommandNames::JSON_GET => Some(Permissions::GET),
+            // Popping an element removes it from the list.
+            CommandNames::DEL
+            | CommandNames::LPOP
+            | CommandNames::RPOP
+            | CommandNames::LREM
+            | CommandNames::BLPOP
+            | CommandNames::SREM
+            | CommandNames::ZREM
+            | CommandNames::JSON_DEL => Some(Permissions::DEL),
+            _ => None,
+        }
+    }
+
+    // Same as `data_permission`, except that deleting a whole store needs
+    // STORE_DELETE rather than DEL.
+    fn command_permission(data: &mut Store, cmd: &Command) -> Option<Permissions> {
+        if cmd.name == CommandNames::DEL {
+            let key = Key::new(cmd.args[0].clone());
+            if data.get_version(key.clone()).is_err() && data.get_store(key).is_ok() {
+                return Some(Permissions::STORE_DELETE);
+            }
+        }
+        Self::data_permission(&cmd.name)
+    }
+
+    async fn check_command_auth(
+        &self,
+        data: &mut Store,
+        cmd: &Command,
+        session: &Session,
+    ) -> Result<(), KvError> {
+        let permission = Self::command_permission(data, cmd).ok_or_else(|| {
+            KvError::BadArgs(format!("{} can not be used in a transaction", cmd.name))
+        })?;
+        self.check_auth(session, permission, Self::command_keys(cmd))
+            .await
+    }
+
+    fn apply_command(data: &mut Store, cmd: &Command) -> Result<Payload, KvError> {
+        Self::check_reserved_keys(cmd)?;
 
+        match cmd.name {
+            CommandNames::LRANGE => {
+                let start = Self::parse_index_arg(&cmd.args[1])?;
+                let stop = Self::parse_index_arg(&cmd.args[2])?;
+
+                let result = data.read_value(Key::new(cmd.args[0].clone()), |value| {
+                    Ok(value.as_list()?.range(start, stop))
+                });
+                Self::or_if_missing(result, Vec::new()).map(Payload::list)
+            }
+            CommandNames::BLPOP => {
+                // Inside a transaction BLPOP does not block: it pops from the
+                // first list that has an element or fails.
+                let keys = &cmd.args[..cmd.args.len() - 1];
+                for key in keys {
+                    let result = data.update_value(Key::new(key.clone()), None, |value| {
+                        value
+                            .as_list_mut()?
+                            .pop_front()
+                            .ok_or_else(|| KvError::NotFound("List is empty".to_string()))
+                    });
+                    match result {
+                        Ok(item) => return Ok(Payload::list([key.clone(), item])),
+                        Err(KvError::NotFound(_)) => continue,
+                        Err(e) => return Err(e),
+                    }
+                }
+                Err(KvError::NotFound("No list has an element".to_string()))
+            }
+            CommandNames::SMEMBERS => {
+                Ok(Payload::list(Self::read_set(data, &cmd.args[0])?.members()))
+            }
+            CommandNames::SUNION | CommandNames::SINTER | CommandNames::SDIFF => {
+                // The keys may be in different stores, e.g. `a:b:tags` and
+                // `c:tags`.
+                let mut result = Self::read_set(data, &cmd.args[0])?;
+                for key in &cmd.args[1..] {
+                    let other = Self::read_set(data, key)?;
+                    result = match cmd.name {
+                        CommandNames::SUNION => result.union(&other),
+                        CommandNames::SINTER => result.intersection(&other),
+                        _ => result.difference(&other),
+                    };
+                }
+                Ok(Payload::list(result.members()))
+            }
+            CommandNames::ZRANGE => {
+                let start = Self::parse_index_arg(&cmd.args[1])?;
+                let stop = Self::parse_index_arg(&cmd.args[2])?;
+                let options =
+                    RangeOptions::parse(&cmd.args[3..], false).map_err(KvError::BadArgs)?;
+
+                let result = data.read_value(Key::new(cmd.args[0].clone()), |value| {
+                    let range = value.as_sorted_set()?.range(start, stop);
+                    Ok(Self::scored_members(range, options.with_scores))
+                });
+                Self::or_if_missing(result, Vec::new()).map(Payload::list)
+            }
+            CommandNames::ZRANGEBYSCORE => {
+                let min = ScoreBound::from_str(&cmd.args[1]).map_err(KvError::BadArgs)?;
+                let max = ScoreBound::from_str(&cmd.args[2]).map_err(KvError::BadArgs)?;
+                let options =
+                    RangeOptions::parse(&cmd.args[3..], true).map_err(KvError::BadArgs)?;
+
+                let result = data.read_value(Key::new(cmd.args[0].clone()), |value| {
+                    let range = value
+                        .as_sorted_set()?
+                        .range_by_score(min, max, options.limit);
+                    Ok(Self::scored_members(range, options.with_scores))
+                });
+                Self::or_if_missing(result, Vec::new()).map(Payload::list)
+            }
+            CommandNames::LIST_KEYS => {
                 let key = cmd.args[0].clone();
 
                 if key == "." {
-                    let data = self.data.lock().await;
-                    return Ok((data.list_keys()?, session));
+                    Ok(Payload::list(data.store_names()))
                 } else {
-                    let mut data = self.data.lock().await;
-                    let store = data.get_store(Key::new(key.clone()));
-                    match store {
-                        Ok(store) => {
-                            return Ok((store.list_keys()?, session));
+                    match data.get_store(Key::new(key)) {
+                        Ok(store) => Ok(Payload::list(store.store_names())),
+                        Err(_) => Err(KvError::NotFound("Invalid store".to_string())),
+                    }
+                }
+            }
+            _ => Self::apply_value_command(data, cmd).map(Payload::Value),
+        }
+    }
+
+    // Commands whose reply is a single value.
+    fn apply_value_command(data: &mut Store, cmd: &Command) -> Result<String, KvError> {
+        match cmd.name {
+            CommandNames::SET => {
+                let key = Key::new(cmd.args[0].clone());
+                let options = Self::parse_set_options(&cmd.args[3..])?;
+                let value = match options.encoding {
+                    Some(encoding) => Self::binary_value(&cmd.args[1], &cmd.args[2], encoding)?,
+                    None => DataValue::from_type_name(cmd.args[1].clone(), &cmd.args[2])?,
+                };
+
+                if options.if_absent || options.if_present {
+                    let exists = Self::current_version(data, &key)? > 0;
+                    if options.if_absent && exists {
+                        return Err(KvError::AlreadyExists(format!(
+                            "Key already exists: {}",
+                            key.to_str()
+                        )));
+                    }
+                    if options.if_present && !exists {
+                        return Err(KvError::key_not_found());
+                    }
+                }
+
+                data.set_data_value(key.clone(), value)?;
+                if options.expires_at.is_some() {
+                    data.set_expiry(key, options.expires_at)?;
+                }
+                Ok("OK".to_string())
+            }
+            CommandNames::GET => {
+                let key = Key::new(cmd.args[0].clone());
+                let options = Self::parse_get_options(&cmd.args[1..])?;
+
+                // WITHVERSION prefixes the value with its version on a line
+                // of its own. Stores have no version.
+                let version = match options.with_version {
+                    true => Some(data.get_version(key.clone())?),
+                    false => None,
+                };
+                let value = match options.encoding {
+                    Some(encoding) => data
+                        .read_value(key.clone(), |value| Ok(value.as_bytes()?.encode(encoding)))?,
+                    None => data.get(key)?,
+                };
+
+                match version {
+                    Some(version) => Ok(format!("{}\n{}", version, value)),
+                    None => Ok(value),
+                }
+            }
+            CommandNames::CAS => {
+                let key = Key::new(cmd.args[0].clone());
+                let expected = Self::parse_version_arg(&cmd.args[1])?;
+                let value = DataValue::from_type_name(cmd.args[2].clone(), &cmd.args[3])?;
+
+                let current = Self::current_version(data, &key)?;
+                if current != expected {
+                    return Err(KvError::VersionMismatch(format!(
+                        "Expected version {} but found {}",
+                        expected, current
+                    )));
+                }
+
+                data.set_data_value(key.clone(), value)?;
+                Ok(data.get_version(key)?.to_string())
+            }
+            CommandNames::DEL => match data.del(Key::new(cmd.args[0].clone())) {
+                Ok(_) => Ok("OK".to_string()),
+                Err(_) => Err(KvError::key_not_found()),
+            },
+            CommandNames::INCR | CommandNames::DECR | CommandNames::INCRBY => {
+                let delta = match cmd.name {
+                    CommandNames::INCR => 1,
+                    CommandNames::DECR => -1,
+                    _ => i64::from_str(&cmd.args[1])
+                        .map_err(|_| KvError::BadArgs("Invalid increment".to_string()))?,
+                };
+
+                // Counters that do not exist yet start at 0.
+                let initial = DataValue::new("0".to_string(), DataTypes::INT)?;
+                let value =
+                    data.update_value(Key::new(cmd.args[0].clone()), Some(initial), |value| {
+                        value.incr_by(delta)
+                    })?;
+                Ok(value.to_string())
+            }
+            CommandNames::INCRBYFLOAT => {
+                let delta = f64::from_str(&cmd.args[1])
+                    .map_err(|_| KvError::BadArgs("Invalid increment".to_string()))?;
+
+                let initial = DataValue::new("0".to_string(), DataTypes::FLOAT)?;
+                let value =
+                    data.update_value(Key::new(cmd.args[0].clone()), Some(initial), |value| {
+                        value.incr_by_float(delta)
+                    })?;
+                Ok(value.to_string())
+            }
+            CommandNames::TOGGLE => {
+                let value =
+                    data.update_value(Key::new(cmd.args[0].clone()), None, DataValue::toggle)?;
+                Ok(value.to_string())
+            }
+            CommandNames::LPUSH | CommandNames::RPUSH => {
+                let values = &cmd.args[1..];
+
+                // Pushing to a missing key creates an untyped list.
+                let initial = DataValue::new_list(List::new(DataTypes::STRING));
+                let length =
+                    data.update_value(Key::new(cmd.args[0].clone()), Some(initial), |value| {
+                        let list = value.as_list_mut()?;
+                        match cmd.name {
+                            CommandNames::LPUSH => list.push_front(values),
+                            _ => list.push_back(values),
+                        }
+                    })?;
+                Ok(length.to_string())
+            }
+            CommandNames::LPOP | CommandNames::RPOP => {
+                data.update_value(Key::new(cmd.args[0].clone()), None, |value| {
+                    let list = value.as_list_mut()?;
+                    let item = match cmd.name {
+                        CommandNames::LPOP => list.pop_front(),
+                        _ => list.pop_back(),
+                    };
+                    item.ok_or_else(|| KvError::NotFound("List is empty".to_string()))
+                })
+            }
+            CommandNames::LLEN => {
+                let result = data.read_value(Key::new(cmd.args[0].clone()), |value| {
+                    Ok(value.as_list()?.len().to_string())
+                });
+                Self::or_if_missing(result, "0".to_string())
+            }
+            CommandNames::LINDEX => {
+                let index = Self::parse_index_arg(&cmd.args[1])?;
+
+                data.read_value(Key::new(cmd.args[0].clone()), |value| {
+                    value
+                        .as_list()?
+                        .get(index)
+                        .cloned()
+                        .ok_or_else(|| KvError::NotFound("Index out of range".to_string()))
+                })
+            }
+            CommandNames::LREM => {
+                let count = i64::from_str(&cmd.args[1])
+                    .map_err(|_| KvError::BadArgs("Invalid count".to_string()))?;
+                let element = &cmd.args[2];
+
+                let result = data.update_value(Key::new(cmd.args[0].clone()), None, |value| {
+                    Ok(value.as_list_mut()?.remove(count, element).to_string())
+                });
+                Self::or_if_missing(result, "0".to_string())
+            }
+            CommandNames::SADD => {
+                let initial = DataValue::new_set(Set::new());
+                let added =
+                    data.update_value(Key::new(cmd.args[0].clone()), Some(initial), |value| {
+                        Ok(value.as_set_mut()?.add(&cmd.args[1..]))
+                    })?;
+                Ok(added.to_string())
+            }
+            CommandNames::SREM => {
+                let result = data.update_value(Key::new(cmd.args[0].clone()), None, |value| {
+                    Ok(value.as_set_mut()?.remove(&cmd.args[1..]).to_string())
+                });
+                Self::or_if_missing(result, "0".to_string())
+            }
+            CommandNames::SISMEMBER => {
+                let result = data.read_value(Key::new(cmd.args[0].clone()), |value| {
+                    Ok(value.as_set()?.contains(&cmd.args[1]).to_string())
+                });
+                Self::or_if_missing(result, "false".to_string())
+            }
+            CommandNames::SCARD => Ok(Self::read_set(data, &cmd.args[0])?.len().to_string()),
+            CommandNames::ZADD => {
+                let mut members = Vec::new();
+                for pair in cmd.args[1..].chunks(2) {
+                    let score = parse_score(&pair[0]).map_err(KvError::BadArgs)?;
+                    members.push((pair[1].clone(), score));
+                }
+
+                let initial = DataValue::new_sorted_set(SortedSet::new());
+                let added =
+                    data.update_value(Key::new(cmd.args[0].clone()), Some(initial), |value| {
+                        let zset = value.as_sorted_set_mut()?;
+                        Ok(members
+                            .into_iter()
+                            .filter(|(member, score)| zset.insert(member.clone(), *score))
+                            .count())
+                    })?;
+                Ok(added.to_string())
+            }
+            CommandNames::ZINCRBY => {
+                let delta = parse_score(&cmd.args[1])
+                    .map_err(|_| KvError::BadArgs("Invalid increment".to_string()))?;
+                let member = &cmd.args[2];
+
+                let initial = DataValue::new_sorted_set(SortedSet::new());
+                let score =
+                    data.update_value(Key::new(cmd.args[0].clone()), Some(initial), |value| {
+                        value
+                            .as_sorted_set_mut()?
+                            .increment(member, delta)
+                            .map_err(KvError::BadArgs)
+                    })?;
+                Ok(score.to_string())
+            }
+            CommandNames::ZSCORE | CommandNames::ZRANK => {
+                let member = &cmd.args[1];
+
+                data.read_value(Key::new(cmd.args[0].clone()), |value| {
+                    let zset = value.as_sorted_set()?;
+                    let result = match cmd.name {
+                        CommandNames::ZSCORE => zset.score(member).map(|score| score.to_string()),
+                        _ => zset.rank(member).map(|rank| rank.to_string()),
+                    };
+                    result.ok_or_else(|| KvError::NotFound("Member not found".to_string()))
+                })
+            }
+            CommandNames::ZREM => {
+                let result = data.update_value(Key::new(cmd.args[0].clone()), None, |value| {
+                    let zset = value.as_sorted_set_mut()?;
+                    let removed = cmd.args[1..]
+                        .iter()
+                        .filter(|member| zset.remove(member))
+                        .count();
+                    Ok(removed.to_string())
+                });
+                Self::or_if_missing(result, "0".to_string())
+            }
+            CommandNames::JSON_GET => {
+                let path = Self::parse_json_path_arg(cmd.args.get(1))?;
+
+                data.read_value(Key::new(cmd.args[0].clone()), |value| {
+                    path.get(value.as_json()?)
+                        .map(|node| node.to_string())
+                        .ok_or_else(|| KvError::NotFound(format!("Path not found: {}", path)))
+                })
+            }
+            CommandNames::JSON_SET => {
+                let path = Self::parse_json_path_arg(cmd.args.get(1))?;
+                let node = Self::parse_json_arg(&cmd.args[2])?;
+
+                // Only a whole document can create the key.
+                let initial = path
+                    .is_root()
+                    .then(|| DataValue::new_json(serde_json::Value::Null));
+                data.update_value(Key::new(cmd.args[0].clone()), initial, |value| {
+                    path.set(value.as_json_mut()?, node)
+                        .map_err(KvError::NotFound)
+                })?;
+                Ok("OK".to_string())
+            }
+            CommandNames::JSON_DEL => {
+                let key = Key::new(cmd.args[0].clone());
+                let path = Self::parse_json_path_arg(cmd.args.get(1))?;
+
+                // Deleting the root deletes the whole document.
+                if path.is_root() {
+                    let result = data.read_value(key.clone(), |value| {
+                        value.as_json().map(|_| "1".to_string())
+                    });
+                    if result.is_ok() {
+                        data.del(key)?;
+                    }
+                    return Self::or_if_missing(result, "0".to_string());
+                }
+                let result = data.update_value(key, None, |value| {
+                    Ok(u8::from(path.delete(value.as_json_mut()?)).to_string())
+                });
+                Self::or_if_missing(result, "0".to_string())
+            }
+            CommandNames::JSON_ARRAPPEND => {
+                let path = Self::parse_json_path_arg(cmd.args.get(1))?;
+                let nodes = cmd.args[2..]
+                    .iter()
+                    .map(|arg| Self::parse_json_arg(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let len =
+                    data.update_value(Key::new(cmd.args[0].clone()), None, |value| {
+                        match path.get_mut(value.as_json_mut()?) {
+                            Some(serde_json::Value::Array(array)) => {
+                                array.extend(nodes);
+                                Ok(array.len())
+                            }
+                            Some(_) => Err(KvError::WrongType(format!(
+                                "Value at {} is not an array",
+                                path
+                            ))),
+                            None => Err(KvError::NotFound(format!("Path not found: {}", path))),
                         }
-                        Err(_) => {
-                            return Err("Invalid store".to_string());
+                    })?;
+                Ok(len.to_string())
+            }
+            CommandNames::CREATE_STORE => {
+                data.set_store(Key::new(cmd.args[0].clone()))?;
+                Ok("OK".to_string())
+            }
+            CommandNames::EXPIRE => {
+                let key = Key::new(cmd.args[0].clone());
+                let seconds = Self::parse_seconds_arg(&cmd.args[1])?;
+
+                data.set_expiry(key, Some(expires_in(seconds)))?;
+                Ok("OK".to_string())
+            }
+            CommandNames::TTL => {
+                // Keys without an expiry report -1.
+                match data.get_expiry(Key::new(cmd.args[0].clone()))? {
+                    Some(expires_at) => Ok(remaining_seconds(expires_at, now_millis()).to_string()),
+                    None => Ok("-1".to_string()),
+                }
+            }
+            CommandNames::PERSIST => {
+                data.set_expiry(Key::new(cmd.args[0].clone()), None)?;
+                Ok("OK".to_string())
+            }
+            _ => Err(KvError::BadArgs(format!(
+                "{} can not be used in a transaction",
+                cmd.name
+            ))),
+        }
+    }
+
+    // Runs the queued commands under a single lock of the store. The keys each
+    // command writes are saved before it runs, and if any of them fails they
+    // are put back so the store is left as it was before EXEC.
+    async fn exec(&self, commands: &[Command], session: &Session) -> Result<Payload, KvError> {
+        let mut data = self.data.lock().await;
+        for cmd in commands {
+            self.check_command_auth(&mut data, cmd, session).await?;
+        }
+
+        let mut undo_log = Vec::new();
+        let mut replies = Vec::new();
+        for cmd in commands {
+            // Earlier commands can change the permission a command needs,
+            // e.g. a DEL of a store created in the same transaction.
+            let result = match self.check_command_auth(&mut data, cmd, session).await {
+                Ok(()) => {
+                    if !Self::is_read_only(cmd) {
+                        for key in Self::command_keys(cmd) {
+                            let key = Key::new(key.clone());
+                            undo_log.push((key.clone(), data.entry(key)));
                         }
                     }
+                    Self::apply_command(&mut data, cmd)
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(reply) => replies.push(reply),
+                Err(e) => {
+                    for (key, entry) in undo_log.into_iter().rev() {
+                        data.restore_entry(key, entry);
+                    }
+                    return Err(e);
                 }
             }
         }
+
+        Ok(Payload::List(replies))
+    }
+
+    // Commands that leave both the store and the users untouched.
+    pub fn is_read_only(cmd: &Command) -> bool {
+        matches!(
+            cmd.name,
+            CommandNames::AUTH
+                | CommandNames::GET_USER
+                | CommandNames::LIST_ROLES
+                | CommandNames::MULTI
+                | CommandNames::DISCARD
+        ) || matches!(
+            Self::data_permission(&cmd.name),
+            Some(Permissions::GET | Permissions::LIST)
+        )
+    }
+
+    // Pops the first element of the first non-empty list, waiting for one to
+    // be pushed when they are all empty. A timeout of 0 waits until an
+    // element arrives or the server shuts down.
+    async fn blpop(&self, cmd: &Command) -> Result<Payload, KvError> {
+        let timeout = Self::parse_timeout_arg(&cmd.args[cmd.args.len() - 1])?;
+        let deadline = (timeout > 0).then(|| Instant::now() + Duration::from_secs(timeout));
+
+        loop {
+            match Self::apply_command(&mut *self.data.lock().await, cmd) {
+                Err(KvError::NotFound(_)) => {}
+                result => return result,
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(BLPOP_POLL_INTERVAL) => {}
+                _ = self.shutdown.cancelled() => break,
+            }
+        }
+
+        Err(KvError::NotFound(
+            "Timed out waiting for an element".to_string(),
+        ))
+    }
+
+    // Keys a data command reads or writes.
+    fn command_keys(cmd: &Command) -> &[String] {
+        match cmd.name {
+            CommandNames::SUNION | CommandNames::SINTER | CommandNames::SDIFF => &cmd.args[..],
+            CommandNames::BLPOP => &cmd.args[..cmd.args.len() - 1],
+            _ => &cmd.args[..cmd.args.len().min(1)],
+        }
+    }
+
+    // Refuses keys that are in a reserved namespace, or under one, e.g.
+    // `_auth` or `_auth:users:admin`.
+    fn check_reserved_keys(cmd: &Command) -> Result<(), KvError> {
+        for key in Self::command_keys(cmd) {
+            let namespace = key.split(':').next().unwrap_or_default();
+            if RESERVED_NAMESPACES.contains(&namespace) {
+                return Err(KvError::BadArgs(format!("Forbidden key name! {}", key)));
+            }
+        }
+        Ok(())
+    }
+
+    // Copy of the set at `key`. Missing keys are read as empty sets.
+    fn read_set(data: &mut Store, key: &str) -> Result<Set, KvError> {
+        match data.read_value(Key::new(key.to_string()), |value| value.as_set().cloned()) {
+            Err(KvError::NotFound(_)) => Ok(Set::new()),
+            result => result,
+        }
+    }
+
+    // Members of a range, each followed by its score when WITHSCORES was
+    // given.
+    fn scored_members(range: Vec<(String, f64)>, with_scores: bool) -> Vec<String> {
+        range
+            .into_iter()
+            .flat_map(|(member, score)| match with_scores {
+                true => vec![member, score.to_string()],
+                false => vec![member],
+            })
+            .collect()
+    }
+
+    // Reads of missing collections behave like reads of empty ones.
+    fn or_if_missing<T>(result: Result<T, KvError>, empty: T) -> Result<T, KvError> {
+        match result {
+            Err(KvError::NotFound(_)) => Ok(empty),
+            result => result,
+        }
+    }
+
+    // The path is optional and defaults to the whole document.
+    fn parse_json_path_arg(arg: Option<&String>) -> Result<JsonPath, KvError> {
+        match arg {
+            Some(arg) => JsonPath::from_str(arg).map_err(KvError::BadArgs),
+            None => Ok(JsonPath::from_str("$").unwrap()),
+        }
+    }
+
+    fn parse_json_arg(arg: &str) -> Result<serde_json::Value, KvError> {
+        serde_json::from_str(arg).map_err(|_| KvError::BadArgs("Invalid JSON document".to_string()))
+    }
+
+    fn parse_index_arg(arg: &str) -> Result<i64, KvError> {
+        i64::from_str(arg).map_err(|_| KvError::BadArgs("Invalid index".to_string()))
+    }
+
+    fn parse_timeout_arg(arg: &str) -> Result<u64, KvError> {
+        u64::from_str(arg).map_err(|_| KvError::BadArgs("Invalid timeout".to_string()))
+    }
+
+    fn parse_seconds_arg(arg: &str) -> Result<u64, KvError> {
+        u64::from_str(arg).map_err(|_| KvError::BadArgs("Invalid expire time".to_string()))
+    }
+
+    fn parse_version_arg(arg: &str) -> Result<u64, KvError> {
+        u64::from_str(arg).map_err(|_| KvError::BadArgs("Invalid version".to_string()))
+    }
+
+    fn parse_set_options(options: &[String]) -> Result<SetOptions, KvError> {
+        let mut set_options = SetOptions::default();
+        let mut options = options.iter();
+
+        while let Some(option) = options.next() {
+            match option.as_str() {
+                "EX" => {
+                    let seconds = options.next().map(String::as_str).unwrap_or_default();
+                    set_options.expires_at = Some(expires_in(Self::parse_seconds_arg(seconds)?));
+                }
+                "NX" => set_options.if_absent = true,
+                "XX" => set_options.if_present = true,
+                "ENCODING" => {
+                    set_options.encoding = Some(Self::parse_encoding_arg(options.next())?)
+                }
+                _ => return Err(KvError::BadArgs("Invalid SET option".to_string())),
+            }
+        }
+
+        Ok(set_options)
+    }
+
+    fn parse_get_options(options: &[String]) -> Result<GetOptions, KvError> {
+        let mut get_options = GetOptions::default();
+        let mut options = options.iter();
+
+        while let Some(option) = options.next() {
+            match option.as_str() {
+                "WITHVERSION" => get_options.with_version = true,
+                "ENCODING" => {
+                    get_options.encoding = Some(Self::parse_encoding_arg(options.next())?)
+                }
+                _ => return Err(KvError::BadArgs("Invalid GET option".to_string())),
+            }
+        }
+
+        Ok(get_options)
+    }
+
+    fn parse_encoding_arg(arg: Option<&String>) -> Result<Encoding, KvError> {
+        let arg = arg.map(String::as_str).unwrap_or_default();
+        Encoding::from_str(arg).map_err(KvError::BadArgs)
+    }
+
+    // BYTES value given in an explicit encoding.
+    fn binary_value(
+        value: &str,
+        type_name: &str,
+        encoding: Encoding,
+    ) -> Result<DataValue, KvError> {
+        if type_name != DataTypes::BYTES.to_string() {
+            return Err(KvError::BadArgs(
+                "ENCODING can only be used with BYTES values".to_string(),
+            ));
+        }
+        let bytes = Binary::decode(value, encoding).map_err(KvError::WrongType)?;
+        Ok(DataValue::new_bytes(bytes))
+    }
+
+    // Version of the value stored at `key`, 0 if there is none.
+    fn current_version(data: &mut Store, key: &Key) -> Result<u64, KvError> {
+        match data.get_version(key.clone()) {
+            Ok(version) => Ok(version),
+            Err(KvError::NotFound(_)) => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn parse_permissions_arg(arg: &str) -> Result<PermissionSet, KvError> {
+        PermissionSet::from_str(arg).map_err(KvError::BadArgs)
+    }
+
+    // ACL rules only cover the keys they match, user administration is
+    // never scoped.
+    fn parse_acl_scope(
+        args: &[String],
+        permissions: PermissionSet,
+    ) -> Result<Option<&str>, KvError> {
+        let pattern = args.first().map(String::as_str);
+        if pattern.is_some() {
+            if let Some(permission) = permissions.iter().find(|p| !p.is_key_permission()) {
+                return Err(KvError::BadArgs(format!(
+                    "{} can not be scoped to keys",
+                    permission
+                )));
+            }
+        }
+        Ok(pattern)
     }
 
     async fn check_permission(
         &self,
         session: &Session,
         permission: Permissions,
-    ) -> Result<(), String> {
+        keys: &[String],
+    ) -> Result<(), KvError> {
         if !self
             .auth_manager
-            .check_permission(session.username.clone(), permission)
+            .check_permission(session.username.clone(), permission, keys)
             .await
         {
-            return Err("User does not have permission".to_string());
+            return Err(KvError::NoPermission);
         }
         Ok(())
     }
 
-    async fn check_auth(&self, session: &Session, permission: Permissions) -> Result<(), String> {
+    // Keys are checked against the ACL rules of the user when the permission
+    // is not granted globally.
+    async fn check_auth(
+        &self,
+        session: &Session,
+        permission: Permissions,
+        keys: &[String],
+    ) -> Result<(), KvError> {
         if !session.is_authenticated {
-            return Err("User not authenticated".to_string());
+            return Err(KvError::NotAuthenticated);
         }
         if !self.auth_manager.has_user(session.username.clone()).await {
-            return Err("User not authenticated".to_string());
+            return Err(KvError::NotAuthenticated);
         }
-        self.check_permission(session, permission).await?;
-        Ok(())
-    }
-
-    async fn set(
-        &mut self,
-        key: Key,
-        value: String,
-        data_type: DataTypes,
-    ) -> Result<String, String> {
-        let mut data = self.data.lock().await;
-        data.set(key, value, data_type)?;
-        Ok("OK".to_string())
-    }
-
-    async fn get(&mut self, key: Key) -> Result<String, String> {
-        let mut data = self.data.lock().await;
-        data.get(key)
-    }
-
-    async fn del(&mut self, key: Key) -> Result<String, String> {
-        let mut data = self.data.lock().await;
-        match data.del(key) {
-            Ok(_) => Ok("OK".to_string()),
-            Err(_) => Err("Key not found".to_string()),
+        if session.must_change_password {
+            return Err(KvError::PasswordChangeRequired);
         }
+        self.check_permission(session, permission, keys).await?;
+        Ok(())
     }
 
     async fn auth(
@@ -259,7 +1222,7 @@ impl DataManager {
         user_name: String,
         password: String,
         session: Session,
-    ) -> Result<Session, String> {
+    ) -> Result<Session, KvError> {
         self.auth_manager
             .login_user(user_name, password, session)
             .await
@@ -269,20 +1232,14 @@ impl DataManager {
         &mut self,
         user_name: String,
         password: String,
-        permissions: u8,
-    ) -> Result<String, String> {
+        permissions: PermissionSet,
+    ) -> Result<String, KvError> {
         self.auth_manager
             .create_user(user_name, password, permissions)
             .await
     }
 
-    async fn delete_user(&mut self, user_name: String) -> Result<String, String> {
+    async fn delete_user(&mut self, user_name: String) -> Result<String, KvError> {
         self.auth_manager.delete_user(user_name).await
     }
-
-    async fn create_store(&mut self, store_name: Key) -> Result<String, String> {
-        let mut data = self.data.lock().await;
-        data.set_store(store_name)?;
-        Ok("OK".to_string())
-    }
 }
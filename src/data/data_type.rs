@@ -2,6 +2,8 @@ use std::{fmt::Display, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 
+use super::typed_value::TypedValue;
+
 #[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum DataTypes {
     STRING,
@@ -9,6 +11,11 @@ pub enum DataTypes {
     FLOAT,
     BOOL,
     STORE,
+    LIST,
+    SET,
+    ZSET,
+    JSON,
+    BYTES,
 }
 
 impl FromStr for DataTypes {
@@ -21,6 +28,11 @@ impl FromStr for DataTypes {
             "FLOAT" => Ok(DataTypes::FLOAT),
             "BOOL" => Ok(DataTypes::BOOL),
             "STORE" => Ok(DataTypes::STORE),
+            "LIST" => Ok(DataTypes::LIST),
+            "SET" => Ok(DataTypes::SET),
+            "ZSET" => Ok(DataTypes::ZSET),
+            "JSON" => Ok(DataTypes::JSON),
+            "BYTES" => Ok(DataTypes::BYTES),
             _ => Err("Invalid data type".to_string()),
         }
     }
@@ -34,27 +46,42 @@ impl Display for DataTypes {
             DataTypes::FLOAT => write!(f, "FLOAT"),
             DataTypes::BOOL => write!(f, "BOOL"),
             DataTypes::STORE => write!(f, "STORE"),
+            DataTypes::LIST => write!(f, "LIST"),
+            DataTypes::SET => write!(f, "SET"),
+            DataTypes::ZSET => write!(f, "ZSET"),
+            DataTypes::JSON => write!(f, "JSON"),
+            DataTypes::BYTES => write!(f, "BYTES"),
         }
     }
 }
 
 impl DataTypes {
+    // Parses a type name that may also give the type of the elements of a
+    // LIST, e.g. `LIST<INT>`.
+    pub fn parse_with_element_type(name: &str) -> Result<(DataTypes, Option<DataTypes>), String> {
+        let element_type = match name.strip_prefix("LIST<") {
+            Some(element_type) => element_type,
+            None => return Ok((DataTypes::from_str(name)?, None)),
+        };
+
+        match element_type.strip_suffix('>').map(DataTypes::from_str) {
+            Some(Ok(
+                DataTypes::STORE
+                | DataTypes::LIST
+                | DataTypes::SET
+                | DataTypes::ZSET
+                | DataTypes::JSON
+                | DataTypes::BYTES,
+            )) => Err("Invalid list element type".to_string()),
+            Some(Ok(element_type)) => Ok((DataTypes::LIST, Some(element_type))),
+            _ => Err("Invalid data type".to_string()),
+        }
+    }
+
     pub fn validate_data(&self, value: &str) -> Result<(), String> {
         match self {
-            DataTypes::STRING => Ok(()),
-            DataTypes::INT => match value.parse::<i64>() {
-                Ok(_) => Ok(()),
-                Err(_) => Err("Invalid data type".to_string()),
-            },
-            DataTypes::FLOAT => match value.parse::<f64>() {
-                Ok(_) => Ok(()),
-                Err(_) => Err("Invalid data type".to_string()),
-            },
-            DataTypes::BOOL => match value.parse::<bool>() {
-                Ok(_) => Ok(()),
-                Err(_) => Err("Invalid data type".to_string()),
-            },
             DataTypes::STORE => Ok(()),
+            data_type => TypedValue::parse(value, *data_type).map(|_| ()),
         }
     }
 }
@@ -144,6 +171,36 @@ mod data_types_tests {
         assert_eq!(result, Err("Invalid data type".to_string()));
     }
 
+    #[test]
+    fn test_data_type_parse_with_element_type() {
+        assert_eq!(
+            DataTypes::parse_with_element_type("INT"),
+            Ok((DataTypes::INT, None))
+        );
+        assert_eq!(
+            DataTypes::parse_with_element_type("LIST"),
+            Ok((DataTypes::LIST, None))
+        );
+        assert_eq!(
+            DataTypes::parse_with_element_type("LIST<FLOAT>"),
+            Ok((DataTypes::LIST, Some(DataTypes::FLOAT)))
+        );
+        assert!(DataTypes::parse_with_element_type("LIST<STORE>").is_err());
+        assert!(DataTypes::parse_with_element_type("LIST<INT").is_err());
+    }
+
+    #[test]
+    fn test_data_type_validate_json() {
+        let data_type = DataTypes::JSON;
+        let value = r#"{"name": "alice", "tags": [1, 2]}"#;
+        let result = data_type.validate_data(value);
+        assert_eq!(result, Ok(()));
+
+        let value = "{name: alice}";
+        let result = data_type.validate_data(value);
+        assert_eq!(result, Err("Invalid JSON document".to_string()));
+    }
+
     #[test]
     fn test_data_type_reparse_data_type() {
         let data_type = DataTypes::from_str("STRING").unwrap();
@@ -1,7 +1,9 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 use std::fmt::Display;
 
-use crate::data::DataTypes;
+use crate::data::{Binary, DataTypes, List, Set, SortedSet, TypedValue};
+use crate::error::KvError;
 
 use super::key::Key;
 
@@ -13,29 +15,324 @@ pub trait Data {
         key: Key,
         value: String,
         data_type: DataTypes,
-    ) -> Result<String, String>;
+    ) -> Result<String, KvError>;
 
-    fn get_value(&self, key: Key) -> Result<String, String>;
+    fn get_value(&self, key: Key) -> Result<String, KvError>;
 
-    fn del_value(&mut self, key: &Key) -> Result<String, String>;
+    // The value in its native form, for code embedding the store.
+    fn get_typed_value(&self, key: Key) -> Result<&TypedValue, KvError>;
+
+    fn del_value(&mut self, key: &Key) -> Result<String, KvError>;
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone)]
 pub struct DataValue {
-    pub value: String,
+    value: TypedValue,
+    expires_at: Option<u64>,
+    // Increased every time the value is replaced.
+    version: u64,
+}
+
+fn initial_version() -> u64 {
+    1
+}
+
+fn is_initial_version(version: &u64) -> bool {
+    *version == initial_version()
+}
+
+// Layout of a value in snapshots. Scalars are kept as their canonical text
+// in `value` and collections in a field of their own, so snapshots written
+// before values were typed still load.
+#[derive(Deserialize)]
+struct StoredValue {
+    #[serde(default)]
+    value: String,
     data_type: DataTypes,
+    #[serde(default)]
+    expires_at: Option<u64>,
+    #[serde(default = "initial_version")]
+    version: u64,
+    #[serde(default)]
+    list: Option<List>,
+    #[serde(default)]
+    set: Option<Set>,
+    #[serde(default)]
+    zset: Option<SortedSet>,
+    #[serde(default)]
+    json: Option<Value>,
+    #[serde(default)]
+    bytes: Option<Binary>,
+}
+
+// Same layout as `StoredValue`, borrowing the collections so writing a
+// snapshot does not copy them.
+#[derive(Serialize)]
+struct StoredValueRef<'a> {
+    value: String,
+    data_type: DataTypes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
+    #[serde(skip_serializing_if = "is_initial_version")]
+    version: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    list: Option<&'a List>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set: Option<&'a Set>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    zset: Option<&'a SortedSet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    json: Option<&'a Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes: Option<&'a Binary>,
+}
+
+impl Serialize for DataValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut stored = StoredValueRef {
+            value: String::new(),
+            data_type: self.value.data_type(),
+            expires_at: self.expires_at,
+            version: self.version,
+            list: None,
+            set: None,
+            zset: None,
+            json: None,
+            bytes: None,
+        };
+        match &self.value {
+            TypedValue::List(list) => stored.list = Some(list),
+            TypedValue::Set(set) => stored.set = Some(set),
+            TypedValue::SortedSet(zset) => stored.zset = Some(zset),
+            TypedValue::Json(json) => stored.json = Some(json),
+            TypedValue::Bytes(bytes) => stored.bytes = Some(bytes),
+            scalar => stored.value = scalar.to_string(),
+        }
+        stored.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DataValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DataValue, D::Error> {
+        let stored = StoredValue::deserialize(deserializer)?;
+
+        let value = match (
+            stored.list,
+            stored.set,
+            stored.zset,
+            stored.json,
+            stored.bytes,
+        ) {
+            (Some(list), ..) => TypedValue::List(list),
+            (_, Some(set), ..) => TypedValue::Set(set),
+            (_, _, Some(zset), ..) => TypedValue::SortedSet(zset),
+            (.., Some(json), _) => TypedValue::Json(json),
+            (.., Some(bytes)) => TypedValue::Bytes(bytes),
+            _ => TypedValue::parse(&stored.value, stored.data_type)
+                .map_err(serde::de::Error::custom)?,
+        };
+        if value.data_type() != stored.data_type {
+            return Err(serde::de::Error::custom(format!(
+                "Value is not of type {}",
+                stored.data_type
+            )));
+        }
+
+        Ok(DataValue {
+            value,
+            expires_at: stored.expires_at,
+            version: stored.version,
+        })
+    }
 }
 
 impl DataValue {
-    pub fn new(value: String, data_type: DataTypes) -> Result<DataValue, String> {
-        data_type.validate_data(&value)?;
-        Ok(DataValue { value, data_type })
+    pub fn new(value: String, data_type: DataTypes) -> Result<DataValue, KvError> {
+        let value = TypedValue::parse(&value, data_type).map_err(KvError::WrongType)?;
+        Ok(DataValue::from_typed_value(value))
+    }
+
+    pub fn from_typed_value(value: TypedValue) -> DataValue {
+        DataValue {
+            value,
+            expires_at: None,
+            version: initial_version(),
+        }
+    }
+
+    pub fn new_list(list: List) -> DataValue {
+        DataValue::from_typed_value(TypedValue::List(list))
+    }
+
+    pub fn new_set(set: Set) -> DataValue {
+        DataValue::from_typed_value(TypedValue::Set(set))
+    }
+
+    pub fn new_sorted_set(zset: SortedSet) -> DataValue {
+        DataValue::from_typed_value(TypedValue::SortedSet(zset))
+    }
+
+    pub fn new_json(json: Value) -> DataValue {
+        DataValue::from_typed_value(TypedValue::Json(json))
+    }
+
+    pub fn new_bytes(bytes: Binary) -> DataValue {
+        DataValue::from_typed_value(TypedValue::Bytes(bytes))
+    }
+
+    // Like `new`, but also accepts typed lists such as `LIST<INT>`.
+    pub fn from_type_name(value: String, type_name: &str) -> Result<DataValue, KvError> {
+        match DataTypes::parse_with_element_type(type_name).map_err(KvError::BadArgs)? {
+            (DataTypes::LIST, Some(element_type)) => {
+                let list = List::parse(&value, element_type).map_err(KvError::WrongType)?;
+                Ok(DataValue::new_list(list))
+            }
+            (data_type, _) => DataValue::new(value, data_type),
+        }
+    }
+
+    pub fn value(&self) -> &TypedValue {
+        &self.value
+    }
+
+    fn wrong_type(data_type: DataTypes) -> KvError {
+        KvError::WrongType(format!("Value is not of type {}", data_type))
+    }
+
+    pub fn as_list(&self) -> Result<&List, KvError> {
+        match &self.value {
+            TypedValue::List(list) => Ok(list),
+            _ => Err(Self::wrong_type(DataTypes::LIST)),
+        }
+    }
+
+    pub fn as_list_mut(&mut self) -> Result<&mut List, KvError> {
+        match &mut self.value {
+            TypedValue::List(list) => Ok(list),
+            _ => Err(Self::wrong_type(DataTypes::LIST)),
+        }
+    }
+
+    pub fn as_set(&self) -> Result<&Set, KvError> {
+        match &self.value {
+            TypedValue::Set(set) => Ok(set),
+            _ => Err(Self::wrong_type(DataTypes::SET)),
+        }
+    }
+
+    pub fn as_set_mut(&mut self) -> Result<&mut Set, KvError> {
+        match &mut self.value {
+            TypedValue::Set(set) => Ok(set),
+            _ => Err(Self::wrong_type(DataTypes::SET)),
+        }
+    }
+
+    pub fn as_sorted_set(&self) -> Result<&SortedSet, KvError> {
+        match &self.value {
+            TypedValue::SortedSet(zset) => Ok(zset),
+            _ => Err(Self::wrong_type(DataTypes::ZSET)),
+        }
+    }
+
+    pub fn as_sorted_set_mut(&mut self) -> Result<&mut SortedSet, KvError> {
+        match &mut self.value {
+            TypedValue::SortedSet(zset) => Ok(zset),
+            _ => Err(Self::wrong_type(DataTypes::ZSET)),
+        }
+    }
+
+    pub fn as_json(&self) -> Result<&Value, KvError> {
+        match &self.value {
+            TypedValue::Json(json) => Ok(json),
+            _ => Err(Self::wrong_type(DataTypes::JSON)),
+        }
+    }
+
+    pub fn as_json_mut(&mut self) -> Result<&mut Value, KvError> {
+        match &mut self.value {
+            TypedValue::Json(json) => Ok(json),
+            _ => Err(Self::wrong_type(DataTypes::JSON)),
+        }
+    }
+
+    pub fn as_bytes(&self) -> Result<&Binary, KvError> {
+        match &self.value {
+            TypedValue::Bytes(bytes) => Ok(bytes),
+            _ => Err(Self::wrong_type(DataTypes::BYTES)),
+        }
+    }
+
+    // Lists, sets and sorted sets are removed once their last element is.
+    pub fn is_empty_collection(&self) -> bool {
+        match &self.value {
+            TypedValue::List(list) => list.is_empty(),
+            TypedValue::Set(set) => set.is_empty(),
+            TypedValue::SortedSet(zset) => zset.is_empty(),
+            _ => false,
+        }
+    }
+
+    // Name of the type as it is written in commands, e.g. `LIST<INT>`.
+    fn type_name(&self) -> String {
+        match &self.value {
+            TypedValue::List(list) if list.element_type() != DataTypes::STRING => {
+                format!("{}<{}>", DataTypes::LIST, list.element_type())
+            }
+            value => value.data_type().to_string(),
+        }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+
+    pub fn incr_by(&mut self, delta: i64) -> Result<i64, KvError> {
+        let TypedValue::Int(value) = &mut self.value else {
+            return Err(Self::wrong_type(DataTypes::INT));
+        };
+        *value = value
+            .checked_add(delta)
+            .ok_or_else(|| KvError::BadArgs("Increment or decrement would overflow".to_string()))?;
+        Ok(*value)
+    }
+
+    pub fn incr_by_float(&mut self, delta: f64) -> Result<f64, KvError> {
+        let TypedValue::Float(value) = &mut self.value else {
+            return Err(Self::wrong_type(DataTypes::FLOAT));
+        };
+        if !(*value + delta).is_finite() {
+            return Err(KvError::BadArgs(
+                "Increment would produce NaN or Infinity".to_string(),
+            ));
+        }
+        *value += delta;
+        Ok(*value)
+    }
+
+    pub fn toggle(&mut self) -> Result<bool, KvError> {
+        let TypedValue::Bool(value) = &mut self.value else {
+            return Err(Self::wrong_type(DataTypes::BOOL));
+        };
+        *value = !*value;
+        Ok(*value)
+    }
+
+    pub fn expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
+
+    pub fn set_expires_at(&mut self, expires_at: Option<u64>) {
+        self.expires_at = expires_at;
     }
 }
 
 impl Data for DataValue {
     fn get_type(&self) -> DataTypes {
-        self.data_type.clone()
+        self.value.data_type()
     }
 
     fn set_value(
@@ -43,26 +340,58 @@ impl Data for DataValue {
         key: Key,
         value: String,
         data_type: DataTypes,
-    ) -> Result<String, String> {
-        if data_type != self.data_type {
-            return Err("Invalid data type".to_string());
+    ) -> Result<String, KvError> {
+        if data_type != self.get_type() {
+            return Err(KvError::WrongType("Invalid data type".to_string()));
         }
-        self.value = value;
+        self.value = TypedValue::parse(&value, data_type).map_err(KvError::WrongType)?;
         Ok(format!("{} set", key.to_str()))
     }
 
-    fn get_value(&self, _key: Key) -> Result<String, String> {
-        Ok(self.value.clone())
+    fn get_value(&self, _key: Key) -> Result<String, KvError> {
+        Ok(self.value.to_string())
     }
 
-    fn del_value(&mut self, _key: &Key) -> Result<String, String> {
-        self.value = "".to_string();
+    fn get_typed_value(&self, _key: Key) -> Result<&TypedValue, KvError> {
+        Ok(&self.value)
+    }
+
+    fn del_value(&mut self, _key: &Key) -> Result<String, KvError> {
+        self.value = TypedValue::String(String::new());
         Ok("Deleted".to_string())
     }
 }
 
 impl Display for DataValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} ({})", self.value, self.data_type)
+        write!(f, "{} ({})", self.value, self.type_name())
+    }
+}
+
+#[cfg(test)]
+mod data_value_tests {
+    use super::*;
+
+    #[test]
+    fn test_load_snapshot_value() {
+        // Layout written before values were typed.
+        let value: DataValue =
+            serde_json::from_str(r#"{"value":"1.50","data_type":"FLOAT","version":3}"#).unwrap();
+        assert_eq!(value.value(), &TypedValue::Float(1.5));
+        assert_eq!(value.version(), 3);
+
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"value":"1.5","data_type":"FLOAT","version":3}"#);
+
+        let value: DataValue =
+            serde_json::from_str(r#"{"value":"","data_type":"SET","set":{"members":["a"]}}"#)
+                .unwrap();
+        assert_eq!(value.to_string(), r#"["a"] (SET)"#);
+
+        assert!(serde_json::from_str::<DataValue>(r#"{"value":"x","data_type":"INT"}"#).is_err());
+        assert!(
+            serde_json::from_str::<DataValue>(r#"{"value":"","data_type":"INT","json":1}"#)
+                .is_err()
+        );
     }
 }
@@ -0,0 +1,43 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Expiry times are stored as milliseconds since the Unix epoch so they stay
+// meaningful after the store is saved and loaded again.
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub fn expires_in(seconds: u64) -> u64 {
+    now_millis().saturating_add(seconds.saturating_mul(1000))
+}
+
+pub fn is_expired(expires_at: Option<u64>, now: u64) -> bool {
+    matches!(expires_at, Some(expires_at) if expires_at <= now)
+}
+
+// Remaining time to live in whole seconds, rounded up.
+pub fn remaining_seconds(expires_at: u64, now: u64) -> u64 {
+    expires_at.saturating_sub(now).div_ceil(1000)
+}
+
+#[cfg(test)]
+mod expiry_tests {
+    use super::*;
+
+    #[test]
+    fn test_is_expired() {
+        assert!(!is_expired(None, 1000));
+        assert!(!is_expired(Some(1001), 1000));
+        assert!(is_expired(Some(1000), 1000));
+        assert!(is_expired(Some(999), 1000));
+    }
+
+    #[test]
+    fn test_remaining_seconds() {
+        assert_eq!(remaining_seconds(11_000, 1000), 10);
+        assert_eq!(remaining_seconds(1500, 1000), 1);
+        assert_eq!(remaining_seconds(1000, 2000), 0);
+    }
+}
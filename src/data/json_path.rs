@@ -0,0 +1,229 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Field(String),
+    Index(i64),
+}
+
+// Path to a node of a JSON document, e.g. `$`, `$.user.name`, `$.tags[0]`,
+// `$.tags[-1]` or `$["a key"]`. `$` is the whole document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+impl FromStr for JsonPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid JSON path: {}", s);
+
+        let mut rest = s.strip_prefix('$').ok_or_else(invalid)?;
+        let mut segments = Vec::new();
+
+        while !rest.is_empty() {
+            if let Some(field) = rest.strip_prefix('.') {
+                let end = field.find(['.', '[']).unwrap_or(field.len());
+                if end == 0 {
+                    return Err(invalid());
+                }
+                segments.push(Segment::Field(field[..end].to_string()));
+                rest = &field[end..];
+            } else if let Some(quoted) = rest.strip_prefix("[\"") {
+                let end = quoted.find("\"]").ok_or_else(invalid)?;
+                segments.push(Segment::Field(quoted[..end].to_string()));
+                rest = &quoted[end + 2..];
+            } else if let Some(index) = rest.strip_prefix('[') {
+                let end = index.find(']').ok_or_else(invalid)?;
+                let index = index[..end].parse().map_err(|_| invalid())?;
+                segments.push(Segment::Index(index));
+                rest = &rest[end + 2..];
+            } else {
+                return Err(invalid());
+            }
+        }
+
+        Ok(JsonPath { segments })
+    }
+}
+
+impl Display for JsonPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "$")?;
+        for segment in &self.segments {
+            match segment {
+                Segment::Field(field) => write!(f, ".{}", field)?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Position of `index` in an array of `len` elements. Negative indexes count
+// from the end, -1 being the last element.
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    usize::try_from(index).ok().filter(|index| *index < len)
+}
+
+fn child<'a>(value: &'a Value, segment: &Segment) -> Option<&'a Value> {
+    match (value, segment) {
+        (Value::Object(object), Segment::Field(field)) => object.get(field),
+        (Value::Array(array), Segment::Index(index)) => {
+            array.get(resolve_index(*index, array.len())?)
+        }
+        _ => None,
+    }
+}
+
+fn child_mut<'a>(value: &'a mut Value, segment: &Segment) -> Option<&'a mut Value> {
+    match (value, segment) {
+        (Value::Object(object), Segment::Field(field)) => object.get_mut(field),
+        (Value::Array(array), Segment::Index(index)) => {
+            let index = resolve_index(*index, array.len())?;
+            array.get_mut(index)
+        }
+        _ => None,
+    }
+}
+
+impl JsonPath {
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn get<'a>(&self, document: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(document, |value, segment| child(value, segment))
+    }
+
+    pub fn get_mut<'a>(&self, document: &'a mut Value) -> Option<&'a mut Value> {
+        self.segments
+            .iter()
+            .try_fold(document, |value, segment| child_mut(value, segment))
+    }
+
+    // Splits the path into the path of the parent node and the last segment.
+    fn split_last(&self) -> Option<(JsonPath, &Segment)> {
+        let (last, parent) = self.segments.split_last()?;
+        Some((
+            JsonPath {
+                segments: parent.to_vec(),
+            },
+            last,
+        ))
+    }
+
+    // Replaces the node at the path. The parent has to exist already: a new
+    // field can be added to an object, but arrays only accept existing
+    // indexes.
+    pub fn set(&self, document: &mut Value, new_value: Value) -> Result<(), String> {
+        let (parent, last) = match self.split_last() {
+            Some(split) => split,
+            None => {
+                *document = new_value;
+                return Ok(());
+            }
+        };
+
+        let not_found = || format!("Path not found: {}", self);
+        match (parent.get_mut(document).ok_or_else(not_found)?, last) {
+            (Value::Object(object), Segment::Field(field)) => {
+                object.insert(field.clone(), new_value);
+                Ok(())
+            }
+            (Value::Array(array), Segment::Index(index)) => {
+                let index = resolve_index(*index, array.len()).ok_or_else(not_found)?;
+                array[index] = new_value;
+                Ok(())
+            }
+            _ => Err(not_found()),
+        }
+    }
+
+    // Removes the node at the path, returning `false` if there is none. The
+    // root can not be removed this way.
+    pub fn delete(&self, document: &mut Value) -> bool {
+        let (parent, last) = match self.split_last() {
+            Some(split) => split,
+            None => return false,
+        };
+
+        match (parent.get_mut(document), last) {
+            (Some(Value::Object(object)), Segment::Field(field)) => object.remove(field).is_some(),
+            (Some(Value::Array(array)), Segment::Index(index)) => {
+                match resolve_index(*index, array.len()) {
+                    Some(index) => {
+                        array.remove(index);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod json_path_tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn path(path: &str) -> JsonPath {
+        JsonPath::from_str(path).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(path("$").is_root());
+        assert_eq!(path("$.user.tags[-1]").to_string(), "$.user.tags[-1]");
+        assert_eq!(path(r#"$["a key"].b"#).to_string(), "$.a key.b");
+
+        for invalid in ["", "user", "$.", "$..a", "$[x]", "$[1", "$a"] {
+            assert!(JsonPath::from_str(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let mut document = json!({"user": {"name": "alice", "tags": ["a", "b"]}});
+
+        assert_eq!(path("$.user.tags[1]").get(&document), Some(&json!("b")));
+        assert_eq!(path("$.user.tags[-2]").get(&document), Some(&json!("a")));
+        assert_eq!(path("$.user.age").get(&document), None);
+
+        path("$.user.age").set(&mut document, json!(30)).unwrap();
+        path("$.user.tags[0]")
+            .set(&mut document, json!("c"))
+            .unwrap();
+        assert!(path("$.user.tags[5]").set(&mut document, json!(1)).is_err());
+        assert!(path("$.other.field").set(&mut document, json!(1)).is_err());
+
+        assert_eq!(
+            document,
+            json!({"user": {"name": "alice", "age": 30, "tags": ["c", "b"]}})
+        );
+
+        path("$").set(&mut document, json!([])).unwrap();
+        assert_eq!(document, json!([]));
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut document = json!({"a": 1, "b": [1, 2, 3]});
+
+        assert!(path("$.a").delete(&mut document));
+        assert!(!path("$.a").delete(&mut document));
+        assert!(path("$.b[-1]").delete(&mut document));
+        assert!(!path("$").delete(&mut document));
+
+        assert_eq!(document, json!({"b": [1, 2]}));
+    }
+}
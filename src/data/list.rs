@@ -0,0 +1,235 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::DataTypes;
+use crate::error::KvError;
+
+fn default_element_type() -> DataTypes {
+    DataTypes::STRING
+}
+
+fn is_default_element_type(element_type: &DataTypes) -> bool {
+    *element_type == default_element_type()
+}
+
+// Elements of a LIST value. A list created as e.g. `LIST<INT>` only accepts
+// elements of that type, other lists hold plain strings.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct List {
+    items: VecDeque<String>,
+    #[serde(
+        default = "default_element_type",
+        skip_serializing_if = "is_default_element_type"
+    )]
+    element_type: DataTypes,
+}
+
+impl List {
+    pub fn new(element_type: DataTypes) -> List {
+        List {
+            items: VecDeque::new(),
+            element_type,
+        }
+    }
+
+    // Reads a list written as a JSON array, e.g. `[1, 2, 3]` or `["a", "b"]`.
+    pub fn parse(value: &str, element_type: DataTypes) -> Result<List, String> {
+        let values = serde_json::from_str::<Vec<Value>>(value)
+            .map_err(|_| "Invalid list. Expected a JSON array".to_string())?;
+
+        let mut list = List::new(element_type);
+        for value in values {
+            let item = match value {
+                Value::String(item) => item,
+                Value::Number(item) => item.to_string(),
+                Value::Bool(item) => item.to_string(),
+                _ => return Err("Invalid list element".to_string()),
+            };
+            element_type.validate_data(&item)?;
+            list.items.push_back(item);
+        }
+        Ok(list)
+    }
+
+    pub fn element_type(&self) -> DataTypes {
+        self.element_type
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn validate(&self, values: &[String]) -> Result<(), KvError> {
+        for value in values {
+            self.element_type.validate_data(value).map_err(|_| {
+                KvError::WrongType(format!("Value is not a valid {}", self.element_type))
+            })?;
+        }
+        Ok(())
+    }
+
+    // Pushes the values one after the other to the head of the list, so they
+    // end up in reverse order. Returns the new length.
+    pub fn push_front(&mut self, values: &[String]) -> Result<usize, KvError> {
+        self.validate(values)?;
+        for value in values {
+            self.items.push_front(value.clone());
+        }
+        Ok(self.items.len())
+    }
+
+    pub fn push_back(&mut self, values: &[String]) -> Result<usize, KvError> {
+        self.validate(values)?;
+        self.items.extend(values.iter().cloned());
+        Ok(self.items.len())
+    }
+
+    pub fn pop_front(&mut self) -> Option<String> {
+        self.items.pop_front()
+    }
+
+    pub fn pop_back(&mut self) -> Option<String> {
+        self.items.pop_back()
+    }
+
+    // Negative indexes count from the end of the list, -1 being the last
+    // element.
+    fn resolve_index(&self, index: i64) -> i64 {
+        if index < 0 {
+            self.items.len() as i64 + index
+        } else {
+            index
+        }
+    }
+
+    pub fn get(&self, index: i64) -> Option<&String> {
+        let index = usize::try_from(self.resolve_index(index)).ok()?;
+        self.items.get(index)
+    }
+
+    // Elements from `start` to `stop`, both included. Out of range indexes
+    // are clamped to the list.
+    pub fn range(&self, start: i64, stop: i64) -> Vec<String> {
+        let start = self.resolve_index(start).max(0);
+        let stop = self.resolve_index(stop).min(self.items.len() as i64 - 1);
+        if start > stop {
+            return Vec::new();
+        }
+
+        self.items
+            .range(start as usize..=stop as usize)
+            .cloned()
+            .collect()
+    }
+
+    // Removes up to `count` elements equal to `value`, starting from the head
+    // for a positive count and from the tail for a negative one. A count of 0
+    // removes all of them. Returns how many elements were removed.
+    pub fn remove(&mut self, count: i64, value: &str) -> usize {
+        let limit = match count {
+            0 => usize::MAX,
+            _ => count.unsigned_abs() as usize,
+        };
+
+        let mut removed = 0;
+        let mut kept = VecDeque::with_capacity(self.items.len());
+        let items = std::mem::take(&mut self.items);
+
+        if count < 0 {
+            for item in items.into_iter().rev() {
+                if removed < limit && item == value {
+                    removed += 1;
+                } else {
+                    kept.push_front(item);
+                }
+            }
+        } else {
+            for item in items {
+                if removed < limit && item == value {
+                    removed += 1;
+                } else {
+                    kept.push_back(item);
+                }
+            }
+        }
+
+        self.items = kept;
+        removed
+    }
+}
+
+impl Display for List {
+    // Lists are shown as JSON arrays, the same format SET accepts.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let items = self
+            .items
+            .iter()
+            .map(|item| match self.element_type {
+                DataTypes::STRING => Value::String(item.clone()).to_string(),
+                _ => item.clone(),
+            })
+            .collect::<Vec<String>>();
+        write!(f, "[{}]", items.join(","))
+    }
+}
+
+#[cfg(test)]
+mod list_tests {
+    use super::*;
+
+    fn list(items: &[&str]) -> List {
+        let mut list = List::new(DataTypes::STRING);
+        for item in items {
+            list.items.push_back(item.to_string());
+        }
+        list
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let list = List::parse("[1, 2, 3]", DataTypes::INT).unwrap();
+        assert_eq!(list.to_string(), "[1,2,3]");
+
+        let list = List::parse(r#"["a", 1, true]"#, DataTypes::STRING).unwrap();
+        assert_eq!(list.to_string(), r#"["a","1","true"]"#);
+
+        assert!(List::parse(r#"["a"]"#, DataTypes::INT).is_err());
+        assert!(List::parse("[[1]]", DataTypes::STRING).is_err());
+        assert!(List::parse("1", DataTypes::STRING).is_err());
+    }
+
+    #[test]
+    fn test_range() {
+        let list = list(&["a", "b", "c", "d"]);
+
+        assert_eq!(list.range(0, -1), vec!["a", "b", "c", "d"]);
+        assert_eq!(list.range(1, 2), vec!["b", "c"]);
+        assert_eq!(list.range(-2, 10), vec!["c", "d"]);
+        assert!(list.range(3, 1).is_empty());
+        assert!(list.range(5, 10).is_empty());
+        assert_eq!(list.get(-1), Some(&"d".to_string()));
+        assert_eq!(list.get(4), None);
+        assert_eq!(list.get(-5), None);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut removed = list(&["a", "b", "a", "c", "a"]);
+        assert_eq!(removed.remove(2, "a"), 2);
+        assert_eq!(removed.range(0, -1), vec!["b", "c", "a"]);
+
+        let mut removed = list(&["a", "b", "a", "c", "a"]);
+        assert_eq!(removed.remove(-2, "a"), 2);
+        assert_eq!(removed.range(0, -1), vec!["a", "b", "c"]);
+
+        let mut removed = list(&["a", "b", "a", "c", "a"]);
+        assert_eq!(removed.remove(0, "a"), 3);
+        assert_eq!(removed.range(0, -1), vec!["b", "c"]);
+    }
+}
@@ -1,12 +1,29 @@
+mod binary;
 mod data_manager;
 mod data_type;
 mod data_value;
+mod expiry;
+mod json_path;
 mod key;
+mod list;
+mod payload;
+mod set;
+mod sorted_set;
 mod store;
+mod typed_value;
+pub use binary::{Binary, Encoding};
 pub use data_manager::*;
 pub use data_type::*;
+pub use data_value::{Data, DataValue};
+pub use expiry::{expires_in, now_millis, remaining_seconds};
+pub use json_path::JsonPath;
 pub use key::*;
-pub use store::{Store, StoreManager};
+pub use list::List;
+pub use payload::Payload;
+pub use set::Set;
+pub use sorted_set::{parse_score, RangeOptions, ScoreBound, SortedSet};
+pub use store::{Entry, Store, StoreManager};
+pub use typed_value::TypedValue;
 
 #[cfg(test)]
 mod test;
@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+// Result of a successful command. Replies with several elements keep them
+// apart, so protocols that frame each element on its own (like RESP) do not
+// have to split a joined string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Payload {
+    Value(String),
+    List(Vec<Payload>),
+}
+
+impl Payload {
+    pub fn list<I: IntoIterator<Item = String>>(items: I) -> Payload {
+        Payload::List(items.into_iter().map(Payload::Value).collect())
+    }
+}
+
+impl From<String> for Payload {
+    fn from(value: String) -> Self {
+        Payload::Value(value)
+    }
+}
+
+impl From<&str> for Payload {
+    fn from(value: &str) -> Self {
+        Payload::Value(value.to_string())
+    }
+}
+
+// The line protocol puts each element on a line of its own.
+impl Display for Payload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Payload::Value(value) => write!(f, "{}", value),
+            Payload::List(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "{}", items.join("\n"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod payload_tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_to_string() {
+        assert_eq!(Payload::from("OK").to_string(), "OK");
+        assert_eq!(
+            Payload::list(["a".to_string(), "b".to_string()]).to_string(),
+            "a\nb"
+        );
+        assert_eq!(Payload::list([]).to_string(), "");
+        assert_eq!(
+            Payload::List(vec!["OK".into(), Payload::list(["a".to_string()])]).to_string(),
+            "OK\na"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Members of a SET value. Members are kept sorted so they are always listed
+// in the same order.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct Set {
+    members: BTreeSet<String>,
+}
+
+impl Set {
+    pub fn new() -> Set {
+        Set::default()
+    }
+
+    // Reads a set written as a JSON array of strings, e.g. `["a", "b"]`.
+    // Duplicates are dropped.
+    pub fn parse(value: &str) -> Result<Set, String> {
+        let members = serde_json::from_str::<BTreeSet<String>>(value)
+            .map_err(|_| "Invalid set. Expected a JSON array of strings".to_string())?;
+        Ok(Set { members })
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn contains(&self, member: &str) -> bool {
+        self.members.contains(member)
+    }
+
+    pub fn members(&self) -> Vec<String> {
+        self.members.iter().cloned().collect()
+    }
+
+    // Returns how many of the members were not in the set yet.
+    pub fn add(&mut self, members: &[String]) -> usize {
+        members
+            .iter()
+            .filter(|member| self.members.insert(member.to_string()))
+            .count()
+    }
+
+    // Returns how many of the members were in the set.
+    pub fn remove(&mut self, members: &[String]) -> usize {
+        members
+            .iter()
+            .filter(|member| self.members.remove(member.as_str()))
+            .count()
+    }
+
+    pub fn union(&self, other: &Set) -> Set {
+        Set {
+            members: self.members.union(&other.members).cloned().collect(),
+        }
+    }
+
+    pub fn intersection(&self, other: &Set) -> Set {
+        Set {
+            members: self.members.intersection(&other.members).cloned().collect(),
+        }
+    }
+
+    pub fn difference(&self, other: &Set) -> Set {
+        Set {
+            members: self.members.difference(&other.members).cloned().collect(),
+        }
+    }
+}
+
+impl Display for Set {
+    // Sets are shown as JSON arrays, the same format SET accepts.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let members = self
+            .members
+            .iter()
+            .map(|member| Value::String(member.clone()).to_string())
+            .collect::<Vec<String>>();
+        write!(f, "[{}]", members.join(","))
+    }
+}
+
+#[cfg(test)]
+mod set_tests {
+    use super::*;
+
+    fn set(members: &[&str]) -> Set {
+        let mut set = Set::new();
+        set.add(&members.iter().map(|m| m.to_string()).collect::<Vec<_>>());
+        set
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let parsed = Set::parse(r#"["b", "a", "b"]"#).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.to_string(), r#"["a","b"]"#);
+
+        assert!(Set::parse("[1]").is_err());
+        assert!(Set::parse("a").is_err());
+    }
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut tags = set(&["a"]);
+
+        assert_eq!(tags.add(&["a".to_string(), "b".to_string()]), 1);
+        assert_eq!(tags.remove(&["a".to_string(), "c".to_string()]), 1);
+        assert_eq!(tags.members(), vec!["b"]);
+    }
+
+    #[test]
+    fn test_algebra() {
+        let first = set(&["a", "b", "c"]);
+        let second = set(&["b", "c", "d"]);
+
+        assert_eq!(first.union(&second).members(), vec!["a", "b", "c", "d"]);
+        assert_eq!(first.intersection(&second).members(), vec!["b", "c"]);
+        assert_eq!(first.difference(&second).members(), vec!["a"]);
+    }
+}
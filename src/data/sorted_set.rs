@@ -0,0 +1,377 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Display,
+    ops::Bound,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Score of a member. Scores are always finite, which makes them totally
+// ordered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Score(f64);
+
+impl Score {
+    // `total_cmp` orders -0.0 before 0.0, so it is stored as 0.0 for both to
+    // be the same score.
+    fn new(value: f64) -> Score {
+        Score(if value == 0.0 { 0.0 } else { value })
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+pub fn parse_score(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(score) if score.is_finite() => Ok(score),
+        _ => Err("Invalid score".to_string()),
+    }
+}
+
+// Lower or upper end of a ZRANGEBYSCORE, e.g. `1.5`, `(1.5` (exclusive),
+// `-inf` or `+inf`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreBound {
+    value: f64,
+    exclusive: bool,
+}
+
+impl FromStr for ScoreBound {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, exclusive) = match s.strip_prefix('(') {
+            Some(value) => (value, true),
+            None => (s, false),
+        };
+
+        match value.parse::<f64>() {
+            Ok(value) if !value.is_nan() => Ok(ScoreBound { value, exclusive }),
+            _ => Err("Invalid score".to_string()),
+        }
+    }
+}
+
+impl ScoreBound {
+    fn is_above(&self, score: f64) -> bool {
+        score > self.value || (!self.exclusive && score == self.value)
+    }
+
+    fn is_below(&self, score: f64) -> bool {
+        score < self.value || (!self.exclusive && score == self.value)
+    }
+}
+
+// Options that can follow the range of a ZRANGE or ZRANGEBYSCORE.
+#[derive(Default, Debug, PartialEq)]
+pub struct RangeOptions {
+    pub with_scores: bool,
+    // LIMIT offset count, a negative count returns all remaining members.
+    pub limit: Option<(usize, Option<usize>)>,
+}
+
+impl RangeOptions {
+    pub fn parse(options: &[String], allow_limit: bool) -> Result<RangeOptions, String> {
+        let mut range_options = RangeOptions::default();
+        let mut options = options.iter();
+
+        while let Some(option) = options.next() {
+            match option.as_str() {
+                "WITHSCORES" => range_options.with_scores = true,
+                "LIMIT" if allow_limit => {
+                    let offset = options.next().and_then(|offset| offset.parse().ok());
+                    let count = options.next().and_then(|count| count.parse::<i64>().ok());
+                    match (offset, count) {
+                        (Some(offset), Some(count)) => {
+                            range_options.limit = Some((offset, usize::try_from(count).ok()));
+                        }
+                        _ => return Err("Invalid LIMIT".to_string()),
+                    }
+                }
+                _ => return Err("Invalid range option".to_string()),
+            }
+        }
+
+        Ok(range_options)
+    }
+}
+
+// Members of a ZSET ordered by score, then by member. The ordered index is
+// kept up to date on every change so reads never have to sort.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(from = "BTreeMap<String, f64>", into = "BTreeMap<String, f64>")]
+pub struct SortedSet {
+    scores: HashMap<String, f64>,
+    ordered: BTreeSet<(Score, String)>,
+}
+
+impl From<BTreeMap<String, f64>> for SortedSet {
+    fn from(scores: BTreeMap<String, f64>) -> SortedSet {
+        let mut sorted_set = SortedSet::new();
+        for (member, score) in scores {
+            sorted_set.insert(member, score);
+        }
+        sorted_set
+    }
+}
+
+impl From<SortedSet> for BTreeMap<String, f64> {
+    fn from(sorted_set: SortedSet) -> BTreeMap<String, f64> {
+        sorted_set.scores.into_iter().collect()
+    }
+}
+
+impl SortedSet {
+    pub fn new() -> SortedSet {
+        SortedSet::default()
+    }
+
+    // Reads a sorted set written as a JSON object of members and their
+    // scores, e.g. `{"alice": 10, "bob": 7.5}`.
+    pub fn parse(value: &str) -> Result<SortedSet, String> {
+        let scores = serde_json::from_str::<BTreeMap<String, f64>>(value)
+            .map_err(|_| "Invalid sorted set. Expected a JSON object of scores".to_string())?;
+        Ok(SortedSet::from(scores))
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub fn score(&self, member: &str) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    // Sets the score of `member`, returning `true` if it is a new member.
+    pub fn insert(&mut self, member: String, score: f64) -> bool {
+        let score = Score::new(score);
+        let is_new = match self.scores.insert(member.clone(), score.0) {
+            Some(previous) => {
+                self.ordered.remove(&(Score(previous), member.clone()));
+                false
+            }
+            None => true,
+        };
+        self.ordered.insert((score, member));
+        is_new
+    }
+
+    pub fn increment(&mut self, member: &str, delta: f64) -> Result<f64, String> {
+        let score = self.score(member).unwrap_or(0.0) + delta;
+        if !score.is_finite() {
+            return Err("Increment would produce NaN or Infinity".to_string());
+        }
+        self.insert(member.to_string(), score);
+        Ok(score)
+    }
+
+    pub fn remove(&mut self, member: &str) -> bool {
+        match self.scores.remove(member) {
+            Some(score) => self.ordered.remove(&(Score(score), member.to_string())),
+            None => false,
+        }
+    }
+
+    // Position of `member` when ordered by ascending score, starting at 0.
+    // The index does not keep subtree sizes, so this counts the members
+    // before it and takes O(n) time.
+    pub fn rank(&self, member: &str) -> Option<usize> {
+        let score = self.score(member)?;
+        Some(
+            self.ordered
+                .range(..(Score(score), member.to_string()))
+                .count(),
+        )
+    }
+
+    // Members from rank `start` to `stop`, both included. Negative ranks
+    // count from the highest score, -1 being the last member.
+    pub fn range(&self, start: i64, stop: i64) -> Vec<(String, f64)> {
+        let len = self.len() as i64;
+        let resolve = |index: i64| if index < 0 { len + index } else { index };
+
+        let start = resolve(start).max(0);
+        let stop = resolve(stop).min(len - 1);
+        if start > stop {
+            return Vec::new();
+        }
+
+        self.ordered
+            .iter()
+            .skip(start as usize)
+            .take((stop - start + 1) as usize)
+            .map(|(score, member)| (member.clone(), score.0))
+            .collect()
+    }
+
+    pub fn range_by_score(
+        &self,
+        min: ScoreBound,
+        max: ScoreBound,
+        limit: Option<(usize, Option<usize>)>,
+    ) -> Vec<(String, f64)> {
+        let (offset, count) = limit.unwrap_or((0, None));
+
+        // Members with the same score are ordered by name, so the empty
+        // string is the first of them.
+        let start = Bound::Included((Score::new(min.value), String::new()));
+
+        self.ordered
+            .range((start, Bound::Unbounded))
+            .skip_while(|(score, _)| !min.is_above(score.0))
+            .take_while(|(score, _)| max.is_below(score.0))
+            .skip(offset)
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(score, member)| (member.clone(), score.0))
+            .collect()
+    }
+}
+
+impl Display for SortedSet {
+    // Sorted sets are shown as JSON objects in score order, the same format
+    // SET accepts.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let members = self
+            .ordered
+            .iter()
+            .map(|(score, member)| {
+                format!("{}:{}", Value::String(member.clone()), Value::from(score.0))
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{{{}}}", members.join(","))
+    }
+}
+
+#[cfg(test)]
+mod sorted_set_tests {
+    use super::*;
+
+    fn leaderboard() -> SortedSet {
+        SortedSet::parse(r#"{"alice": 10, "bob": 7.5, "carol": 10, "dave": 3}"#).unwrap()
+    }
+
+    fn members(range: Vec<(String, f64)>) -> Vec<String> {
+        range.into_iter().map(|(member, _)| member).collect()
+    }
+
+    #[test]
+    fn test_order_and_rank() {
+        let mut scores = leaderboard();
+
+        assert_eq!(
+            members(scores.range(0, -1)),
+            vec!["dave", "bob", "alice", "carol"]
+        );
+        assert_eq!(scores.rank("alice"), Some(2));
+        assert_eq!(scores.rank("nobody"), None);
+
+        assert!(!scores.insert("dave".to_string(), 20.0));
+        assert_eq!(scores.rank("dave"), Some(3));
+        assert_eq!(scores.increment("bob", -10.0), Ok(-2.5));
+        assert_eq!(scores.rank("bob"), Some(0));
+
+        assert!(scores.remove("bob"));
+        assert!(!scores.remove("bob"));
+        assert_eq!(scores.len(), 3);
+        assert_eq!(members(scores.range(-2, -1)), vec!["carol", "dave"]);
+    }
+
+    #[test]
+    fn test_range_by_score() {
+        let scores = leaderboard();
+        let bound = |s: &str| ScoreBound::from_str(s).unwrap();
+
+        assert_eq!(
+            members(scores.range_by_score(bound("-inf"), bound("+inf"), None)),
+            vec!["dave", "bob", "alice", "carol"]
+        );
+        assert_eq!(
+            members(scores.range_by_score(bound("7.5"), bound("10"), None)),
+            vec!["bob", "alice", "carol"]
+        );
+        assert_eq!(
+            members(scores.range_by_score(bound("(7.5"), bound("(11"), Some((1, None)))),
+            vec!["carol"]
+        );
+        assert_eq!(
+            members(scores.range_by_score(bound("0"), bound("10"), Some((1, Some(2))))),
+            vec!["bob", "alice"]
+        );
+        assert!(ScoreBound::from_str("(nan").is_err());
+    }
+
+    #[test]
+    fn test_negative_zero_score() {
+        let mut scores = SortedSet::new();
+        scores.insert("zero".to_string(), -0.0);
+        let bound = |s: &str| ScoreBound::from_str(s).unwrap();
+
+        assert_eq!(scores.score("zero").unwrap().to_string(), "0");
+        assert_eq!(
+            members(scores.range_by_score(bound("0"), bound("0"), None)),
+            vec!["zero"]
+        );
+        assert_eq!(
+            members(scores.range_by_score(bound("-0"), bound("+inf"), None)),
+            vec!["zero"]
+        );
+
+        assert!(!scores.insert("zero".to_string(), 0.0));
+        assert!(scores.remove("zero"));
+        assert!(scores.is_empty());
+    }
+
+    #[test]
+    fn test_persisted_format() {
+        let scores = leaderboard();
+
+        let json = serde_json::to_string(&scores).unwrap();
+        assert_eq!(json, r#"{"alice":10.0,"bob":7.5,"carol":10.0,"dave":3.0}"#);
+
+        let loaded: SortedSet = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.to_string(), scores.to_string());
+        assert_eq!(
+            scores.to_string(),
+            r#"{"dave":3.0,"bob":7.5,"alice":10.0,"carol":10.0}"#
+        );
+    }
+
+    #[test]
+    fn test_range_options() {
+        let options = |args: &[&str]| {
+            RangeOptions::parse(
+                &args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>(),
+                true,
+            )
+        };
+
+        assert_eq!(
+            options(&["WITHSCORES", "LIMIT", "1", "-1"]),
+            Ok(RangeOptions {
+                with_scores: true,
+                limit: Some((1, None)),
+            })
+        );
+        assert!(options(&["LIMIT", "1"]).is_err());
+        assert!(options(&["REV"]).is_err());
+    }
+}
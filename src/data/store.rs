@@ -2,27 +2,55 @@ use std::{collections::HashMap, fmt::Display};
 
 use serde::{Deserialize, Serialize};
 
-use crate::data::data_value::Data;
-
-use super::{data_value::DataValue, key::Key, DataTypes};
+use crate::{
+    data::{data_value::Data, TypedValue},
+    error::KvError,
+};
+
+use super::{
+    data_value::DataValue,
+    expiry::{is_expired, now_millis},
+    key::Key,
+    DataTypes,
+};
 
 pub trait StoreManager: Data {
     fn get_name(&self) -> String;
 
-    fn set_store(&mut self, store_name: Key) -> Result<String, String>;
+    fn set_store(&mut self, store_name: Key) -> Result<String, KvError>;
+
+    fn list_keys(&self) -> Result<String, KvError>;
 
-    fn list_keys(&self) -> Result<String, String>;
+    fn get_store(&mut self, store_name: Key) -> Result<&mut Store, KvError>;
 
-    fn get_store(&mut self, store_name: Key) -> Result<&mut Store, String>;
+    fn del_store(&mut self, store_name: &Key) -> Result<String, KvError>;
+}
 
-    fn del_store(&mut self, store_name: &Key) -> Result<String, String>;
+// What a single key holds. Transactions save the entries of the keys they
+// write so that they can put them back when a command fails.
+#[derive(Clone)]
+pub enum Entry {
+    Value(DataValue),
+    Store(Store),
+    Missing,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Store {
     name: String,
     pub data: HashMap<String, DataValue>,
     pub stores: HashMap<String, Store>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
+    // Last version given to a value. Only the counter of the root store is
+    // used, so a key that is deleted and set again never gets back a version
+    // it had before.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    version: u64,
+}
+
+fn is_zero(version: &u64) -> bool {
+    *version == 0
 }
 
 impl Store {
@@ -31,26 +59,44 @@ impl Store {
             name,
             data: HashMap::new(),
             stores: HashMap::new(),
+            expires_at: None,
+            version: 0,
         }
     }
 
-    pub fn set(&mut self, key: Key, value: String, data_type: DataTypes) -> Result<String, String> {
-        if key.is_value_key() {
-            return self.set_value(key, value, data_type);
-        }
+    pub fn expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
 
-        let store: &mut Store = self.get_store(key.get_store_key())?;
+    pub fn set(
+        &mut self,
+        key: Key,
+        value: String,
+        data_type: DataTypes,
+    ) -> Result<String, KvError> {
+        let value = DataValue::new(value, data_type)?;
 
-        let key = key.get_next_key();
+        self.set_data_value(key, value)
+    }
+
+    // Like `set`, for a value that was already built, e.g. a typed list.
+    pub fn set_data_value(&mut self, key: Key, mut value: DataValue) -> Result<String, KvError> {
+        let version = self.next_version();
+        let (store, name) = self.store_of(key)?;
+
+        value.set_version(version);
+        store.data.insert(name, value);
 
-        store.set(key, value, data_type)
+        self.version = version;
+        Ok("OK".to_string())
     }
 
-    pub fn get(&mut self, key: Key) -> Result<String, String> {
+    pub fn get(&mut self, key: Key) -> Result<String, KvError> {
         if key.is_value_key() {
-            if key.key.clone().unwrap() == "*".to_string() {
+            if key.key.clone().unwrap() == "*" {
                 return Ok(self.to_string());
             }
+            self.remove_if_expired(key.key.as_ref().unwrap());
             return self.get_value(key);
         }
 
@@ -61,13 +107,14 @@ impl Store {
         store.get(key)
     }
 
-    pub fn del(&mut self, key: Key) -> Result<String, String> {
+    pub fn del(&mut self, key: Key) -> Result<String, KvError> {
         if key.is_value_key() {
+            self.remove_if_expired(key.key.as_ref().unwrap());
             return match self.del_value(&key) {
                 Ok(_) => Ok("OK".to_string()),
                 Err(_) => match self.del_store(&key) {
                     Ok(_) => Ok("OK".to_string()),
-                    Err(_) => Err("Key not found".to_string()),
+                    Err(_) => Err(KvError::key_not_found()),
                 },
             };
         }
@@ -78,6 +125,280 @@ impl Store {
 
         store.del(key)
     }
+
+    // Sets (or with `None` clears) the expiry time of a value or a whole
+    // sub-store.
+    pub fn set_expiry(&mut self, key: Key, expires_at: Option<u64>) -> Result<String, KvError> {
+        if key.is_value_key() {
+            let name = key.key.unwrap();
+            self.remove_if_expired(&name);
+
+            if let Some(value) = self.data.get_mut(&name) {
+                value.set_expires_at(expires_at);
+            } else if let Some(store) = self.stores.get_mut(&name) {
+                store.expires_at = expires_at;
+            } else {
+                return Err(KvError::key_not_found());
+            }
+
+            return Ok("OK".to_string());
+        }
+
+        let store: &mut Store = self.get_store(key.get_store_key())?;
+
+        let key = key.get_next_key();
+
+        store.set_expiry(key, expires_at)
+    }
+
+    pub fn get_expiry(&mut self, key: Key) -> Result<Option<u64>, KvError> {
+        if key.is_value_key() {
+            let name = key.key.unwrap();
+            self.remove_if_expired(&name);
+
+            if let Some(value) = self.data.get(&name) {
+                return Ok(value.expires_at());
+            }
+            if let Some(store) = self.stores.get(&name) {
+                return Ok(store.expires_at);
+            }
+            return Err(KvError::key_not_found());
+        }
+
+        let store: &mut Store = self.get_store(key.get_store_key())?;
+
+        let key = key.get_next_key();
+
+        store.get_expiry(key)
+    }
+
+    // Changes the value at `key` in place, keeping its expiry. A missing value
+    // is created from `initial` first when one is given. Lists and sets that
+    // end up empty are removed.
+    //
+    // Like a SET of the same value, every update that succeeds gives the value
+    // a new version, even when it is left as it was (e.g. SADD of a member
+    // that is already in the set).
+    pub fn update_value<T, F>(
+        &mut self,
+        key: Key,
+        initial: Option<DataValue>,
+        update: F,
+    ) -> Result<T, KvError>
+    where
+        F: FnOnce(&mut DataValue) -> Result<T, KvError>,
+    {
+        let version = self.next_version();
+        let (store, name) = self.store_of(key)?;
+        store.remove_if_expired(&name);
+
+        let result = if let Some(value) = store.data.get_mut(&name) {
+            let result = update(value)?;
+            value.set_version(version);
+            if value.is_empty_collection() {
+                store.data.remove(&name);
+            }
+            result
+        } else {
+            if store.stores.contains_key(&name) {
+                return Err(KvError::WrongType("Key is a store".to_string()));
+            }
+
+            let mut value = initial.ok_or_else(KvError::key_not_found)?;
+            let result = update(&mut value)?;
+            value.set_version(version);
+            if !value.is_empty_collection() {
+                store.data.insert(name, value);
+            }
+            result
+        };
+
+        self.version = version;
+        Ok(result)
+    }
+
+    // Store that holds `key`, with the name of the key in it.
+    fn store_of(&mut self, key: Key) -> Result<(&mut Store, String), KvError> {
+        if key.is_value_key() {
+            return Ok((self, key.key.unwrap()));
+        }
+
+        let store: &mut Store = self.get_store(key.get_store_key())?;
+
+        store.store_of(key.get_next_key())
+    }
+
+    // Version for the next value written to the store. Snapshots written
+    // before the counter existed continue from the highest version in them.
+    fn next_version(&mut self) -> u64 {
+        if self.version == 0 {
+            self.version = self.max_version();
+        }
+        self.version + 1
+    }
+
+    fn max_version(&self) -> u64 {
+        let values = self.data.values().map(DataValue::version);
+        let stores = self.stores.values().map(Store::max_version);
+        values.chain(stores).max().unwrap_or(0)
+    }
+
+    // Reads the value at `key` without changing it.
+    pub fn read_value<T, F>(&mut self, key: Key, read: F) -> Result<T, KvError>
+    where
+        F: FnOnce(&DataValue) -> Result<T, KvError>,
+    {
+        self.remove_expired_on_path(key.clone());
+        read(self.lookup_value(key)?)
+    }
+
+    // Value at `key`. Values and stores that expired count as missing even
+    // when they were not removed yet.
+    fn lookup_value(&self, key: Key) -> Result<&DataValue, KvError> {
+        let now = now_millis();
+
+        if key.is_value_key() {
+            let name = key.key.unwrap();
+
+            let value = self.data.get(&name);
+            if let Some(value) = value.filter(|value| !is_expired(value.expires_at(), now)) {
+                return Ok(value);
+            }
+            if self.lookup_store(&name, now).is_some() {
+                return Err(KvError::WrongType("Key is a store".to_string()));
+            }
+            return Err(KvError::key_not_found());
+        }
+
+        let store_name = key.get_store_key().key.unwrap_or_default();
+        let store = self
+            .lookup_store(&store_name, now)
+            .ok_or_else(KvError::key_not_found)?;
+
+        store.lookup_value(key.get_next_key())
+    }
+
+    fn lookup_store(&self, name: &str, now: u64) -> Option<&Store> {
+        self.stores
+            .get(name)
+            .filter(|store| !is_expired(store.expires_at, now))
+    }
+
+    // Drops the expired value or stores on the way to `key`.
+    fn remove_expired_on_path(&mut self, key: Key) {
+        if key.is_value_key() {
+            self.remove_if_expired(key.key.as_ref().unwrap());
+            return;
+        }
+
+        if let Ok(store) = self.get_store(key.get_store_key()) {
+            store.remove_expired_on_path(key.get_next_key());
+        }
+    }
+
+    pub fn get_version(&mut self, key: Key) -> Result<u64, KvError> {
+        if key.is_value_key() {
+            let name = key.key.unwrap();
+            self.remove_if_expired(&name);
+
+            if let Some(value) = self.data.get(&name) {
+                return Ok(value.version());
+            }
+            if self.stores.contains_key(&name) {
+                return Err(KvError::WrongType("Stores have no version".to_string()));
+            }
+            return Err(KvError::key_not_found());
+        }
+
+        let store: &mut Store = self.get_store(key.get_store_key())?;
+
+        let key = key.get_next_key();
+
+        store.get_version(key)
+    }
+
+    // Names of the sub-stores that have not expired.
+    pub fn store_names(&self) -> Vec<String> {
+        let now = now_millis();
+        self.stores
+            .iter()
+            .filter(|(_, store)| !is_expired(store.expires_at, now))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    // Copy of what is stored at `key`, to be put back with `restore_entry`.
+    pub fn entry(&mut self, key: Key) -> Entry {
+        if key.is_value_key() {
+            let name = key.key.unwrap();
+            if let Some(value) = self.data.get(&name) {
+                return Entry::Value(value.clone());
+            }
+            return match self.stores.get(&name) {
+                Some(store) => Entry::Store(store.clone()),
+                None => Entry::Missing,
+            };
+        }
+
+        match self.get_store(key.get_store_key()) {
+            Ok(store) => store.entry(key.get_next_key()),
+            Err(_) => Entry::Missing,
+        }
+    }
+
+    // Entries have to be restored in the reverse order they were saved in.
+    // The parent stores of a key are then as they were when its entry was
+    // saved, so a missing parent means the key was missing too.
+    pub fn restore_entry(&mut self, key: Key, entry: Entry) {
+        if key.is_value_key() {
+            let name = key.key.unwrap();
+            self.data.remove(&name);
+            self.stores.remove(&name);
+
+            match entry {
+                Entry::Value(value) => {
+                    self.data.insert(name, value);
+                }
+                Entry::Store(store) => {
+                    self.stores.insert(name, store);
+                }
+                Entry::Missing => {}
+            }
+            return;
+        }
+
+        if let Ok(store) = self.get_store(key.get_store_key()) {
+            store.restore_entry(key.get_next_key(), entry);
+        }
+    }
+
+    // Drops every value and sub-store that expired at `now`, returning how
+    // many entries were removed.
+    pub fn remove_expired(&mut self, now: u64) -> usize {
+        let count = self.data.len() + self.stores.len();
+
+        self.data
+            .retain(|_, value| !is_expired(value.expires_at(), now));
+        self.stores
+            .retain(|_, store| !is_expired(store.expires_at, now));
+
+        let mut removed = count - self.data.len() - self.stores.len();
+        for store in self.stores.values_mut() {
+            removed += store.remove_expired(now);
+        }
+        removed
+    }
+
+    fn remove_if_expired(&mut self, name: &str) {
+        let now = now_millis();
+
+        if matches!(self.data.get(name), Some(value) if is_expired(value.expires_at(), now)) {
+            self.data.remove(name);
+        }
+        if matches!(self.stores.get(name), Some(store) if is_expired(store.expires_at, now)) {
+            self.stores.remove(name);
+        }
+    }
 }
 
 impl Data for Store {
@@ -85,40 +406,43 @@ impl Data for Store {
         DataTypes::STORE
     }
 
-    fn get_value(&self, key: Key) -> Result<String, String> {
+    fn get_value(&self, key: Key) -> Result<String, KvError> {
         if key.is_value_key() {
             let data_key = key.key.clone().unwrap();
 
             let data = self.data.get(&data_key);
 
             if data.is_none() {
-                return Err("Key not found".to_string());
+                return Err(KvError::key_not_found());
             }
 
-            return data.unwrap().get_value(key);
+            data.unwrap().get_value(key)
         } else {
-            return Err("Invalid key".to_string());
+            Err(KvError::BadArgs("Invalid key".to_string()))
         }
     }
 
+    // Expired values are not removed here, as the store is borrowed
+    // immutably, but they are not returned either.
+    fn get_typed_value(&self, key: Key) -> Result<&TypedValue, KvError> {
+        self.lookup_value(key).map(DataValue::value)
+    }
+
     fn set_value(
         &mut self,
         key: Key,
         value: String,
         data_type: DataTypes,
-    ) -> Result<String, String> {
-        self.data
-            .insert(key.key.clone().unwrap(), DataValue::new(value, data_type)?);
-        Ok("OK".to_string())
+    ) -> Result<String, KvError> {
+        self.set(key, value, data_type)
     }
 
-    fn del_value(&mut self, key: &Key) -> Result<String, String> {
+    fn del_value(&mut self, key: &Key) -> Result<String, KvError> {
         let value_key = key.key.clone().unwrap();
-        if self.data.contains_key(&value_key) {
-            self.data.remove(&value_key);
+        if self.data.remove(&value_key).is_some() {
             return Ok("OK".to_string());
         }
-        return Err("Key not found".to_string());
+        Err(KvError::key_not_found())
     }
 }
 
@@ -127,61 +451,64 @@ impl StoreManager for Store {
         self.name.clone()
     }
 
-    fn set_store(&mut self, store_name: Key) -> Result<String, String> {
+    fn set_store(&mut self, store_name: Key) -> Result<String, KvError> {
         if store_name.is_value_key() {
             let store_key = store_name.key.unwrap();
+            self.remove_if_expired(&store_key);
             if self.stores.contains_key(&store_key) {
-                return Err(format!("Key already exists: {}", store_key));
+                return Err(KvError::AlreadyExists(format!(
+                    "Key already exists: {}",
+                    store_key
+                )));
             }
             let new_store = Store::new(store_key.clone());
 
             self.stores.insert(store_key, new_store);
 
-            return Ok("OK".to_string());
+            Ok("OK".to_string())
         } else {
             let store = self.get_store(store_name.get_store_key())?;
             let store_name = store_name.get_next_key();
-            return store.set_store(store_name);
+            store.set_store(store_name)
         }
     }
 
-    fn list_keys(&self) -> Result<String, String> {
-        Ok(self
-            .stores
-            .keys()
-            .cloned()
-            .collect::<Vec<String>>()
-            .join("\n"))
+    fn list_keys(&self) -> Result<String, KvError> {
+        Ok(self.store_names().join("\n"))
     }
 
-    fn get_store(&mut self, store_name: Key) -> Result<&mut Store, String> {
+    fn get_store(&mut self, store_name: Key) -> Result<&mut Store, KvError> {
         if store_name.is_value_key() {
             let store_key = store_name.key.clone().unwrap();
+            self.remove_if_expired(&store_key);
             if self.stores.contains_key(&store_key) {
                 return Ok(self.stores.get_mut(&store_key).unwrap());
             }
-            return Err("Key not found".to_string());
+            return Err(KvError::key_not_found());
         }
         let store_key = store_name.get_store_key();
         let store = self.get_store(store_key)?;
         let store_name = store_name.get_next_key();
-        return store.get_store(store_name);
+        store.get_store(store_name)
     }
 
-    fn del_store(&mut self, store_name: &Key) -> Result<String, String> {
+    fn del_store(&mut self, store_name: &Key) -> Result<String, KvError> {
         let store_key = store_name.key.clone().unwrap();
-        if self.stores.contains_key(&store_key) {
-            self.stores.remove(&store_key);
+        if self.stores.remove(&store_key).is_some() {
             return Ok("OK".to_string());
         }
-        return Err("Key not found".to_string());
+        Err(KvError::key_not_found())
     }
 }
 
 impl Display for Store {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name)?;
+        let now = now_millis();
         for (key, value) in &self.data {
+            if is_expired(value.expires_at(), now) {
+                continue;
+            }
             write!(f, "\n{}: {}", key, value)?;
         }
         Ok(())
@@ -0,0 +1,139 @@
+use std::str::FromStr;
+
+use crate::{
+    commands::Command,
+    data::{test::data_tests_utils::*, DataManager},
+    error::KvError,
+    session::Session,
+};
+
+async fn create_scoped_user(data: &mut DataManager) -> Session {
+    let admin = create_session();
+    for line in [
+        "CREATE_STORE orders",
+        "CREATE_STORE orders:eu",
+        "SET orders:1 a",
+        "SET orders:eu:1 b",
+        "SET customers 1",
+        "CREATE_USER alice Password4 0",
+        "GRANT alice GET,SET,STORE_CREATE,LIST ON orders:*",
+    ] {
+        run(data, admin.clone(), line).await.unwrap();
+    }
+    Session::new().set_authenticated("alice")
+}
+
+#[tokio::test]
+async fn test_command_acl_scopes_keys() {
+    let mut data = create_data_manager().await;
+    let alice = create_scoped_user(&mut data).await;
+
+    assert_eq!(
+        run(&mut data, alice.clone(), "GET orders:1").await,
+        Ok("a".to_string())
+    );
+    assert_eq!(
+        run(&mut data, alice.clone(), "GET orders:eu:1").await,
+        Ok("b".to_string())
+    );
+    assert!(run(&mut data, alice.clone(), "SET orders:2 c")
+        .await
+        .is_ok());
+    assert!(run(&mut data, alice.clone(), "CREATE_STORE orders:us")
+        .await
+        .is_ok());
+    assert!(run(&mut data, alice.clone(), "LIST_KEYS orders")
+        .await
+        .is_ok());
+
+    for line in [
+        "GET customers",
+        "SET customers 2",
+        "DEL orders:1",
+        "LIST_KEYS .",
+    ] {
+        assert_eq!(
+            run(&mut data, alice.clone(), line).await,
+            Err(KvError::NoPermission),
+            "{}",
+            line
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_command_acl_in_transaction() {
+    let mut data = create_data_manager().await;
+    let alice = create_scoped_user(&mut data).await;
+
+    let mut session = alice;
+    for line in ["MULTI", "SET orders:3 x", "GET customers"] {
+        let cmd = Command::from_str(line).unwrap();
+        (_, session) = data.handle_command(cmd, session).await.unwrap();
+    }
+    let cmd = Command::from_str("EXEC").unwrap();
+    let result = data.handle_command(cmd, session).await.unwrap_err();
+    assert_eq!(result, KvError::NoPermission);
+
+    let result = run(&mut data, create_session(), "GET orders:3").await;
+    assert!(matches!(result, Err(KvError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_command_acl_shown_and_revoked() {
+    let mut data = create_data_manager().await;
+    let alice = create_scoped_user(&mut data).await;
+    let admin = create_session();
+
+    assert_eq!(
+        run(&mut data, admin.clone(), "GET_USER alice").await,
+        Ok("User: alice Permissions: NONE ACL: orders:*=SET,GET,STORE_CREATE,LIST".to_string())
+    );
+
+    run(&mut data, admin.clone(), "REVOKE alice SET ON orders:*")
+        .await
+        .unwrap();
+    assert_eq!(
+        run(&mut data, admin.clone(), "GET_USER alice").await,
+        Ok("User: alice Permissions: NONE ACL: orders:*=GET,STORE_CREATE,LIST".to_string())
+    );
+    assert_eq!(
+        run(&mut data, alice, "SET orders:1 c").await,
+        Err(KvError::NoPermission)
+    );
+
+    let result = run(&mut data, admin, "GRANT alice USER_ADMIN ON orders:*").await;
+    assert!(matches!(result, Err(KvError::BadArgs(_))));
+}
+
+#[tokio::test]
+async fn test_command_acl_grant_requires_access() {
+    let mut data = create_data_manager().await;
+    create_scoped_user(&mut data).await;
+    let admin = create_session();
+
+    run(
+        &mut data,
+        admin.clone(),
+        "CREATE_USER manager Password4 USER_ADMIN",
+    )
+    .await
+    .unwrap();
+    run(&mut data, admin.clone(), "CREATE_USER bob Password4 0")
+        .await
+        .unwrap();
+    run(&mut data, admin, "GRANT manager GET ON orders:*")
+        .await
+        .unwrap();
+
+    let manager = Session::new().set_authenticated("manager");
+    assert!(
+        run(&mut data, manager.clone(), "GRANT bob GET ON orders:eu:*")
+            .await
+            .is_ok()
+    );
+    assert_eq!(
+        run(&mut data, manager, "GRANT bob GET ON customers:*").await,
+        Err(KvError::NoPermission)
+    );
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::{commands::Command, data::test::data_tests_utils::*};
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError};
 
 #[tokio::test]
 async fn test_command_auth_no_user() {
@@ -12,7 +12,7 @@ async fn test_command_auth_no_user() {
         .await
         .unwrap_err();
 
-    assert_eq!(result_err, "Username or password is incorrect".to_string());
+    assert_eq!(result_err, KvError::AuthFailed);
 }
 
 #[tokio::test]
@@ -30,7 +30,7 @@ async fn test_command_auth_wrong_password() {
         .await
         .unwrap_err();
 
-    assert_eq!(result_err, "Username or password is incorrect".to_string());
+    assert_eq!(result_err, KvError::AuthFailed);
 }
 
 #[tokio::test]
@@ -0,0 +1,61 @@
+use crate::{data::test::data_tests_utils::*, error::KvError};
+
+#[tokio::test]
+async fn test_command_bytes_set_and_get() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let result = run(&mut data, session.clone(), "SET blob AP8Q BYTES")
+        .await
+        .unwrap();
+    assert_eq!(result, "OK".to_string());
+
+    let result = run(&mut data, session.clone(), "GET blob").await.unwrap();
+    assert_eq!(result, "AP8Q".to_string());
+
+    let result = run(&mut data, session.clone(), "GET blob ENCODING HEX")
+        .await
+        .unwrap();
+    assert_eq!(result, "00ff10".to_string());
+
+    let result = run(
+        &mut data,
+        session.clone(),
+        "SET blob cafe BYTES ENCODING HEX EX 100",
+    )
+    .await
+    .unwrap();
+    assert_eq!(result, "OK".to_string());
+
+    let result = run(
+        &mut data,
+        session.clone(),
+        "GET blob ENCODING BASE64 WITHVERSION",
+    )
+    .await
+    .unwrap();
+    assert_eq!(result, "2\nyv4=".to_string());
+
+    let result = run(&mut data, session, "TTL blob").await.unwrap();
+    assert!(result.parse::<u64>().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_command_bytes_wrong_type() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET name alice")
+        .await
+        .unwrap();
+
+    let result_err = run(&mut data, session.clone(), "GET name ENCODING HEX")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::WrongType(_)));
+
+    let result_err = run(&mut data, session, "GET missing ENCODING HEX")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::{commands::Command, data::test::data_tests_utils::*, session::Session};
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError, session::Session};
 
 #[tokio::test]
 async fn test_command_create_user_success() {
@@ -37,7 +37,7 @@ async fn test_command_create_user_no_more_permission() {
     let cmd = Command::from_str("CREATE_USER user2 Password4 GET").unwrap();
     let result = data.handle_command(cmd, session).await.unwrap_err();
 
-    assert_eq!(result, "User does not have permission".to_string());
+    assert_eq!(result, KvError::NoPermission);
 }
 
 #[tokio::test]
@@ -47,7 +47,7 @@ async fn test_command_create_user_check_auth() {
     let cmd = Command::from_str("CREATE_USER user Password4").unwrap();
     let result = data.handle_command(cmd, Session::new()).await.unwrap_err();
 
-    assert_eq!(result, "User not authenticated".to_string());
+    assert_eq!(result, KvError::NotAuthenticated);
 
     data.handle_command(
         Command::from_str("CREATE_USER user Password4 USER_ADMIN").unwrap(),
@@ -93,7 +93,7 @@ async fn test_command_create_user_permission() {
 
     let result_err = data.handle_command(cmd, session.clone()).await.unwrap_err();
 
-    assert_eq!(result_err, "User does not have permission".to_string());
+    assert_eq!(result_err, KvError::NoPermission);
 
     data.handle_command(
         Command::from_str("GRANT user USER_ADMIN").unwrap(),
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::{commands::Command, data::test::data_tests_utils::*, session::Session};
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError, session::Session};
 
 #[tokio::test]
 async fn test_command_del_simple_key() {
@@ -23,7 +23,7 @@ async fn test_command_del_simple_key() {
 
     let result_err = data.handle_command(cmd, session).await.unwrap_err();
 
-    assert_eq!(result_err, "Key not found".to_string());
+    assert_eq!(result_err, KvError::key_not_found());
 }
 
 #[tokio::test]
@@ -53,7 +53,7 @@ async fn test_command_del_key_in_store() {
 
     let result_err = data.handle_command(cmd, session).await.unwrap_err();
 
-    assert_eq!(result_err, "Key not found".to_string());
+    assert_eq!(result_err, KvError::key_not_found());
 }
 
 #[tokio::test]
@@ -89,7 +89,7 @@ async fn test_command_del_key_in_embedded_store() {
 
     let result_err = data.handle_command(cmd, session).await.unwrap_err();
 
-    assert_eq!(result_err, "Key not found".to_string());
+    assert_eq!(result_err, KvError::key_not_found());
 }
 
 #[tokio::test]
@@ -119,7 +119,7 @@ async fn test_command_del_delete_store() {
 
     let result_err = data.handle_command(cmd, session).await.unwrap_err();
 
-    assert_eq!(result_err, "Key not found".to_string());
+    assert_eq!(result_err, KvError::key_not_found());
 }
 
 #[tokio::test]
@@ -167,7 +167,7 @@ async fn test_command_del_delete_embedded_store() {
 
     let result_err = data.handle_command(cmd, session).await.unwrap_err();
 
-    assert_eq!(result_err, "Key not found".to_string());
+    assert_eq!(result_err, KvError::key_not_found());
 }
 
 #[tokio::test]
@@ -195,7 +195,7 @@ async fn test_command_del_permission() {
     let cmd = Command::from_str("DEL key").unwrap();
 
     let result_err = data.handle_command(cmd, session.clone()).await.unwrap_err();
-    assert_eq!(result_err, "User does not have permission".to_string());
+    assert_eq!(result_err, KvError::NoPermission);
 
     data.handle_command(
         Command::from_str("GRANT user DEL").unwrap(),
@@ -212,7 +212,7 @@ async fn test_command_del_permission() {
     let cmd = Command::from_str("GET key").unwrap();
     let result = data.handle_command(cmd, session).await.unwrap_err();
 
-    assert_eq!(result, "Key not found".to_string());
+    assert_eq!(result, KvError::key_not_found());
 }
 
 #[tokio::test]
@@ -230,7 +230,7 @@ async fn test_command_del_check_auth() {
     let cmd = Command::from_str("DEL key").unwrap();
     let result = data.handle_command(cmd, session.clone()).await.unwrap_err();
 
-    assert_eq!(result, "User not authenticated".to_string());
+    assert_eq!(result, KvError::NotAuthenticated);
 
     let cmd = Command::from_str("CREATE_USER user Password4 GET DEL").unwrap();
     data.handle_command(cmd, create_session()).await.unwrap();
@@ -247,5 +247,5 @@ async fn test_command_del_check_auth() {
     let cmd = Command::from_str("GET key").unwrap();
     let result = data.handle_command(cmd, session).await.unwrap_err();
 
-    assert_eq!(result, "Key not found".to_string());
+    assert_eq!(result, KvError::key_not_found());
 }
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::{commands::Command, data::test::data_tests_utils::*, session::Session};
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError, session::Session};
 
 #[tokio::test]
 async fn test_command_delete_user() {
@@ -23,7 +23,7 @@ async fn test_command_delete_user() {
         .await
         .unwrap_err();
 
-    assert_eq!(result_err, "Username or password is incorrect".to_string());
+    assert_eq!(result_err, KvError::AuthFailed);
 }
 
 #[tokio::test]
@@ -53,7 +53,7 @@ async fn test_command_delete_user_not_allowed_after() {
 
     let cmd = Command::from_str("GET key").unwrap();
     let result = data.handle_command(cmd, user_session).await.unwrap_err();
-    assert_eq!(result, "User not authenticated".to_string());
+    assert_eq!(result, KvError::NotAuthenticated);
 
     let cmd = Command::from_str("GET key").unwrap();
     let (result, _) = data.handle_command(cmd, admin_session).await.unwrap();
@@ -0,0 +1,200 @@
+use std::str::FromStr;
+
+use crate::{
+    commands::Command,
+    data::{now_millis, test::data_tests_utils::*},
+    error::KvError,
+};
+
+#[tokio::test]
+async fn test_command_ttl_without_expiry() {
+    let mut data = create_data_manager().await;
+    let admin_session = create_session();
+
+    let cmd = Command::from_str("SET key value").unwrap();
+
+    data.handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    let cmd = Command::from_str("TTL key").unwrap();
+
+    let (result, _) = data.handle_command(cmd, admin_session).await.unwrap();
+
+    assert_eq!(result, "-1".to_string());
+}
+
+#[tokio::test]
+async fn test_command_set_with_expiry() {
+    let mut data = create_data_manager().await;
+    let admin_session = create_session();
+
+    let cmd = Command::from_str("SET key value EX 100").unwrap();
+
+    data.handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    let cmd = Command::from_str("TTL key").unwrap();
+
+    let (result, _) = data
+        .handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(result, "100".to_string());
+
+    // A plain SET replaces the value together with its expiry.
+    let cmd = Command::from_str("SET key other").unwrap();
+
+    data.handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    let cmd = Command::from_str("TTL key").unwrap();
+
+    let (result, _) = data.handle_command(cmd, admin_session).await.unwrap();
+
+    assert_eq!(result, "-1".to_string());
+}
+
+#[tokio::test]
+async fn test_command_expire_key() {
+    let mut data = create_data_manager().await;
+    let admin_session = create_session();
+
+    let cmd = Command::from_str("SET key value").unwrap();
+
+    data.handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    let cmd = Command::from_str("EXPIRE key 0").unwrap();
+
+    let (result, _) = data
+        .handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(result, "OK".to_string());
+
+    let cmd = Command::from_str("GET key").unwrap();
+
+    let result_err = data
+        .handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap_err();
+
+    assert_eq!(result_err, KvError::key_not_found());
+
+    let cmd = Command::from_str("TTL key").unwrap();
+
+    let result_err = data.handle_command(cmd, admin_session).await.unwrap_err();
+
+    assert_eq!(result_err, KvError::key_not_found());
+}
+
+#[tokio::test]
+async fn test_command_persist_key() {
+    let mut data = create_data_manager().await;
+    let admin_session = create_session();
+
+    let cmd = Command::from_str("SET key value EX 100").unwrap();
+
+    data.handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    let cmd = Command::from_str("PERSIST key").unwrap();
+
+    data.handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    let cmd = Command::from_str("TTL key").unwrap();
+
+    let (result, _) = data
+        .handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(result, "-1".to_string());
+
+    let cmd = Command::from_str("PERSIST missing").unwrap();
+
+    let result_err = data.handle_command(cmd, admin_session).await.unwrap_err();
+
+    assert_eq!(result_err, KvError::key_not_found());
+}
+
+#[tokio::test]
+async fn test_command_expire_store() {
+    let mut data = create_data_manager().await;
+    let admin_session = create_session();
+
+    for line in [
+        "CREATE_STORE store",
+        "CREATE_STORE store:embedded",
+        "SET store:embedded:key value",
+        "EXPIRE store:embedded 0",
+    ] {
+        let cmd = Command::from_str(line).unwrap();
+        data.handle_command(cmd, admin_session.clone())
+            .await
+            .unwrap();
+    }
+
+    let cmd = Command::from_str("GET store:embedded:key").unwrap();
+
+    let result_err = data
+        .handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap_err();
+
+    assert_eq!(result_err, KvError::key_not_found());
+
+    let cmd = Command::from_str("LIST_KEYS store").unwrap();
+
+    let (result, _) = data
+        .handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(result, "".to_string());
+
+    // The name is free again once the store expired.
+    let cmd = Command::from_str("CREATE_STORE store:embedded").unwrap();
+
+    let (result, _) = data.handle_command(cmd, admin_session).await.unwrap();
+
+    assert_eq!(result, "OK".to_string());
+}
+
+#[tokio::test]
+async fn test_remove_expired() {
+    let mut data = create_data_manager().await;
+    let admin_session = create_session();
+
+    for line in [
+        "SET short value EX 10",
+        "SET long value EX 1000",
+        "CREATE_STORE store",
+        "SET store:key value EX 10",
+        "EXPIRE store 1000",
+    ] {
+        let cmd = Command::from_str(line).unwrap();
+        data.handle_command(cmd, admin_session.clone())
+            .await
+            .unwrap();
+    }
+
+    let mut store = data.data.lock().await;
+
+    assert_eq!(store.remove_expired(now_millis()), 0);
+    assert_eq!(store.remove_expired(now_millis() + 100_000), 2);
+    assert!(store.data.contains_key("long"));
+    assert!(store.stores["store"].data.is_empty());
+
+    assert_eq!(store.remove_expired(now_millis() + 10_000_000), 2);
+    assert!(!store.stores.contains_key("store"));
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::{commands::Command, data::test::data_tests_utils::*, session::Session};
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError, session::Session};
 
 #[tokio::test]
 async fn test_command_get_simple_key() {
@@ -99,7 +99,7 @@ async fn test_command_get_permission() {
     let cmd = Command::from_str("GET key").unwrap();
 
     let result_err = data.handle_command(cmd, session.clone()).await.unwrap_err();
-    assert_eq!(result_err, "User does not have permission".to_string());
+    assert_eq!(result_err, KvError::NoPermission);
 
     data.handle_command(
         Command::from_str("GRANT user GET").unwrap(),
@@ -129,7 +129,7 @@ async fn test_command_get_check_auth() {
     let cmd = Command::from_str("GET key").unwrap();
     let result = data.handle_command(cmd, session.clone()).await.unwrap_err();
 
-    assert_eq!(result, "User not authenticated".to_string());
+    assert_eq!(result, KvError::NotAuthenticated);
 
     let cmd = Command::from_str("CREATE_USER user Password4 GET").unwrap();
     data.handle_command(cmd, create_session()).await.unwrap();
@@ -178,3 +178,27 @@ async fn test_command_get_whole_store() {
     assert!(result.contains("key2: 100 (INT)"));
     assert!(result.contains("test_store_name"));
 }
+
+#[tokio::test]
+async fn test_command_get_canonical_value() {
+    let mut data = create_data_manager().await;
+    let admin_session = create_session();
+
+    for line in ["SET price 1.50 FLOAT", "SET count +042 INT"] {
+        let cmd = Command::from_str(line).unwrap();
+        data.handle_command(cmd, admin_session.clone())
+            .await
+            .unwrap();
+    }
+
+    let cmd = Command::from_str("GET price").unwrap();
+    let (result, _) = data
+        .handle_command(cmd, admin_session.clone())
+        .await
+        .unwrap();
+    assert_eq!(result, "1.5".to_string());
+
+    let cmd = Command::from_str("GET count").unwrap();
+    let (result, _) = data.handle_command(cmd, admin_session).await.unwrap();
+    assert_eq!(result, "42".to_string());
+}
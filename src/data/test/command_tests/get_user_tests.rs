@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::{commands::Command, data::test::data_tests_utils::*};
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError};
 
 #[tokio::test]
 async fn test_command_get_user() {
@@ -12,7 +12,7 @@ async fn test_command_get_user() {
         .await
         .unwrap_err();
 
-    assert_eq!(result_err, "User not found".to_string());
+    assert_eq!(result_err, KvError::NotFound("User not found".to_string()));
 
     let cmd = Command::from_str("CREATE_USER user Password4").unwrap();
     let (result, _) = data.handle_command(cmd, create_session()).await.unwrap();
@@ -22,5 +22,5 @@ async fn test_command_get_user() {
     let cmd = Command::from_str("GET_USER user").unwrap();
     let (result, _) = data.handle_command(cmd, create_session()).await.unwrap();
 
-    assert_eq!(result, "User: user Permissions: 0".to_string())
+    assert_eq!(result, "User: user Permissions: NONE".to_string())
 }
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::{commands::Command, data::test::data_tests_utils::*, session::Session};
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError, session::Session};
 
 #[tokio::test]
 async fn test_command_grant_number() {
@@ -27,7 +27,10 @@ async fn test_command_grant_number() {
         .await
         .unwrap();
 
-    assert_eq!(result, "User: user Permissions: 1".to_string());
+    assert_eq!(
+        result,
+        "User: user Permissions: SET,STORE_CREATE".to_string()
+    );
 }
 
 #[tokio::test]
@@ -55,7 +58,7 @@ async fn test_command_grant_name() {
         .await
         .unwrap();
 
-    assert_eq!(result, "User: user Permissions: 8".to_string());
+    assert_eq!(result, "User: user Permissions: USER_ADMIN".to_string());
 }
 
 #[tokio::test]
@@ -83,7 +86,10 @@ async fn test_command_grant_name_multiple() {
         .await
         .unwrap();
 
-    assert_eq!(result, "User: user Permissions: 11".to_string());
+    assert_eq!(
+        result,
+        "User: user Permissions: SET,GET,USER_ADMIN".to_string()
+    );
 }
 
 #[tokio::test]
@@ -116,5 +122,5 @@ async fn test_command_grant_not_more_than_users() {
         .await
         .unwrap_err();
 
-    assert_eq!(result, "User does not have permission".to_string());
+    assert_eq!(result, KvError::NoPermission);
 }
@@ -0,0 +1,139 @@
+use crate::{data::test::data_tests_utils::*, error::KvError};
+
+#[tokio::test]
+async fn test_command_json_set_and_get() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let result = run(
+        &mut data,
+        session.clone(),
+        r#"SET user "{\"name\": \"alice\", \"tags\": [\"a\"]}" JSON"#,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result, "OK".to_string());
+
+    let result = run(&mut data, session.clone(), "JSON.GET user $.name")
+        .await
+        .unwrap();
+    assert_eq!(result, r#""alice""#.to_string());
+
+    let result = run(&mut data, session.clone(), "JSON.SET user $.age 30")
+        .await
+        .unwrap();
+    assert_eq!(result, "OK".to_string());
+
+    let result = run(
+        &mut data,
+        session.clone(),
+        r#"JSON.SET user $.tags[0] "\"b\"""#,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result, "OK".to_string());
+
+    let result = run(&mut data, session.clone(), "JSON.GET user")
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        r#"{"age":30,"name":"alice","tags":["b"]}"#.to_string()
+    );
+
+    let result = run(&mut data, session.clone(), "GET user").await.unwrap();
+    assert_eq!(
+        result,
+        r#"{"age":30,"name":"alice","tags":["b"]}"#.to_string()
+    );
+
+    let result_err = run(&mut data, session.clone(), "JSON.GET user $.email")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+
+    // Only the whole document can be set on a missing key.
+    let result_err = run(&mut data, session.clone(), "JSON.SET other $.a 1")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+
+    let result = run(&mut data, session.clone(), "JSON.SET other $ [1]")
+        .await
+        .unwrap();
+    assert_eq!(result, "OK".to_string());
+
+    let result = run(&mut data, session, "JSON.GET other $[0]")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+}
+
+#[tokio::test]
+async fn test_command_json_del_and_arrappend() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(
+        &mut data,
+        session.clone(),
+        r#"JSON.SET user $ "{\"name\": \"alice\", \"tags\": []}""#,
+    )
+    .await
+    .unwrap();
+
+    let result = run(
+        &mut data,
+        session.clone(),
+        r#"JSON.ARRAPPEND user $.tags "\"a\"" 2"#,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result, "2".to_string());
+
+    let result_err = run(&mut data, session.clone(), "JSON.ARRAPPEND user $.name 1")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::WrongType(_)));
+
+    let result = run(&mut data, session.clone(), "JSON.DEL user $.tags[0]")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result = run(&mut data, session.clone(), "JSON.DEL user $.email")
+        .await
+        .unwrap();
+    assert_eq!(result, "0".to_string());
+
+    let result = run(&mut data, session.clone(), "JSON.GET user")
+        .await
+        .unwrap();
+    assert_eq!(result, r#"{"name":"alice","tags":[2]}"#.to_string());
+
+    let result = run(&mut data, session.clone(), "JSON.DEL user")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result = run(&mut data, session, "JSON.DEL user").await.unwrap();
+    assert_eq!(result, "0".to_string());
+}
+
+#[tokio::test]
+async fn test_command_json_wrong_type() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET name alice")
+        .await
+        .unwrap();
+
+    let result_err = run(&mut data, session.clone(), "JSON.GET name")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::WrongType(_)));
+
+    let result_err = run(&mut data, session, "JSON.DEL name").await.unwrap_err();
+    assert!(matches!(result_err, KvError::WrongType(_)));
+}
@@ -0,0 +1,234 @@
+use std::{str::FromStr, sync::Arc};
+
+use tokio::sync::Mutex;
+
+use crate::{
+    commands::Command,
+    config::Config,
+    data::{test::data_tests_utils::*, DataManager},
+    error::KvError,
+    session::Session,
+};
+
+#[tokio::test]
+async fn test_command_push_and_range() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let result = run(&mut data, session.clone(), "RPUSH list b c")
+        .await
+        .unwrap();
+    assert_eq!(result, "2".to_string());
+
+    let result = run(&mut data, session.clone(), "LPUSH list a z")
+        .await
+        .unwrap();
+    assert_eq!(result, "4".to_string());
+
+    let result = run(&mut data, session.clone(), "LRANGE list 0 -1")
+        .await
+        .unwrap();
+    assert_eq!(result, "z\na\nb\nc".to_string());
+
+    let result = run(&mut data, session.clone(), "LRANGE list 1 2")
+        .await
+        .unwrap();
+    assert_eq!(result, "a\nb".to_string());
+
+    let result = run(&mut data, session.clone(), "LLEN list").await.unwrap();
+    assert_eq!(result, "4".to_string());
+
+    let result = run(&mut data, session.clone(), "LINDEX list -1")
+        .await
+        .unwrap();
+    assert_eq!(result, "c".to_string());
+
+    let result_err = run(&mut data, session.clone(), "LINDEX list 10")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+
+    let result = run(&mut data, session, "GET list").await.unwrap();
+    assert_eq!(result, r#"["z","a","b","c"]"#.to_string());
+}
+
+#[tokio::test]
+async fn test_command_pop() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "RPUSH list a b c")
+        .await
+        .unwrap();
+
+    let result = run(&mut data, session.clone(), "LPOP list").await.unwrap();
+    assert_eq!(result, "a".to_string());
+
+    let result = run(&mut data, session.clone(), "RPOP list").await.unwrap();
+    assert_eq!(result, "c".to_string());
+
+    let result = run(&mut data, session.clone(), "LPOP list").await.unwrap();
+    assert_eq!(result, "b".to_string());
+
+    // The list is removed once it is empty.
+    let result_err = run(&mut data, session.clone(), "GET list")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+
+    let result_err = run(&mut data, session.clone(), "LPOP list")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+
+    let result = run(&mut data, session.clone(), "LLEN list").await.unwrap();
+    assert_eq!(result, "0".to_string());
+
+    let result = run(&mut data, session, "LRANGE list 0 -1").await.unwrap();
+    assert_eq!(result, "".to_string());
+}
+
+#[tokio::test]
+async fn test_command_lrem() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "RPUSH list a b a c a")
+        .await
+        .unwrap();
+
+    let result = run(&mut data, session.clone(), "LREM list -2 a")
+        .await
+        .unwrap();
+    assert_eq!(result, "2".to_string());
+
+    let result = run(&mut data, session.clone(), "LRANGE list 0 -1")
+        .await
+        .unwrap();
+    assert_eq!(result, "a\nb\nc".to_string());
+
+    let result = run(&mut data, session.clone(), "LREM list 0 x")
+        .await
+        .unwrap();
+    assert_eq!(result, "0".to_string());
+
+    let result = run(&mut data, session, "LREM missing 0 a").await.unwrap();
+    assert_eq!(result, "0".to_string());
+}
+
+#[tokio::test]
+async fn test_command_typed_list() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(
+        &mut data,
+        session.clone(),
+        r#"SET scores "[1, 2]" LIST<INT>"#,
+    )
+    .await
+    .unwrap();
+
+    let result = run(&mut data, session.clone(), "RPUSH scores 3")
+        .await
+        .unwrap();
+    assert_eq!(result, "3".to_string());
+
+    let result_err = run(&mut data, session.clone(), "RPUSH scores 4 four")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::WrongType(_)));
+
+    let result = run(&mut data, session.clone(), "GET scores").await.unwrap();
+    assert_eq!(result, "[1,2,3]".to_string());
+
+    let result = run(&mut data, session, "GET *").await.unwrap();
+    assert!(result.contains("scores: [1,2,3] (LIST<INT>)"));
+}
+
+#[tokio::test]
+async fn test_command_list_wrong_type() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET name john")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "RPUSH list a")
+        .await
+        .unwrap();
+
+    for line in ["LPUSH name a", "LPOP name", "LRANGE name 0 -1", "LLEN name"] {
+        let result_err = run(&mut data, session.clone(), line).await.unwrap_err();
+        assert!(matches!(result_err, KvError::WrongType(_)), "{}", line);
+    }
+
+    let result_err = run(&mut data, session, "INCR list").await.unwrap_err();
+    assert!(matches!(result_err, KvError::WrongType(_)));
+}
+
+#[tokio::test]
+async fn test_command_blpop() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "RPUSH second x")
+        .await
+        .unwrap();
+
+    let result = run(&mut data, session.clone(), "BLPOP first second 1")
+        .await
+        .unwrap();
+    assert_eq!(result, "second\nx".to_string());
+
+    let result_err = run(&mut data, session.clone(), "BLPOP first second 1")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+
+    // A push from another connection wakes up the waiting BLPOP.
+    let config = Arc::new(Mutex::new(Config::new()));
+    let mut other = DataManager::new(Arc::clone(&data.data), Arc::clone(&data.auth), config)
+        .await
+        .unwrap();
+    let pusher = tokio::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+        run(&mut other, create_session(), "RPUSH first y")
+            .await
+            .unwrap();
+    });
+
+    let result = run(&mut data, session, "BLPOP first 0").await.unwrap();
+    assert_eq!(result, "first\ny".to_string());
+    pusher.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_command_list_permissions() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "RPUSH list a")
+        .await
+        .unwrap();
+    run(
+        &mut data,
+        session.clone(),
+        "CREATE_USER reader Password1 GET",
+    )
+    .await
+    .unwrap();
+
+    let cmd = Command::from_str("AUTH reader Password1").unwrap();
+    let (_, reader) = data.handle_command(cmd, Session::new()).await.unwrap();
+
+    let result = run(&mut data, reader.clone(), "LRANGE list 0 -1")
+        .await
+        .unwrap();
+    assert_eq!(result, "a".to_string());
+
+    for line in ["RPUSH list b", "LPOP list", "LREM list 0 a", "BLPOP list 1"] {
+        let result_err = run(&mut data, reader.clone(), line).await.unwrap_err();
+        assert_eq!(result_err, KvError::NoPermission, "{}", line);
+    }
+}
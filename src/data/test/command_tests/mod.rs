@@ -1,10 +1,24 @@
+mod acl_tests;
 mod auth_tests;
+mod bytes_tests;
 mod create_store_tests;
 mod create_user_tests;
 mod del_tests;
 mod delete_user_tests;
+mod expire_tests;
 mod get_tests;
 mod get_user_tests;
 mod grant_tests;
+mod json_tests;
+mod list_tests;
+mod numeric_tests;
+mod password_tests;
+mod permission_tests;
+mod reserved_namespace_tests;
 mod revoke_tests;
+mod role_tests;
 mod set_tests;
+mod set_type_tests;
+mod sorted_set_tests;
+mod transaction_tests;
+mod version_tests;
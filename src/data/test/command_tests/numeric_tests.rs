@@ -0,0 +1,155 @@
+use crate::{data::test::data_tests_utils::*, error::KvError};
+
+#[tokio::test]
+async fn test_command_incr_and_decr() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    // Missing counters start at 0.
+    let result = run(&mut data, session.clone(), "INCR counter")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result = run(&mut data, session.clone(), "INCRBY counter 10")
+        .await
+        .unwrap();
+    assert_eq!(result, "11".to_string());
+
+    let result = run(&mut data, session.clone(), "DECR counter")
+        .await
+        .unwrap();
+    assert_eq!(result, "10".to_string());
+
+    let result = run(&mut data, session.clone(), "INCRBY counter -15")
+        .await
+        .unwrap();
+    assert_eq!(result, "-5".to_string());
+
+    let result = run(&mut data, session.clone(), "GET counter WITHVERSION")
+        .await
+        .unwrap();
+    assert_eq!(result, "4\n-5".to_string());
+}
+
+#[tokio::test]
+async fn test_command_incr_wrong_type() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET name john")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "SET price 1.5 FLOAT")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "CREATE_STORE store")
+        .await
+        .unwrap();
+
+    for line in ["INCR name", "DECR price", "INCRBY store 2"] {
+        let result_err = run(&mut data, session.clone(), line).await.unwrap_err();
+        assert!(matches!(result_err, KvError::WrongType(_)), "{}", line);
+    }
+
+    let result = run(&mut data, session, "GET name").await.unwrap();
+    assert_eq!(result, "john".to_string());
+}
+
+#[tokio::test]
+async fn test_command_incr_overflow() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(
+        &mut data,
+        session.clone(),
+        "SET big 9223372036854775807 INT",
+    )
+    .await
+    .unwrap();
+
+    let result_err = run(&mut data, session.clone(), "INCR big")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::BadArgs(_)));
+
+    // The value is left untouched.
+    let result = run(&mut data, session, "GET big WITHVERSION")
+        .await
+        .unwrap();
+    assert_eq!(result, "1\n9223372036854775807".to_string());
+}
+
+#[tokio::test]
+async fn test_command_incr_by_float() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let result = run(&mut data, session.clone(), "INCRBYFLOAT price 1.5")
+        .await
+        .unwrap();
+    assert_eq!(result, "1.5".to_string());
+
+    let result = run(&mut data, session.clone(), "INCRBYFLOAT price -0.25")
+        .await
+        .unwrap();
+    assert_eq!(result, "1.25".to_string());
+
+    run(&mut data, session.clone(), "SET count 1 INT")
+        .await
+        .unwrap();
+
+    let result_err = run(&mut data, session, "INCRBYFLOAT count 1.5")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::WrongType(_)));
+}
+
+#[tokio::test]
+async fn test_command_toggle() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let result_err = run(&mut data, session.clone(), "TOGGLE flag")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+
+    run(&mut data, session.clone(), "SET flag true BOOL")
+        .await
+        .unwrap();
+
+    let result = run(&mut data, session.clone(), "TOGGLE flag")
+        .await
+        .unwrap();
+    assert_eq!(result, "false".to_string());
+
+    let result = run(&mut data, session.clone(), "TOGGLE flag")
+        .await
+        .unwrap();
+    assert_eq!(result, "true".to_string());
+
+    run(&mut data, session.clone(), "SET name john")
+        .await
+        .unwrap();
+
+    let result_err = run(&mut data, session, "TOGGLE name").await.unwrap_err();
+    assert!(matches!(result_err, KvError::WrongType(_)));
+}
+
+#[tokio::test]
+async fn test_command_incr_keeps_expiry() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET counter 1 INT EX 100")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "INCR counter")
+        .await
+        .unwrap();
+
+    let result = run(&mut data, session, "TTL counter").await.unwrap();
+    assert_ne!(result, "-1".to_string());
+}
@@ -0,0 +1,191 @@
+use crate::{config::Config, data::test::data_tests_utils::*, error::KvError, session::Session};
+
+#[tokio::test]
+async fn test_command_password() {
+    let mut data = create_data_manager().await;
+
+    run(
+        &mut data,
+        create_session(),
+        "CREATE_USER alice Password4 GET",
+    )
+    .await
+    .unwrap();
+
+    let alice = Session::new().set_authenticated("alice");
+    assert_eq!(
+        run(&mut data, alice.clone(), "PASSWORD Password4 Password5").await,
+        Ok("OK".to_string())
+    );
+
+    assert_eq!(
+        run(&mut data, Session::new(), "AUTH alice Password4").await,
+        Err(KvError::AuthFailed)
+    );
+    assert!(run(&mut data, Session::new(), "AUTH alice Password5")
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_command_password_errors() {
+    let mut data = create_data_manager().await;
+
+    run(&mut data, create_session(), "CREATE_USER alice Password4")
+        .await
+        .unwrap();
+
+    let alice = Session::new().set_authenticated("alice");
+    assert_eq!(
+        run(&mut data, alice.clone(), "PASSWORD Wrong1234 Password5").await,
+        Err(KvError::AuthFailed)
+    );
+    for line in ["PASSWORD Password4 weak", "PASSWORD Password4 Password4"] {
+        let result = run(&mut data, alice.clone(), line).await;
+        assert!(
+            matches!(result, Err(KvError::InvalidPassword(_))),
+            "{}",
+            line
+        );
+    }
+    assert_eq!(
+        run(&mut data, Session::new(), "PASSWORD Password4 Password5").await,
+        Err(KvError::NotAuthenticated)
+    );
+}
+
+#[tokio::test]
+async fn test_command_set_password() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    for line in [
+        "CREATE_USER alice Password4 GET",
+        "CREATE_USER bob Password4 GET",
+    ] {
+        run(&mut data, admin.clone(), line).await.unwrap();
+    }
+
+    assert_eq!(
+        run(&mut data, admin.clone(), "SET_PASSWORD alice Password5").await,
+        Ok("OK".to_string())
+    );
+    assert!(run(&mut data, Session::new(), "AUTH alice Password5")
+        .await
+        .is_ok());
+
+    let result = run(&mut data, admin, "SET_PASSWORD carol Password5").await;
+    assert!(matches!(result, Err(KvError::NotFound(_))));
+
+    let bob = Session::new().set_authenticated("bob");
+    assert_eq!(
+        run(&mut data, bob, "SET_PASSWORD alice Password6").await,
+        Err(KvError::NoPermission)
+    );
+}
+
+#[tokio::test]
+async fn test_command_set_password_must_change() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    for line in [
+        "SET key value",
+        "CREATE_USER alice Password4 GET",
+        "SET_PASSWORD alice Password5 MUST_CHANGE",
+    ] {
+        run(&mut data, admin.clone(), line).await.unwrap();
+    }
+
+    let (_, alice) = run_session(&mut data, Session::new(), "AUTH alice Password5")
+        .await
+        .unwrap();
+    assert!(alice.must_change_password);
+    assert_eq!(
+        run(&mut data, alice.clone(), "GET key").await,
+        Err(KvError::PasswordChangeRequired)
+    );
+
+    let (_, alice) = run_session(&mut data, alice, "PASSWORD Password5 Password6")
+        .await
+        .unwrap();
+    assert!(!alice.must_change_password);
+    assert_eq!(
+        run(&mut data, alice, "GET key").await,
+        Ok("value".to_string())
+    );
+
+    let (_, alice) = run_session(&mut data, Session::new(), "AUTH alice Password6")
+        .await
+        .unwrap();
+    assert!(!alice.must_change_password);
+}
+
+#[tokio::test]
+async fn test_command_password_expiry() {
+    let mut config = Config::new();
+    config.add_password_max_age(0);
+    let mut data = create_data_manager_with_config(config).await;
+
+    run(
+        &mut data,
+        create_session(),
+        "CREATE_USER alice Password4 GET",
+    )
+    .await
+    .unwrap();
+
+    let (_, alice) = run_session(&mut data, Session::new(), "AUTH alice Password4")
+        .await
+        .unwrap();
+    assert!(alice.must_change_password);
+    assert_eq!(
+        run(&mut data, alice.clone(), "GET key").await,
+        Err(KvError::PasswordChangeRequired)
+    );
+
+    let (_, alice) = run_session(&mut data, alice, "PASSWORD Password4 Password5")
+        .await
+        .unwrap();
+    assert!(matches!(
+        run(&mut data, alice, "GET key").await,
+        Err(KvError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_command_set_password_needs_permissions_of_user() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    for line in [
+        "CREATE_USER helpdesk Password4 USER_ADMIN",
+        "CREATE_USER alice Password4",
+        "CREATE_ROLE reader GET ON orders:*",
+        "ASSIGN_ROLE alice reader",
+    ] {
+        run(&mut data, admin.clone(), line).await.unwrap();
+    }
+
+    let helpdesk = Session::new().set_authenticated("helpdesk");
+    assert_eq!(
+        run(&mut data, helpdesk.clone(), "SET_PASSWORD admin Password5").await,
+        Err(KvError::NoPermission)
+    );
+    assert!(run(&mut data, Session::new(), "AUTH admin Password5")
+        .await
+        .is_err());
+
+    // Permissions that come from a role count as well.
+    assert_eq!(
+        run(&mut data, helpdesk.clone(), "SET_PASSWORD alice Password5").await,
+        Err(KvError::NoPermission)
+    );
+    run(&mut data, admin, "GRANT helpdesk GET ON orders:*")
+        .await
+        .unwrap();
+    assert_eq!(
+        run(&mut data, helpdesk, "SET_PASSWORD alice Password5").await,
+        Ok("OK".to_string())
+    );
+}
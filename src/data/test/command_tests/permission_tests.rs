@@ -0,0 +1,86 @@
+use crate::{data::test::data_tests_utils::*, error::KvError, session::Session};
+
+#[tokio::test]
+async fn test_command_store_permissions() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    for line in [
+        "CREATE_STORE orders",
+        "SET orders:1 a",
+        "CREATE_USER alice Password4 SET GET DEL",
+    ] {
+        run(&mut data, admin.clone(), line).await.unwrap();
+    }
+
+    // Reading, writing and deleting keys does not cover the stores.
+    let alice = Session::new().set_authenticated("alice");
+    assert_eq!(
+        run(&mut data, alice.clone(), "GET orders:1").await,
+        Ok("a".to_string())
+    );
+    for line in ["CREATE_STORE customers", "LIST_KEYS orders", "DEL orders"] {
+        assert_eq!(
+            run(&mut data, alice.clone(), line).await,
+            Err(KvError::NoPermission),
+            "{}",
+            line
+        );
+    }
+    assert!(run(&mut data, alice.clone(), "DEL orders:1").await.is_ok());
+
+    run(
+        &mut data,
+        admin,
+        "GRANT alice STORE_CREATE,STORE_DELETE,LIST",
+    )
+    .await
+    .unwrap();
+    for line in ["CREATE_STORE customers", "LIST_KEYS orders", "DEL orders"] {
+        assert!(
+            run(&mut data, alice.clone(), line).await.is_ok(),
+            "{}",
+            line
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_command_numeric_permissions_cover_stores() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    run(&mut data, admin, "CREATE_USER alice Password4 7")
+        .await
+        .unwrap();
+
+    let alice = Session::new().set_authenticated("alice");
+    for line in ["CREATE_STORE orders", "LIST_KEYS .", "DEL orders"] {
+        assert!(
+            run(&mut data, alice.clone(), line).await.is_ok(),
+            "{}",
+            line
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_command_admin_permissions_can_not_be_scoped() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    run(&mut data, admin.clone(), "CREATE_USER alice Password4")
+        .await
+        .unwrap();
+
+    for line in [
+        "GRANT alice BACKUP ON orders:*",
+        "GRANT alice GET,ADMIN_CONFIG ON orders:*",
+    ] {
+        let result = run(&mut data, admin.clone(), line).await;
+        assert!(matches!(result, Err(KvError::BadArgs(_))), "{}", line);
+    }
+    assert!(run(&mut data, admin, "GRANT alice SUBSCRIBE ON orders:*")
+        .await
+        .is_ok());
+}
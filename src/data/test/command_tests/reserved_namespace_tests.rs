@@ -0,0 +1,37 @@
+use crate::{data::test::data_tests_utils::*, error::KvError};
+
+#[tokio::test]
+async fn test_command_reserved_namespace_is_forbidden() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    for line in [
+        "GET _auth:users:admin:password",
+        "SET _auth:x 1",
+        "DEL _auth",
+        "CREATE_STORE _auth",
+        "LIST_KEYS _auth",
+        "SUNION a _auth:set",
+    ] {
+        let result = run(&mut data, session.clone(), line).await;
+        assert!(matches!(result, Err(KvError::BadArgs(_))), "{}", line);
+    }
+}
+
+#[tokio::test]
+async fn test_command_users_are_not_in_the_data_store() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(
+        &mut data,
+        session.clone(),
+        "CREATE_USER alice Password4 GET",
+    )
+    .await
+    .unwrap();
+
+    let keys = run(&mut data, session, "LIST_KEYS .").await.unwrap();
+    assert!(!keys.contains("_auth"));
+    assert!(data.auth.lock().await.has_user("alice"));
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::{commands::Command, data::test::data_tests_utils::*, session::Session};
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError, session::Session};
 
 #[tokio::test]
 async fn test_command_revoke_number() {
@@ -27,7 +27,7 @@ async fn test_command_revoke_number() {
         .await
         .unwrap();
 
-    assert_eq!(result, "User: user Permissions: 0".to_string());
+    assert_eq!(result, "User: user Permissions: NONE".to_string());
 }
 
 #[tokio::test]
@@ -55,7 +55,7 @@ async fn test_command_revoke_name() {
         .await
         .unwrap();
 
-    assert_eq!(result, "User: user Permissions: 0".to_string());
+    assert_eq!(result, "User: user Permissions: NONE".to_string());
 }
 
 #[tokio::test]
@@ -83,7 +83,10 @@ async fn test_command_revoke_name_multiple() {
         .await
         .unwrap();
 
-    assert_eq!(result, "User: user Permissions: 1".to_string());
+    assert_eq!(
+        result,
+        "User: user Permissions: SET,STORE_CREATE,LIST".to_string()
+    );
 }
 
 #[tokio::test]
@@ -116,5 +119,5 @@ async fn test_command_revoke_not_more_than_users() {
         .await
         .unwrap_err();
 
-    assert_eq!(result, "User does not have permission".to_string());
+    assert_eq!(result, KvError::NoPermission);
 }
@@ -0,0 +1,147 @@
+use crate::{data::test::data_tests_utils::*, error::KvError, session::Session};
+
+#[tokio::test]
+async fn test_command_role_permissions_are_added_to_users() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    for line in [
+        "SET key value",
+        "CREATE_USER alice Password4 SET",
+        "CREATE_ROLE reader GET",
+    ] {
+        run(&mut data, admin.clone(), line).await.unwrap();
+    }
+
+    let alice = Session::new().set_authenticated("alice");
+    assert_eq!(
+        run(&mut data, alice.clone(), "GET key").await,
+        Err(KvError::NoPermission)
+    );
+
+    run(&mut data, admin.clone(), "ASSIGN_ROLE alice reader")
+        .await
+        .unwrap();
+    assert_eq!(
+        run(&mut data, alice.clone(), "GET key").await,
+        Ok("value".to_string())
+    );
+    assert!(run(&mut data, alice.clone(), "SET key other").await.is_ok());
+    assert_eq!(
+        run(&mut data, admin.clone(), "GET_USER alice").await,
+        Ok("User: alice Permissions: SET Roles: reader".to_string())
+    );
+
+    run(&mut data, admin, "UNASSIGN_ROLE alice reader")
+        .await
+        .unwrap();
+    assert_eq!(
+        run(&mut data, alice, "GET key").await,
+        Err(KvError::NoPermission)
+    );
+}
+
+#[tokio::test]
+async fn test_command_role_with_scope() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    for line in [
+        "CREATE_STORE orders",
+        "SET orders:1 a",
+        "SET customers b",
+        "CREATE_USER alice Password4",
+        "CREATE_ROLE orders_reader GET ON orders:*",
+        "ASSIGN_ROLE alice orders_reader",
+    ] {
+        run(&mut data, admin.clone(), line).await.unwrap();
+    }
+
+    let alice = Session::new().set_authenticated("alice");
+    assert_eq!(
+        run(&mut data, alice.clone(), "GET orders:1").await,
+        Ok("a".to_string())
+    );
+    assert_eq!(
+        run(&mut data, alice, "GET customers").await,
+        Err(KvError::NoPermission)
+    );
+}
+
+#[tokio::test]
+async fn test_command_list_and_delete_roles() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    assert_eq!(
+        run(&mut data, admin.clone(), "LIST_ROLES").await,
+        Ok("".to_string())
+    );
+
+    for line in [
+        "CREATE_USER alice Password4",
+        "CREATE_ROLE writer SET DEL",
+        "CREATE_ROLE reader GET ON orders:*",
+        "ASSIGN_ROLE alice writer",
+    ] {
+        run(&mut data, admin.clone(), line).await.unwrap();
+    }
+
+    let result = run(&mut data, admin.clone(), "CREATE_ROLE writer GET").await;
+    assert!(matches!(result, Err(KvError::AlreadyExists(_))));
+
+    assert_eq!(
+        run(&mut data, admin.clone(), "LIST_ROLES").await,
+        Ok(
+            "Role: reader Permissions: NONE ACL: orders:*=GET\nRole: writer Permissions: SET,DEL"
+                .to_string()
+        )
+    );
+
+    run(&mut data, admin.clone(), "DELETE_ROLE writer")
+        .await
+        .unwrap();
+    assert_eq!(
+        run(&mut data, admin.clone(), "GET_USER alice").await,
+        Ok("User: alice Permissions: NONE".to_string())
+    );
+
+    for line in ["DELETE_ROLE writer", "ASSIGN_ROLE alice writer"] {
+        let result = run(&mut data, admin.clone(), line).await;
+        assert!(matches!(result, Err(KvError::NotFound(_))), "{}", line);
+    }
+}
+
+#[tokio::test]
+async fn test_command_roles_require_user_admin() {
+    let mut data = create_data_manager().await;
+    let admin = create_session();
+
+    for line in [
+        "CREATE_USER alice Password4 USER_ADMIN GET",
+        "CREATE_USER bob Password4",
+        "CREATE_ROLE writer SET",
+    ] {
+        run(&mut data, admin.clone(), line).await.unwrap();
+    }
+
+    // Roles can only pass on permissions the assigning user holds.
+    let alice = Session::new().set_authenticated("alice");
+    assert_eq!(
+        run(&mut data, alice.clone(), "ASSIGN_ROLE bob writer").await,
+        Err(KvError::NoPermission)
+    );
+    assert_eq!(
+        run(&mut data, alice.clone(), "CREATE_ROLE deleter DEL").await,
+        Err(KvError::NoPermission)
+    );
+    assert!(run(&mut data, alice, "CREATE_ROLE reader GET")
+        .await
+        .is_ok());
+
+    let bob = Session::new().set_authenticated("bob");
+    assert_eq!(
+        run(&mut data, bob, "LIST_ROLES").await,
+        Err(KvError::NoPermission)
+    );
+}
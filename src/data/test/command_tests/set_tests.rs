@@ -3,6 +3,7 @@ use std::str::FromStr;
 use crate::{
     commands::Command,
     data::{test::data_tests_utils::*, DataTypes},
+    error::KvError,
     session::Session,
 };
 
@@ -135,7 +136,7 @@ async fn test_command_set_permission() {
 
     let result_err = data.handle_command(cmd, session.clone()).await.unwrap_err();
 
-    assert_eq!(result_err, "User does not have permission".to_string());
+    assert_eq!(result_err, KvError::NoPermission);
 
     let cmd = Command::from_str("GRANT user SET").unwrap();
     data.handle_command(cmd, admin_session.clone())
@@ -156,7 +157,7 @@ async fn test_command_set_check_auth() {
     let cmd = Command::from_str("SET key value").unwrap();
     let result = data.handle_command(cmd, session.clone()).await.unwrap_err();
 
-    assert_eq!(result, "User not authenticated".to_string());
+    assert_eq!(result, KvError::NotAuthenticated);
 
     let cmd = Command::from_str("CREATE_USER user Password4 SET").unwrap();
     data.handle_command(cmd, create_session()).await.unwrap();
@@ -0,0 +1,171 @@
+use std::str::FromStr;
+
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError, session::Session};
+
+#[tokio::test]
+async fn test_command_sadd_and_srem() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let result = run(&mut data, session.clone(), "SADD tags b a b")
+        .await
+        .unwrap();
+    assert_eq!(result, "2".to_string());
+
+    let result = run(&mut data, session.clone(), "SADD tags a c")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result = run(&mut data, session.clone(), "SMEMBERS tags")
+        .await
+        .unwrap();
+    assert_eq!(result, "a\nb\nc".to_string());
+
+    let result = run(&mut data, session.clone(), "SCARD tags").await.unwrap();
+    assert_eq!(result, "3".to_string());
+
+    let result = run(&mut data, session.clone(), "SISMEMBER tags b")
+        .await
+        .unwrap();
+    assert_eq!(result, "true".to_string());
+
+    let result = run(&mut data, session.clone(), "SREM tags b x")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result = run(&mut data, session.clone(), "SISMEMBER tags b")
+        .await
+        .unwrap();
+    assert_eq!(result, "false".to_string());
+
+    let result = run(&mut data, session.clone(), "GET tags").await.unwrap();
+    assert_eq!(result, r#"["a","c"]"#.to_string());
+
+    // The set is removed with its last member.
+    run(&mut data, session.clone(), "SREM tags a c")
+        .await
+        .unwrap();
+
+    let result_err = run(&mut data, session.clone(), "GET tags")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+
+    let result = run(&mut data, session, "SCARD tags").await.unwrap();
+    assert_eq!(result, "0".to_string());
+}
+
+#[tokio::test]
+async fn test_command_set_algebra_across_stores() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    for line in [
+        "CREATE_STORE a",
+        "CREATE_STORE a:b",
+        "CREATE_STORE c",
+        "SADD a:b:tags red green blue",
+        "SADD c:tags green yellow blue",
+    ] {
+        run(&mut data, session.clone(), line).await.unwrap();
+    }
+
+    let result = run(&mut data, session.clone(), "SUNION a:b:tags c:tags")
+        .await
+        .unwrap();
+    assert_eq!(result, "blue\ngreen\nred\nyellow".to_string());
+
+    let result = run(&mut data, session.clone(), "SINTER a:b:tags c:tags")
+        .await
+        .unwrap();
+    assert_eq!(result, "blue\ngreen".to_string());
+
+    let result = run(&mut data, session.clone(), "SDIFF a:b:tags c:tags")
+        .await
+        .unwrap();
+    assert_eq!(result, "red".to_string());
+
+    // Missing keys are empty sets.
+    let result = run(&mut data, session, "SINTER a:b:tags missing")
+        .await
+        .unwrap();
+    assert_eq!(result, "".to_string());
+}
+
+#[tokio::test]
+async fn test_command_set_from_json() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(
+        &mut data,
+        session.clone(),
+        r#"SET members "[\"bob\", \"alice\"]" SET"#,
+    )
+    .await
+    .unwrap();
+
+    let result = run(&mut data, session.clone(), "SMEMBERS members")
+        .await
+        .unwrap();
+    assert_eq!(result, "alice\nbob".to_string());
+
+    let result = run(&mut data, session, "GET *").await.unwrap();
+    assert!(result.contains(r#"members: ["alice","bob"] (SET)"#));
+}
+
+#[tokio::test]
+async fn test_command_set_wrong_type() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET name john")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "RPUSH list a")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "SADD tags a")
+        .await
+        .unwrap();
+
+    for line in [
+        "SADD name a",
+        "SISMEMBER list a",
+        "SUNION tags name",
+        "LPUSH tags a",
+    ] {
+        let result_err = run(&mut data, session.clone(), line).await.unwrap_err();
+        assert!(matches!(result_err, KvError::WrongType(_)), "{}", line);
+    }
+}
+
+#[tokio::test]
+async fn test_command_set_permissions() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SADD tags a")
+        .await
+        .unwrap();
+    run(
+        &mut data,
+        session.clone(),
+        "CREATE_USER reader Password1 GET",
+    )
+    .await
+    .unwrap();
+
+    let cmd = Command::from_str("AUTH reader Password1").unwrap();
+    let (_, reader) = data.handle_command(cmd, Session::new()).await.unwrap();
+
+    let result = run(&mut data, reader.clone(), "SUNION tags").await.unwrap();
+    assert_eq!(result, "a".to_string());
+
+    for line in ["SADD tags b", "SREM tags a"] {
+        let result_err = run(&mut data, reader.clone(), line).await.unwrap_err();
+        assert_eq!(result_err, KvError::NoPermission, "{}", line);
+    }
+}
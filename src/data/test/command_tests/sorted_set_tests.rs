@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use crate::{commands::Command, data::test::data_tests_utils::*, error::KvError, session::Session};
+
+#[tokio::test]
+async fn test_command_zadd_and_zscore() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let result = run(&mut data, session.clone(), "ZADD board 10 alice 7.5 bob")
+        .await
+        .unwrap();
+    assert_eq!(result, "2".to_string());
+
+    // Updating a score does not count as a new member.
+    let result = run(&mut data, session.clone(), "ZADD board 12 alice 3 carol")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result = run(&mut data, session.clone(), "ZSCORE board alice")
+        .await
+        .unwrap();
+    assert_eq!(result, "12".to_string());
+
+    let result = run(&mut data, session.clone(), "ZINCRBY board 2.5 bob")
+        .await
+        .unwrap();
+    assert_eq!(result, "10".to_string());
+
+    let result = run(&mut data, session.clone(), "ZINCRBY board 1 dave")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result = run(&mut data, session.clone(), "ZRANK board bob")
+        .await
+        .unwrap();
+    assert_eq!(result, "2".to_string());
+
+    let result_err = run(&mut data, session.clone(), "ZSCORE board nobody")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+
+    let result = run(&mut data, session, "GET board").await.unwrap();
+    assert_eq!(
+        result,
+        r#"{"dave":1.0,"carol":3.0,"bob":10.0,"alice":12.0}"#.to_string()
+    );
+}
+
+#[tokio::test]
+async fn test_command_zrange() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(
+        &mut data,
+        session.clone(),
+        "ZADD board 10 alice 7.5 bob 10 carol 3 dave",
+    )
+    .await
+    .unwrap();
+
+    let result = run(&mut data, session.clone(), "ZRANGE board 0 -1")
+        .await
+        .unwrap();
+    assert_eq!(result, "dave\nbob\nalice\ncarol".to_string());
+
+    let result = run(&mut data, session.clone(), "ZRANGE board -2 -1 WITHSCORES")
+        .await
+        .unwrap();
+    assert_eq!(result, "alice\n10\ncarol\n10".to_string());
+
+    let result = run(&mut data, session.clone(), "ZRANGEBYSCORE board 5 +inf")
+        .await
+        .unwrap();
+    assert_eq!(result, "bob\nalice\ncarol".to_string());
+
+    let result = run(
+        &mut data,
+        session.clone(),
+        "ZRANGEBYSCORE board -inf (10 WITHSCORES LIMIT 1 5",
+    )
+    .await
+    .unwrap();
+    assert_eq!(result, "bob\n7.5".to_string());
+
+    let result = run(&mut data, session, "ZRANGE missing 0 -1")
+        .await
+        .unwrap();
+    assert_eq!(result, "".to_string());
+}
+
+#[tokio::test]
+async fn test_command_zrem() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "ZADD queue 1 a 2 b")
+        .await
+        .unwrap();
+
+    let result = run(&mut data, session.clone(), "ZREM queue a x")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result = run(&mut data, session.clone(), "ZRANK queue b")
+        .await
+        .unwrap();
+    assert_eq!(result, "0".to_string());
+
+    // The sorted set is removed with its last member.
+    run(&mut data, session.clone(), "ZREM queue b")
+        .await
+        .unwrap();
+
+    let result_err = run(&mut data, session, "GET queue").await.unwrap_err();
+    assert!(matches!(result_err, KvError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn test_command_zset_from_json() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(
+        &mut data,
+        session.clone(),
+        r#"SET board "{\"alice\": 2, \"bob\": 1}" ZSET"#,
+    )
+    .await
+    .unwrap();
+
+    let result = run(&mut data, session.clone(), "ZRANGE board 0 -1")
+        .await
+        .unwrap();
+    assert_eq!(result, "bob\nalice".to_string());
+
+    run(&mut data, session.clone(), "SET name john")
+        .await
+        .unwrap();
+
+    for line in ["ZADD name 1 a", "ZSCORE name a", "SADD board a"] {
+        let result_err = run(&mut data, session.clone(), line).await.unwrap_err();
+        assert!(matches!(result_err, KvError::WrongType(_)), "{}", line);
+    }
+}
+
+#[tokio::test]
+async fn test_command_zset_permissions() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "ZADD board 1 a")
+        .await
+        .unwrap();
+    run(
+        &mut data,
+        session.clone(),
+        "CREATE_USER reader Password1 GET",
+    )
+    .await
+    .unwrap();
+
+    let cmd = Command::from_str("AUTH reader Password1").unwrap();
+    let (_, reader) = data.handle_command(cmd, Session::new()).await.unwrap();
+
+    let result = run(&mut data, reader.clone(), "ZSCORE board a")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    for line in ["ZADD board 2 b", "ZINCRBY board 1 a", "ZREM board a"] {
+        let result_err = run(&mut data, reader.clone(), line).await.unwrap_err();
+        assert_eq!(result_err, KvError::NoPermission, "{}", line);
+    }
+}
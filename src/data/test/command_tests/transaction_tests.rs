@@ -0,0 +1,196 @@
+use crate::{data::test::data_tests_utils::*, error::KvError, session::Session};
+
+#[tokio::test]
+async fn test_command_exec_applies_queued_commands() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let (result, session) = run_session(&mut data, session, "MULTI").await.unwrap();
+    assert_eq!(result, "OK".to_string());
+
+    let (result, session) = run_session(&mut data, session, "SET a 1 INT")
+        .await
+        .unwrap();
+    assert_eq!(result, "QUEUED".to_string());
+
+    let (_, session) = run_session(&mut data, session, "SET b 2 INT")
+        .await
+        .unwrap();
+
+    // Nothing is written before EXEC.
+    let (_, session) = run_session(&mut data, session, "GET a").await.unwrap();
+    let (result, session) = run_session(&mut data, session, "EXEC").await.unwrap();
+
+    assert_eq!(result, "OK\nOK\n1".to_string());
+    assert!(!session.in_transaction());
+
+    let (result, _) = run_session(&mut data, session, "GET b").await.unwrap();
+    assert_eq!(result, "2".to_string());
+}
+
+#[tokio::test]
+async fn test_command_exec_rolls_back_on_error() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let (_, session) = run_session(&mut data, session, "SET a 1 INT")
+        .await
+        .unwrap();
+    let (_, session) = run_session(&mut data, session, "MULTI").await.unwrap();
+    let (_, session) = run_session(&mut data, session, "SET a 2 INT")
+        .await
+        .unwrap();
+    let (_, session) = run_session(&mut data, session, "SET b 3 INT")
+        .await
+        .unwrap();
+    let (_, session) = run_session(&mut data, session, "DEL missing")
+        .await
+        .unwrap();
+
+    let result_err = run_session(&mut data, session.clone(), "EXEC")
+        .await
+        .unwrap_err();
+    assert_eq!(result_err, KvError::key_not_found());
+
+    let session = create_session();
+    let (result, session) = run_session(&mut data, session, "GET a").await.unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result_err = run_session(&mut data, session, "GET b").await.unwrap_err();
+    assert_eq!(result_err, KvError::key_not_found());
+}
+
+#[tokio::test]
+async fn test_command_exec_checks_permissions_first() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let (_, _) = run_session(&mut data, session, "CREATE_USER reader Password4 GET")
+        .await
+        .unwrap();
+
+    let reader = Session::new().set_authenticated("reader");
+
+    let (_, reader) = run_session(&mut data, reader, "MULTI").await.unwrap();
+    let (_, reader) = run_session(&mut data, reader, "GET missing").await.unwrap();
+    let (_, reader) = run_session(&mut data, reader, "SET key value")
+        .await
+        .unwrap();
+
+    let result_err = run_session(&mut data, reader, "EXEC").await.unwrap_err();
+    assert_eq!(result_err, KvError::NoPermission);
+}
+
+#[tokio::test]
+async fn test_command_transaction_errors() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let result_err = run_session(&mut data, session.clone(), "EXEC")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::BadArgs(_)));
+
+    let result_err = run_session(&mut data, session.clone(), "DISCARD")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::BadArgs(_)));
+
+    let (_, session) = run_session(&mut data, session, "MULTI").await.unwrap();
+
+    let result_err = run_session(&mut data, session.clone(), "MULTI")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::BadArgs(_)));
+
+    let result_err = run_session(&mut data, session.clone(), "DELETE_USER admin")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::BadArgs(_)));
+
+    let (_, session) = run_session(&mut data, session, "SET key value")
+        .await
+        .unwrap();
+    let (result, session) = run_session(&mut data, session, "DISCARD").await.unwrap();
+    assert_eq!(result, "OK".to_string());
+    assert!(!session.in_transaction());
+
+    let result_err = run_session(&mut data, session, "GET key")
+        .await
+        .unwrap_err();
+    assert_eq!(result_err, KvError::key_not_found());
+}
+
+#[tokio::test]
+async fn test_command_exec_rolls_back_stores() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let (_, session) = run_session(&mut data, session, "CREATE_STORE users")
+        .await
+        .unwrap();
+    let (_, session) = run_session(&mut data, session, "SET users:john 1 INT")
+        .await
+        .unwrap();
+    let (_, session) = run_session(&mut data, session, "MULTI").await.unwrap();
+    let (_, session) = run_session(&mut data, session, "SET users:john 2 INT")
+        .await
+        .unwrap();
+    let (_, session) = run_session(&mut data, session, "DEL users").await.unwrap();
+    let (_, session) = run_session(&mut data, session, "CREATE_STORE orders")
+        .await
+        .unwrap();
+    let (_, session) = run_session(&mut data, session, "SET orders:first 3 INT")
+        .await
+        .unwrap();
+    let (_, session) = run_session(&mut data, session, "DEL missing")
+        .await
+        .unwrap();
+
+    let result_err = run_session(&mut data, session.clone(), "EXEC")
+        .await
+        .unwrap_err();
+    assert_eq!(result_err, KvError::key_not_found());
+
+    let session = create_session();
+    let (result, session) = run_session(&mut data, session, "GET users:john WITHVERSION")
+        .await
+        .unwrap();
+    assert_eq!(result, "1\n1".to_string());
+
+    let (result, _) = run_session(&mut data, session, "LIST_KEYS .")
+        .await
+        .unwrap();
+    assert_eq!(result, "users".to_string());
+}
+
+#[tokio::test]
+async fn test_command_exec_checks_permissions_of_each_command() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let (_, _) = run_session(
+        &mut data,
+        session,
+        "CREATE_USER alice Password4 STORE_CREATE,DEL",
+    )
+    .await
+    .unwrap();
+
+    // The store only exists once CREATE_STORE ran, deleting it still needs
+    // STORE_DELETE.
+    let alice = Session::new().set_authenticated("alice");
+    let (_, alice) = run_session(&mut data, alice, "MULTI").await.unwrap();
+    let (_, alice) = run_session(&mut data, alice, "CREATE_STORE users")
+        .await
+        .unwrap();
+    let (_, alice) = run_session(&mut data, alice, "DEL users").await.unwrap();
+
+    let result_err = run_session(&mut data, alice, "EXEC").await.unwrap_err();
+    assert_eq!(result_err, KvError::NoPermission);
+
+    let (result, _) = run_session(&mut data, create_session(), "LIST_KEYS .")
+        .await
+        .unwrap();
+    assert_eq!(result, "".to_string());
+}
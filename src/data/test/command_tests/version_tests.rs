@@ -0,0 +1,258 @@
+use crate::{
+    data::{test::data_tests_utils::*, DataTypes, Key, Store},
+    error::KvError,
+};
+
+#[tokio::test]
+async fn test_command_get_with_version() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET key first")
+        .await
+        .unwrap();
+
+    let result = run(&mut data, session.clone(), "GET key WITHVERSION")
+        .await
+        .unwrap();
+    assert_eq!(result, "1\nfirst".to_string());
+
+    run(&mut data, session.clone(), "SET key second")
+        .await
+        .unwrap();
+
+    let result = run(&mut data, session.clone(), "GET key WITHVERSION")
+        .await
+        .unwrap();
+    assert_eq!(result, "2\nsecond".to_string());
+
+    let result = run(&mut data, session.clone(), "GET key").await.unwrap();
+    assert_eq!(result, "second".to_string());
+
+    run(&mut data, session.clone(), "CREATE_STORE store")
+        .await
+        .unwrap();
+
+    let result_err = run(&mut data, session, "GET store WITHVERSION")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::WrongType(_)));
+}
+
+#[tokio::test]
+async fn test_command_cas() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    // Version 0 only matches a key that does not exist yet.
+    let result = run(&mut data, session.clone(), "CAS key 0 10 INT")
+        .await
+        .unwrap();
+    assert_eq!(result, "1".to_string());
+
+    let result_err = run(&mut data, session.clone(), "CAS key 0 11 INT")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        result_err,
+        KvError::VersionMismatch("Expected version 0 but found 1".to_string())
+    );
+
+    let result = run(&mut data, session.clone(), "CAS key 1 11 INT")
+        .await
+        .unwrap();
+    assert_eq!(result, "2".to_string());
+
+    let result_err = run(&mut data, session.clone(), "CAS key 1 12 INT")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::VersionMismatch(_)));
+
+    let result = run(&mut data, session, "GET key WITHVERSION")
+        .await
+        .unwrap();
+    assert_eq!(result, "2\n11".to_string());
+}
+
+#[tokio::test]
+async fn test_command_set_conditions() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    let result_err = run(&mut data, session.clone(), "SET key value XX")
+        .await
+        .unwrap_err();
+    assert_eq!(result_err, KvError::key_not_found());
+
+    run(&mut data, session.clone(), "SET key value NX")
+        .await
+        .unwrap();
+
+    let result_err = run(&mut data, session.clone(), "SET key other NX")
+        .await
+        .unwrap_err();
+    assert!(matches!(result_err, KvError::AlreadyExists(_)));
+
+    run(&mut data, session.clone(), "SET key other XX")
+        .await
+        .unwrap();
+
+    let result = run(&mut data, session, "GET key").await.unwrap();
+    assert_eq!(result, "other".to_string());
+}
+
+#[tokio::test]
+async fn test_versions_are_persisted() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET key first")
+        .await
+        .unwrap();
+    run(&mut data, session, "SET key second").await.unwrap();
+
+    let json = serde_json::to_string(&*data.data.lock().await).unwrap();
+    let mut store: Store = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(store.get_version(Key::new("key".to_string())).unwrap(), 2);
+
+    // Values saved before versions existed start at version 1.
+    let mut store: Store = serde_json::from_str(
+        r#"{"name":".","data":{"key":{"value":"v","data_type":"STRING"}},"stores":{}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(store.get_version(Key::new("key".to_string())).unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_versions_continue_after_del() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET key first")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "DEL key").await.unwrap();
+    run(&mut data, session.clone(), "SET key second")
+        .await
+        .unwrap();
+
+    // A version read before the delete must not match the new value.
+    let result_err = run(&mut data, session.clone(), "CAS key 1 third")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        result_err,
+        KvError::VersionMismatch("Expected version 1 but found 2".to_string())
+    );
+
+    let result = run(&mut data, session, "GET key WITHVERSION")
+        .await
+        .unwrap();
+    assert_eq!(result, "2\nsecond".to_string());
+}
+
+#[tokio::test]
+async fn test_versions_continue_after_store_del() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "CREATE_STORE users")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "CREATE_STORE users:john")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "SET users:john:age 30 INT")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "SET users:john:age 31 INT")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "DEL users").await.unwrap();
+
+    run(&mut data, session.clone(), "CREATE_STORE users")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "CREATE_STORE users:john")
+        .await
+        .unwrap();
+    let result = run(&mut data, session, "CAS users:john:age 0 32 INT")
+        .await
+        .unwrap();
+    assert_eq!(result, "3".to_string());
+}
+
+#[test]
+fn test_versions_continue_after_expiry() {
+    let mut store = Store::new(".".to_string());
+    let key = Key::new("key".to_string());
+
+    store
+        .set(key.clone(), "first".to_string(), DataTypes::STRING)
+        .unwrap();
+    store.set_expiry(key.clone(), Some(1)).unwrap();
+    assert_eq!(store.remove_expired(2), 1);
+
+    store
+        .set(key.clone(), "second".to_string(), DataTypes::STRING)
+        .unwrap();
+    assert_eq!(store.get_version(key).unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_versions_increase_on_unchanged_update() {
+    let mut data = create_data_manager().await;
+    let session = create_session();
+
+    run(&mut data, session.clone(), "SET count 1 INT")
+        .await
+        .unwrap();
+    run(&mut data, session.clone(), "INCRBY count 0")
+        .await
+        .unwrap();
+
+    // Every write counts, like a SET of the value the key already has.
+    let result = run(&mut data, session, "GET count WITHVERSION")
+        .await
+        .unwrap();
+    assert_eq!(result, "2\n1".to_string());
+}
+
+#[test]
+fn test_deleted_keys_leave_nothing_behind() {
+    let mut store = Store::new(".".to_string());
+
+    for i in 0..3 {
+        let key = Key::new(format!("cache{}", i));
+        store
+            .set(key.clone(), "value".to_string(), DataTypes::STRING)
+            .unwrap();
+        store.set_expiry(key, Some(1)).unwrap();
+    }
+    assert_eq!(store.remove_expired(2), 3);
+
+    assert_eq!(
+        serde_json::to_string(&store).unwrap(),
+        r#"{"name":".","data":{},"stores":{},"version":3}"#
+    );
+}
+
+#[test]
+fn test_versions_continue_from_old_snapshots() {
+    // Snapshots written before the version counter existed.
+    let mut store: Store = serde_json::from_str(
+        r#"{"name":".","data":{"key":{"value":"v","data_type":"STRING","version":4}},"stores":{}}"#,
+    )
+    .unwrap();
+
+    store
+        .set(
+            Key::new("other".to_string()),
+            "v".to_string(),
+            DataTypes::STRING,
+        )
+        .unwrap();
+    assert_eq!(store.get_version(Key::new("other".to_string())).unwrap(), 5);
+}
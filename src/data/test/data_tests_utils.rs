@@ -1,23 +1,50 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
 use tokio::sync::Mutex;
 
 use crate::{
+    auth::AuthStore,
+    commands::Command,
     config::Config,
     data::{data_manager::DataManager, Store},
+    error::KvError,
     session::Session,
 };
 
 pub async fn create_data_manager() -> DataManager {
-    let config = Config::new();
+    create_data_manager_with_config(Config::new()).await
+}
+
+pub async fn create_data_manager_with_config(config: Config) -> DataManager {
     let shared_config = Arc::new(Mutex::new(config));
 
     let store = Store::new(".".to_string());
     let shared_store = Arc::new(Mutex::new(store));
 
-    DataManager::new(shared_store, shared_config).await.unwrap()
+    let shared_auth = Arc::new(Mutex::new(AuthStore::new()));
+
+    DataManager::new(shared_store, shared_auth, shared_config)
+        .await
+        .unwrap()
 }
 
 pub fn create_session() -> Session {
     Session::new().set_authenticated("admin")
 }
+
+// Runs a command line and returns its reply.
+pub async fn run(data: &mut DataManager, session: Session, line: &str) -> Result<String, KvError> {
+    run_session(data, session, line)
+        .await
+        .map(|(result, _)| result)
+}
+
+// Like `run`, also returning the session the command left behind.
+pub async fn run_session(
+    data: &mut DataManager,
+    session: Session,
+    line: &str,
+) -> Result<(String, Session), KvError> {
+    let cmd = Command::from_str(line).unwrap();
+    data.handle_command(cmd, session).await
+}
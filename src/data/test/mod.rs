@@ -1,3 +1,4 @@
 mod command_tests;
 mod data_tests_utils;
 mod key_tests;
+mod store_tests;
@@ -0,0 +1,79 @@
+use crate::data::{Data, DataTypes, Key, Store, StoreManager, TypedValue};
+use crate::error::KvError;
+
+#[cfg(test)]
+mod get_typed_value {
+    use super::*;
+
+    fn key(key: &str) -> Key {
+        Key::new(key.to_string())
+    }
+
+    #[test]
+    fn test_get_typed_value() {
+        let mut store = Store::new(".".to_string());
+        store
+            .set(key("count"), "42".to_string(), DataTypes::INT)
+            .unwrap();
+
+        assert_eq!(
+            store.get_typed_value(key("count")),
+            Ok(&TypedValue::Int(42))
+        );
+        assert_eq!(
+            store.get_typed_value(key("missing")),
+            Err(KvError::key_not_found())
+        );
+    }
+
+    #[test]
+    fn test_get_typed_value_nested_key() {
+        let mut store = Store::new(".".to_string());
+        store.set_store(key("users")).unwrap();
+        store.set_store(key("users:john")).unwrap();
+        store
+            .set(
+                key("users:john:active"),
+                "true".to_string(),
+                DataTypes::BOOL,
+            )
+            .unwrap();
+
+        assert_eq!(
+            store.get_typed_value(key("users:john:active")),
+            Ok(&TypedValue::Bool(true))
+        );
+        assert!(matches!(
+            store.get_typed_value(key("users:john")),
+            Err(KvError::WrongType(_))
+        ));
+        assert_eq!(
+            store.get_typed_value(key("users:jane:active")),
+            Err(KvError::key_not_found())
+        );
+    }
+
+    #[test]
+    fn test_get_typed_value_expired_key() {
+        let mut store = Store::new(".".to_string());
+        store
+            .set(key("session"), "token".to_string(), DataTypes::STRING)
+            .unwrap();
+        store.set_store(key("cache")).unwrap();
+        store
+            .set(key("cache:entry"), "1.5".to_string(), DataTypes::FLOAT)
+            .unwrap();
+
+        store.set_expiry(key("session"), Some(1)).unwrap();
+        store.set_expiry(key("cache"), Some(1)).unwrap();
+
+        assert_eq!(
+            store.get_typed_value(key("session")),
+            Err(KvError::key_not_found())
+        );
+        assert_eq!(
+            store.get_typed_value(key("cache:entry")),
+            Err(KvError::key_not_found())
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt::Display;
+
+use serde_json::Value;
+
+use super::{
+    binary::{Binary, Encoding},
+    list::List,
+    set::Set,
+    sorted_set::SortedSet,
+    DataTypes,
+};
+
+// Value of a key in its native form. Values are parsed once when they are
+// set, so e.g. the FLOATs `1.50` and `1.5` are the same value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypedValue {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    List(List),
+    Set(Set),
+    SortedSet(SortedSet),
+    Json(Value),
+    Bytes(Binary),
+}
+
+impl TypedValue {
+    // Parses a value written the way SET accepts it for `data_type`.
+    pub fn parse(value: &str, data_type: DataTypes) -> Result<TypedValue, String> {
+        let invalid = || "Invalid data type".to_string();
+
+        match data_type {
+            DataTypes::STRING => Ok(TypedValue::String(value.to_string())),
+            DataTypes::INT => value.parse().map(TypedValue::Int).map_err(|_| invalid()),
+            DataTypes::FLOAT => value.parse().map(TypedValue::Float).map_err(|_| invalid()),
+            DataTypes::BOOL => value.parse().map(TypedValue::Bool).map_err(|_| invalid()),
+            DataTypes::STORE => {
+                Err("Invalid data type. To create STORE use CREATE_STORE command".to_string())
+            }
+            DataTypes::LIST => List::parse(value, DataTypes::STRING).map(TypedValue::List),
+            DataTypes::SET => Set::parse(value).map(TypedValue::Set),
+            DataTypes::ZSET => SortedSet::parse(value).map(TypedValue::SortedSet),
+            DataTypes::JSON => serde_json::from_str(value)
+                .map(TypedValue::Json)
+                .map_err(|_| "Invalid JSON document".to_string()),
+            // Without an ENCODING option binary values are given in base64.
+            DataTypes::BYTES => Binary::decode(value, Encoding::BASE64).map(TypedValue::Bytes),
+        }
+    }
+
+    pub fn data_type(&self) -> DataTypes {
+        match self {
+            TypedValue::String(_) => DataTypes::STRING,
+            TypedValue::Int(_) => DataTypes::INT,
+            TypedValue::Float(_) => DataTypes::FLOAT,
+            TypedValue::Bool(_) => DataTypes::BOOL,
+            TypedValue::List(_) => DataTypes::LIST,
+            TypedValue::Set(_) => DataTypes::SET,
+            TypedValue::SortedSet(_) => DataTypes::ZSET,
+            TypedValue::Json(_) => DataTypes::JSON,
+            TypedValue::Bytes(_) => DataTypes::BYTES,
+        }
+    }
+}
+
+impl Display for TypedValue {
+    // Values are shown in their canonical form, which SET accepts back.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypedValue::String(value) => write!(f, "{}", value),
+            TypedValue::Int(value) => write!(f, "{}", value),
+            TypedValue::Float(value) => write!(f, "{}", value),
+            TypedValue::Bool(value) => write!(f, "{}", value),
+            TypedValue::List(list) => write!(f, "{}", list),
+            TypedValue::Set(set) => write!(f, "{}", set),
+            TypedValue::SortedSet(zset) => write!(f, "{}", zset),
+            TypedValue::Json(json) => write!(f, "{}", json),
+            TypedValue::Bytes(bytes) => write!(f, "{}", bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod typed_value_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_canonical_format() {
+        let first = TypedValue::parse("1.50", DataTypes::FLOAT).unwrap();
+        let second = TypedValue::parse("1.5", DataTypes::FLOAT).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.to_string(), "1.5");
+
+        assert_eq!(
+            TypedValue::parse("+007", DataTypes::INT),
+            Ok(TypedValue::Int(7))
+        );
+        assert_eq!(
+            TypedValue::parse(r#"{"a": [1, 2]}"#, DataTypes::JSON)
+                .unwrap()
+                .to_string(),
+            r#"{"a":[1,2]}"#
+        );
+        assert_eq!(
+            TypedValue::parse("true", DataTypes::BOOL)
+                .unwrap()
+                .data_type(),
+            DataTypes::BOOL
+        );
+
+        assert!(TypedValue::parse("1.5", DataTypes::INT).is_err());
+        assert!(TypedValue::parse("", DataTypes::STORE).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ErrorCode {
+    NOT_FOUND,
+    ALREADY_EXISTS,
+    NOT_AUTHENTICATED,
+    NO_PERMISSION,
+    AUTH_FAILED,
+    INVALID_PASSWORD,
+    PASSWORD_CHANGE_REQUIRED,
+    WRONG_TYPE,
+    VERSION_MISMATCH,
+    BAD_ARGS,
+    PROTOCOL,
+    PERSISTENCE,
+    CONNECTION,
+    INTERNAL,
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ErrorCode::NOT_FOUND => write!(f, "NOT_FOUND"),
+            ErrorCode::ALREADY_EXISTS => write!(f, "ALREADY_EXISTS"),
+            ErrorCode::NOT_AUTHENTICATED => write!(f, "NOT_AUTHENTICATED"),
+            ErrorCode::NO_PERMISSION => write!(f, "NO_PERMISSION"),
+            ErrorCode::AUTH_FAILED => write!(f, "AUTH_FAILED"),
+            ErrorCode::INVALID_PASSWORD => write!(f, "INVALID_PASSWORD"),
+            ErrorCode::PASSWORD_CHANGE_REQUIRED => write!(f, "PASSWORD_CHANGE_REQUIRED"),
+            ErrorCode::WRONG_TYPE => write!(f, "WRONG_TYPE"),
+            ErrorCode::VERSION_MISMATCH => write!(f, "VERSION_MISMATCH"),
+            ErrorCode::BAD_ARGS => write!(f, "BAD_ARGS"),
+            ErrorCode::PROTOCOL => write!(f, "PROTOCOL"),
+            ErrorCode::PERSISTENCE => write!(f, "PERSISTENCE"),
+            ErrorCode::CONNECTION => write!(f, "CONNECTION"),
+            ErrorCode::INTERNAL => write!(f, "INTERNAL"),
+        }
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NOT_FOUND" => Ok(ErrorCode::NOT_FOUND),
+            "ALREADY_EXISTS" => Ok(ErrorCode::ALREADY_EXISTS),
+            "NOT_AUTHENTICATED" => Ok(ErrorCode::NOT_AUTHENTICATED),
+            "NO_PERMISSION" => Ok(ErrorCode::NO_PERMISSION),
+            "AUTH_FAILED" => Ok(ErrorCode::AUTH_FAILED),
+            "INVALID_PASSWORD" => Ok(ErrorCode::INVALID_PASSWORD),
+            "PASSWORD_CHANGE_REQUIRED" => Ok(ErrorCode::PASSWORD_CHANGE_REQUIRED),
+            "WRONG_TYPE" => Ok(ErrorCode::WRONG_TYPE),
+            "VERSION_MISMATCH" => Ok(ErrorCode::VERSION_MISMATCH),
+            "BAD_ARGS" => Ok(ErrorCode::BAD_ARGS),
+            "PROTOCOL" => Ok(ErrorCode::PROTOCOL),
+            "PERSISTENCE" => Ok(ErrorCode::PERSISTENCE),
+            "CONNECTION" => Ok(ErrorCode::CONNECTION),
+            "INTERNAL" => Ok(ErrorCode::INTERNAL),
+            _ => Err("Invalid error code".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum KvError {
+    NotFound(String),
+    AlreadyExists(String),
+    NotAuthenticated,
+    NoPermission,
+    AuthFailed,
+    InvalidPassword(String),
+    // The password has expired or was reset and must be changed with
+    // PASSWORD before anything else can be done.
+    PasswordChangeRequired,
+    WrongType(String),
+    // A compare-and-set found another version than the expected one.
+    VersionMismatch(String),
+    BadArgs(String),
+    Protocol(String),
+    Persistence(String),
+    // Raised by clients when the server can't be reached.
+    Connection(String),
+    Internal(String),
+}
+
+impl KvError {
+    pub fn key_not_found() -> KvError {
+        KvError::NotFound("Key not found".to_string())
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            KvError::NotFound(_) => ErrorCode::NOT_FOUND,
+            KvError::AlreadyExists(_) => ErrorCode::ALREADY_EXISTS,
+            KvError::NotAuthenticated => ErrorCode::NOT_AUTHENTICATED,
+            KvError::NoPermission => ErrorCode::NO_PERMISSION,
+            KvError::AuthFailed => ErrorCode::AUTH_FAILED,
+            KvError::InvalidPassword(_) => ErrorCode::INVALID_PASSWORD,
+            KvError::PasswordChangeRequired => ErrorCode::PASSWORD_CHANGE_REQUIRED,
+            KvError::WrongType(_) => ErrorCode::WRONG_TYPE,
+            KvError::VersionMismatch(_) => ErrorCode::VERSION_MISMATCH,
+            KvError::BadArgs(_) => ErrorCode::BAD_ARGS,
+            KvError::Protocol(_) => ErrorCode::PROTOCOL,
+            KvError::Persistence(_) => ErrorCode::PERSISTENCE,
+            KvError::Connection(_) => ErrorCode::CONNECTION,
+            KvError::Internal(_) => ErrorCode::INTERNAL,
+        }
+    }
+
+    // Rebuilds an error from the code and message of an `ERR` reply.
+    pub fn from_code(code: ErrorCode, message: String) -> KvError {
+        match code {
+            ErrorCode::NOT_FOUND => KvError::NotFound(message),
+            ErrorCode::ALREADY_EXISTS => KvError::AlreadyExists(message),
+            ErrorCode::NOT_AUTHENTICATED => KvError::NotAuthenticated,
+            ErrorCode::NO_PERMISSION => KvError::NoPermission,
+            ErrorCode::AUTH_FAILED => KvError::AuthFailed,
+            ErrorCode::INVALID_PASSWORD => KvError::InvalidPassword(message),
+            ErrorCode::PASSWORD_CHANGE_REQUIRED => KvError::PasswordChangeRequired,
+            ErrorCode::WRONG_TYPE => KvError::WrongType(message),
+            ErrorCode::VERSION_MISMATCH => KvError::VersionMismatch(message),
+            ErrorCode::BAD_ARGS => KvError::BadArgs(message),
+            ErrorCode::PROTOCOL => KvError::Protocol(message),
+            ErrorCode::PERSISTENCE => KvError::Persistence(message),
+            ErrorCode::CONNECTION => KvError::Connection(message),
+            ErrorCode::INTERNAL => KvError::Internal(message),
+        }
+    }
+}
+
+impl Display for KvError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            KvError::NotAuthenticated => write!(f, "User not authenticated"),
+            KvError::NoPermission => write!(f, "User does not have permission"),
+            KvError::AuthFailed => write!(f, "Username or password is incorrect"),
+            KvError::PasswordChangeRequired => write!(f, "Password change required"),
+            KvError::NotFound(message)
+            | KvError::AlreadyExists(message)
+            | KvError::InvalidPassword(message)
+            | KvError::WrongType(message)
+            | KvError::VersionMismatch(message)
+            | KvError::BadArgs(message)
+            | KvError::Protocol(message)
+            | KvError::Persistence(message)
+            | KvError::Connection(message)
+            | KvError::Internal(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for KvError {}
+
+impl From<std::io::Error> for KvError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::InvalidInput => KvError::BadArgs(error.to_string()),
+            std::io::ErrorKind::InvalidData => KvError::Protocol(error.to_string()),
+            _ => KvError::Internal(error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod kv_error_tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_round_trip() {
+        let error = KvError::NotFound("Key not found".to_string());
+
+        assert_eq!(error.code(), ErrorCode::NOT_FOUND);
+        assert_eq!(error.code().to_string(), "NOT_FOUND");
+        assert_eq!(
+            ErrorCode::from_str(&error.code().to_string()).unwrap(),
+            ErrorCode::NOT_FOUND
+        );
+        assert!(ErrorCode::from_str("INVALID").is_err());
+    }
+
+    #[test]
+    fn test_error_from_code() {
+        let error = KvError::from_code(ErrorCode::NO_PERMISSION, "ignored".to_string());
+
+        assert_eq!(error, KvError::NoPermission);
+        assert_eq!(error.to_string(), "User does not have permission");
+
+        let error = KvError::from_code(ErrorCode::WRONG_TYPE, "Invalid data type".to_string());
+
+        assert_eq!(error, KvError::WrongType("Invalid data type".to_string()));
+    }
+
+    #[test]
+    fn test_error_from_io_error() {
+        let error = std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid command");
+
+        assert_eq!(
+            KvError::from(error),
+            KvError::BadArgs("Invalid command".to_string())
+        );
+    }
+}
@@ -0,0 +1,3 @@
+mod kv_error;
+
+pub use kv_error::*;
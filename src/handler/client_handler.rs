@@ -1,155 +1,99 @@
+use crate::auth::AuthStore;
 use crate::commands::Command;
 use crate::config::Config;
 use crate::data::{DataManager, Store};
-use crate::persistence::PersistenceType;
 use crate::session::Session;
+use crate::shutdown::ShutdownHandle;
+use futures::{SinkExt, StreamExt};
 use std::{str::FromStr, sync::Arc};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::Mutex;
-use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
-    net::TcpStream,
-};
+use tokio_util::codec::{Decoder, Framed};
 
-pub struct ClientHandler {
-    socket: TcpStream,
+use super::{codec::KvCodec, executor::execute_command, Reply};
+
+pub struct ClientHandler<S> {
+    socket: S,
     data: Arc<Mutex<Store>>,
+    auth: Arc<Mutex<AuthStore>>,
     config: Arc<Mutex<Config>>,
+    shutdown: ShutdownHandle,
 }
 
-impl<'a> ClientHandler {
-    pub fn new(socket: TcpStream, data: Arc<Mutex<Store>>, config: Arc<Mutex<Config>>) -> Self {
-        Self {
-            socket,
-            data,
-            config,
-        }
-    }
-
-    fn parse_line(&self, buf: [u8; 1024], line_length: usize) -> String {
-        let line = String::from_utf8_lossy(&buf[..line_length]);
-        let line = line.trim();
-        let line = String::from(line);
-        line
-    }
-
-    fn split_line(&self, line: String) -> Vec<String> {
-        line.split_inclusive(";")
-            .map(|x| x.to_string())
-            .collect::<Vec<String>>()
-    }
-
-    fn continue_command(&self, session: &mut Session, mut commands: Vec<String>) -> Vec<String> {
-        if session.incomplete_command.len() > 0 {
-            if commands.len() > 0 {
-                let first_command = commands.remove(0);
-                let real_first_command =
-                    format!("{} {}", session.incomplete_command, first_command);
-                commands.insert(0, real_first_command);
-                session.incomplete_command = "".to_string();
-            }
-        }
-        commands
-    }
-
-    async fn execute_command(
-        &self,
-        data: &mut DataManager,
-        session: Session,
-        command: Command,
-    ) -> Result<(String, Session), String> {
-        let result = data.handle_command(command, session).await?;
-        if data.persistence.get_type() == PersistenceType::JsonFile {
-            match data.save_to_file().await {
-                Ok(_) => Ok(result),
-                Err(e) => Err(e),
-            }
-        } else {
-            Ok(result)
+async fn handle_line(data_manager: &mut DataManager, session: &mut Session, line: &str) -> Reply {
+    match Command::from_str(line) {
+        Ok(cmd) => {
+            let result = execute_command(data_manager, session, cmd).await;
+            Reply::from(result.map(|payload| payload.to_string()))
         }
-    }
-
-    fn handle_command_result(
-        &self,
-        result: Result<(String, Session), String>,
-        session: &mut Session,
-    ) -> String {
-        match result {
-            Ok((response, new_session)) => {
-                session.update(new_session);
-                response.to_string()
-            }
-            Err(e) => e.to_string(),
+        Err(e) => {
+            session.fail_transaction();
+            Reply::Err(e.into())
         }
     }
+}
 
-    async fn write_results(&mut self, results: Vec<String>) {
-        let mut results_string = results.join(";");
-
-        if !results_string.ends_with(";") && results_string.len() > 0 {
-            results_string = results_string + ";";
-        }
-
-        if results_string.len() == 0 {
-            results_string = " ".to_string();
+impl<S> ClientHandler<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    pub fn new(
+        socket: S,
+        data: Arc<Mutex<Store>>,
+        auth: Arc<Mutex<AuthStore>>,
+        config: Arc<Mutex<Config>>,
+        shutdown: ShutdownHandle,
+    ) -> Self {
+        Self {
+            socket,
+            data,
+            auth,
+            config,
+            shutdown,
         }
-
-        let _ = self.socket.write_all(results_string.as_bytes()).await;
     }
 
-    async fn handle_client(mut self, data: Arc<Mutex<Store>>, config: Arc<Mutex<Config>>) {
-        let mut buf = [0; 1024];
+    async fn handle_client(self, data: Arc<Mutex<Store>>, config: Arc<Mutex<Config>>) {
+        let max_frame_size = config.lock().await.get_max_frame_size();
+        let mut framed = Framed::new(self.socket, KvCodec::new(max_frame_size));
         let mut session = Session::new();
-        let mut data_manager = DataManager::new(data, config).await.unwrap();
+        let mut data_manager = DataManager::new(data, self.auth, config)
+            .await
+            .unwrap()
+            .with_shutdown(self.shutdown.clone());
 
         loop {
-            match self.socket.read(&mut buf).await {
-                Ok(0) => return,
-                Err(e) => {
+            let frame = tokio::select! {
+                frame = framed.next() => frame,
+                _ = self.shutdown.cancelled() => break,
+            };
+
+            let line = match frame {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => {
                     eprintln!("failed to read from socket; err = {:?}", e);
+                    let _ = framed.send(Reply::Err(e.into()).to_string()).await;
                     return;
                 }
-                Ok(n) => {
-                    let line = self.parse_line(buf, n);
-
-                    let commands = self.split_line(line);
+                None => return,
+            };
 
-                    let mut commands = self.continue_command(&mut session, commands);
+            let result = handle_line(&mut data_manager, &mut session, &line).await;
 
-                    if !commands[commands.len() - 1].ends_with(";") {
-                        session.incomplete_command = commands[commands.len() - 1].to_string();
-                        commands.pop();
-                    }
-
-                    let commands = commands
-                        .iter()
-                        .map(|line| line.trim_end_matches(";"))
-                        .collect::<Vec<&str>>();
+            if let Err(e) = framed.send(result.to_string()).await {
+                eprintln!("failed to write to socket; err = {:?}", e);
+                return;
+            }
+        }
 
-                    let mut results = Vec::new();
+        // The server is shutting down: answer the commands of the batch that
+        // was already received before closing the connection.
+        let mut buffer = std::mem::take(framed.read_buffer_mut());
+        while let Ok(Some(line)) = framed.codec_mut().decode(&mut buffer) {
+            let result = handle_line(&mut data_manager, &mut session, &line).await;
 
-                    for line in commands {
-                        match Command::from_str(line) {
-                            Ok(cmd) => {
-                                results.push(
-                                    self.handle_command_result(
-                                        self.execute_command(
-                                            &mut data_manager,
-                                            session.clone(),
-                                            cmd,
-                                        )
-                                        .await,
-                                        &mut session,
-                                    ),
-                                );
-                            }
-                            Err(e) => {
-                                results.push(e.to_string());
-                            }
-                        }
-                    }
-                    data_manager.save_to_file().await.unwrap();
-                    self.write_results(results).await;
-                }
+            if framed.send(result.to_string()).await.is_err() {
+                return;
             }
         }
     }
@@ -157,7 +101,8 @@ impl<'a> ClientHandler {
     pub async fn spawn_handler(self) {
         let data = Arc::clone(&self.data);
         let config = Arc::clone(&self.config);
-        tokio::spawn(async move {
+        let shutdown = self.shutdown.clone();
+        shutdown.spawn(async move {
             self.handle_client(data, config).await;
         });
     }
@@ -0,0 +1,264 @@
+use bytes::{Buf, BufMut, BytesMut};
+use std::io::{Error, ErrorKind};
+use tokio_util::codec::{Decoder, Encoder};
+
+pub const COMMAND_TERMINATOR: u8 = b';';
+pub const LENGTH_TERMINATOR: u8 = b'\n';
+const QUOTE: u8 = b'"';
+const ESCAPE: u8 = b'\\';
+
+// Commands are terminated by `;` (outside of double quotes) and may arrive
+// split across several reads or batched together in a single read. Replies are
+// written as `<payload length>\n<payload>` so clients know exactly how much to
+// read.
+pub struct KvCodec {
+    max_frame_size: usize,
+    next_index: usize,
+    in_quotes: bool,
+    escaped: bool,
+}
+
+impl KvCodec {
+    pub fn new(max_frame_size: usize) -> Self {
+        Self {
+            max_frame_size,
+            next_index: 0,
+            in_quotes: false,
+            escaped: false,
+        }
+    }
+
+    fn find_terminator(&mut self, src: &BytesMut) -> Option<usize> {
+        for (index, byte) in src.iter().enumerate().skip(self.next_index) {
+            if self.escaped {
+                self.escaped = false;
+                continue;
+            }
+
+            match *byte {
+                QUOTE => self.in_quotes = !self.in_quotes,
+                ESCAPE if self.in_quotes => self.escaped = true,
+                COMMAND_TERMINATOR if !self.in_quotes => return Some(index),
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    fn frame_too_large(&self) -> Error {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Frame too large! Maximum size is {} bytes",
+                self.max_frame_size
+            ),
+        )
+    }
+}
+
+impl Decoder for KvCodec {
+    type Item = String;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<String>, Error> {
+        loop {
+            let frame_end = match self.find_terminator(src) {
+                Some(index) => index,
+                None => {
+                    if src.len() > self.max_frame_size {
+                        return Err(self.frame_too_large());
+                    }
+                    self.next_index = src.len();
+                    return Ok(None);
+                }
+            };
+
+            self.next_index = 0;
+
+            if frame_end > self.max_frame_size {
+                return Err(self.frame_too_large());
+            }
+
+            let frame = src.split_to(frame_end + 1);
+            let command = String::from_utf8_lossy(&frame[..frame_end])
+                .trim()
+                .to_string();
+
+            if !command.is_empty() {
+                return Ok(Some(command));
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<String>, Error> {
+        // An unterminated command left behind by a closing client is dropped.
+        self.decode(src)
+    }
+}
+
+impl Encoder<String> for KvCodec {
+    type Error = Error;
+
+    fn encode(&mut self, reply: String, dst: &mut BytesMut) -> Result<(), Error> {
+        let length = reply.len().to_string();
+
+        dst.reserve(length.len() + 1 + reply.len());
+        dst.put_slice(length.as_bytes());
+        dst.put_u8(LENGTH_TERMINATOR);
+        dst.put_slice(reply.as_bytes());
+
+        Ok(())
+    }
+}
+
+// Decodes a single length-prefixed reply, used by clients reading the
+// server's responses.
+pub fn decode_reply(src: &mut BytesMut) -> Result<Option<String>, Error> {
+    let header_end = match src.iter().position(|b| *b == LENGTH_TERMINATOR) {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+
+    let length = String::from_utf8_lossy(&src[..header_end])
+        .parse::<usize>()
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid reply length"))?;
+
+    if src.len() < header_end + 1 + length {
+        return Ok(None);
+    }
+
+    src.advance(header_end + 1);
+    let payload = src.split_to(length);
+
+    Ok(Some(String::from_utf8_lossy(&payload).to_string()))
+}
+
+#[cfg(test)]
+mod codec_tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_single_command() {
+        let mut codec = KvCodec::new(1024);
+        let mut buf = BytesMut::from("GET key;");
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some("GET key".to_string()));
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_batch() {
+        let mut codec = KvCodec::new(1024);
+        let mut buf = BytesMut::from("SET key1 value1; SET key2 value2;");
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some("SET key1 value1".to_string())
+        );
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some("SET key2 value2".to_string())
+        );
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_incomplete_command() {
+        let mut codec = KvCodec::new(1024);
+        let mut buf = BytesMut::from("SET key");
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+
+        buf.extend_from_slice(b" value;");
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some("SET key value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_skips_empty_commands() {
+        let mut codec = KvCodec::new(1024);
+        let mut buf = BytesMut::from(";\n;GET key;");
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some("GET key".to_string()));
+    }
+
+    #[test]
+    fn test_decode_quoted_terminator() {
+        let mut codec = KvCodec::new(1024);
+        let mut buf = BytesMut::from(r#"SET key "a;b";SET key2 "c\";";"#);
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(r#"SET key "a;b""#.to_string())
+        );
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(r#"SET key2 "c\";""#.to_string())
+        );
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_quoted_across_reads() {
+        let mut codec = KvCodec::new(1024);
+        let mut buf = BytesMut::from(r#"SET key "a;"#);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+
+        buf.extend_from_slice(b"b\\");
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+
+        buf.extend_from_slice(br#"";";"#);
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(r#"SET key "a;b\";""#.to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_frame_too_large() {
+        let mut codec = KvCodec::new(8);
+        let mut buf = BytesMut::from("SET key value");
+
+        assert!(codec.decode(&mut buf).is_err());
+
+        let mut codec = KvCodec::new(8);
+        let mut buf = BytesMut::from("SET key value;");
+
+        assert!(codec.decode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_encode_reply() {
+        let mut codec = KvCodec::new(1024);
+        let mut buf = BytesMut::new();
+
+        codec.encode("OK".to_string(), &mut buf).unwrap();
+
+        assert_eq!(&buf[..], b"2\nOK");
+    }
+
+    #[test]
+    fn test_decode_reply() {
+        let mut codec = KvCodec::new(1024);
+        let mut buf = BytesMut::new();
+
+        codec.encode("first".to_string(), &mut buf).unwrap();
+        codec.encode("".to_string(), &mut buf).unwrap();
+        buf.extend_from_slice(b"5\nthi");
+
+        assert_eq!(decode_reply(&mut buf).unwrap(), Some("first".to_string()));
+        assert_eq!(decode_reply(&mut buf).unwrap(), Some("".to_string()));
+        assert_eq!(decode_reply(&mut buf).unwrap(), None);
+
+        buf.extend_from_slice(b"rd");
+
+        assert_eq!(decode_reply(&mut buf).unwrap(), Some("third".to_string()));
+    }
+}
@@ -0,0 +1,38 @@
+use crate::commands::{Command, CommandNames};
+use crate::data::{DataManager, Payload};
+use crate::error::KvError;
+use crate::persistence::PersistenceType;
+use crate::session::Session;
+
+// Runs a parsed command for a connection, persisting the store after commands
+// that can change it and carrying any session changes (e.g. after AUTH or
+// MULTI) over to the connection.
+pub async fn execute_command(
+    data: &mut DataManager,
+    session: &mut Session,
+    command: Command,
+) -> Result<Payload, KvError> {
+    let name = command.name.clone();
+    let read_only = DataManager::is_read_only(&command);
+    let (response, new_session) = match data.execute(command, session.clone()).await {
+        Ok(result) => result,
+        Err(e) => {
+            // A failed EXEC or DISCARD still ends the transaction, any other
+            // error while queueing makes EXEC refuse to run it.
+            if matches!(name, CommandNames::EXEC | CommandNames::DISCARD) {
+                session.end_transaction();
+            } else {
+                session.fail_transaction();
+            }
+            return Err(e);
+        }
+    };
+
+    if !read_only && data.persistence.get_type() == PersistenceType::JsonFile {
+        data.save_to_file().await?;
+    }
+
+    session.update(new_session);
+
+    Ok(response)
+}
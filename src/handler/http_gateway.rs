@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    extract::{Path, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+use crate::auth::AuthStore;
+use crate::commands::Command;
+use crate::config::Config;
+use crate::data::{DataManager, Store};
+use crate::error::{ErrorCode, KvError};
+use crate::session::Session;
+use crate::shutdown::ShutdownHandle;
+
+use super::executor::execute_command;
+
+// Like the other listeners, every request gets its own `DataManager` and
+// only the stores are shared, so requests are not serialized behind one lock.
+#[derive(Clone)]
+struct GatewayState {
+    data: Arc<Mutex<Store>>,
+    auth: Arc<Mutex<AuthStore>>,
+    config: Arc<Mutex<Config>>,
+    tokens: Arc<Mutex<HashMap<String, Token>>>,
+}
+
+// Tokens remember when the password of their user was last changed, so
+// changing or resetting it invalidates every token issued before. They also
+// expire once they are older than the configured TTL.
+struct Token {
+    username: String,
+    password_changed_at: Option<u64>,
+    issued_at: Instant,
+}
+
+#[derive(Deserialize)]
+struct SetValueBody {
+    value: String,
+    #[serde(rename = "type")]
+    data_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CreateUserBody {
+    username: String,
+    password: String,
+    #[serde(default)]
+    permissions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PermissionsBody {
+    permissions: Vec<String>,
+}
+
+fn status_code(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::NOT_FOUND => StatusCode::NOT_FOUND,
+        ErrorCode::ALREADY_EXISTS => StatusCode::CONFLICT,
+        ErrorCode::VERSION_MISMATCH => StatusCode::PRECONDITION_FAILED,
+        ErrorCode::NOT_AUTHENTICATED | ErrorCode::AUTH_FAILED => StatusCode::UNAUTHORIZED,
+        ErrorCode::NO_PERMISSION | ErrorCode::PASSWORD_CHANGE_REQUIRED => StatusCode::FORBIDDEN,
+        ErrorCode::INVALID_PASSWORD
+        | ErrorCode::WRONG_TYPE
+        | ErrorCode::BAD_ARGS
+        | ErrorCode::PROTOCOL => StatusCode::BAD_REQUEST,
+        ErrorCode::CONNECTION => StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::PERSISTENCE | ErrorCode::INTERNAL => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn to_response(result: Result<String, KvError>) -> Response {
+    match result {
+        Ok(payload) => (
+            StatusCode::OK,
+            Json(json!({ "status": "OK", "payload": payload })),
+        )
+            .into_response(),
+        Err(e) => (
+            status_code(e.code()),
+            Json(json!({
+                "status": "ERR",
+                "code": e.code().to_string(),
+                "message": e.to_string(),
+            })),
+        )
+            .into_response(),
+    }
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn parse_basic_credentials(encoded: &str) -> Result<(String, String), KvError> {
+    let decoded = STANDARD
+        .decode(encoded.trim())
+        .map_err(|_| KvError::BadArgs("Invalid basic credentials".to_string()))?;
+    let decoded = String::from_utf8(decoded)
+        .map_err(|_| KvError::BadArgs("Invalid basic credentials".to_string()))?;
+
+    match decoded.split_once(':') {
+        Some((username, password)) => Ok((username.to_string(), password.to_string())),
+        None => Err(KvError::BadArgs("Invalid basic credentials".to_string())),
+    }
+}
+
+impl GatewayState {
+    async fn run_command(
+        &self,
+        session: &mut Session,
+        name: &str,
+        args: Vec<String>,
+    ) -> Result<String, KvError> {
+        let command = Command::from_parts(name, args)?;
+        let mut data_manager = DataManager::new(
+            Arc::clone(&self.data),
+            Arc::clone(&self.auth),
+            Arc::clone(&self.config),
+        )
+        .await?;
+        execute_command(&mut data_manager, session, command)
+            .await
+            .map(|payload| payload.to_string())
+    }
+
+    async fn login(&self, encoded: &str) -> Result<Session, KvError> {
+        let (username, password) = parse_basic_credentials(encoded)?;
+        let mut session = Session::new();
+        self.run_command(&mut session, "AUTH", vec![username, password])
+            .await?;
+        Ok(session)
+    }
+
+    // Requests without an `Authorization` header get an anonymous session so
+    // that `DataManager` reports the missing authentication like it does for
+    // every other listener.
+    async fn authenticate(&self, headers: &HeaderMap) -> Result<Session, KvError> {
+        let header = match headers.get(AUTHORIZATION) {
+            Some(header) => header
+                .to_str()
+                .map_err(|_| KvError::BadArgs("Invalid authorization header".to_string()))?,
+            None => return Ok(Session::new()),
+        };
+
+        if let Some(encoded) = header.strip_prefix("Basic ") {
+            return self.login(encoded).await;
+        }
+
+        if let Some(token) = header.strip_prefix("Bearer ") {
+            return self.authenticate_token(token.trim()).await;
+        }
+
+        Err(KvError::BadArgs(
+            "Unsupported authorization scheme".to_string(),
+        ))
+    }
+
+    async fn authenticate_token(&self, token: &str) -> Result<Session, KvError> {
+        let token_ttl = self.config.lock().await.get_token_ttl();
+        let mut tokens = self.tokens.lock().await;
+        let owner = tokens.get(token).ok_or(KvError::AuthFailed)?;
+        let username = owner.username.clone();
+
+        if owner.issued_at.elapsed() >= token_ttl
+            || self.password_changed_at(&username).await != Some(owner.password_changed_at)
+        {
+            tokens.remove(token);
+            return Err(KvError::AuthFailed);
+        }
+        Ok(Session::new().set_authenticated(&username))
+    }
+
+    // `None` if the user does not exist (anymore).
+    async fn password_changed_at(&self, username: &str) -> Option<Option<u64>> {
+        self.auth
+            .lock()
+            .await
+            .get_user(username)
+            .map(|user| user.password_changed_at)
+    }
+
+    async fn issue_token(&self, session: Session) -> Result<String, KvError> {
+        // A token would let the user skip the password change.
+        if session.must_change_password {
+            return Err(KvError::PasswordChangeRequired);
+        }
+        let password_changed_at = self
+            .password_changed_at(&session.username)
+            .await
+            .ok_or(KvError::AuthFailed)?;
+
+        let token_ttl = self.config.lock().await.get_token_ttl();
+        let mut tokens = self.tokens.lock().await;
+        // Expired tokens that were never used again are dropped here.
+        tokens.retain(|_, token| token.issued_at.elapsed() < token_ttl);
+
+        let token = generate_token();
+        tokens.insert(
+            token.clone(),
+            Token {
+                username: session.username,
+                password_changed_at,
+                issued_at: Instant::now(),
+            },
+        );
+        Ok(token)
+    }
+
+    async fn handle(&self, headers: &HeaderMap, name: &str, args: Vec<String>) -> Response {
+        let result = match self.authenticate(headers).await {
+            Ok(mut session) => self.run_command(&mut session, name, args).await,
+            Err(e) => Err(e),
+        };
+        to_response(result)
+    }
+}
+
+async fn create_token(State(state): State<GatewayState>, headers: HeaderMap) -> Response {
+    let encoded = headers
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Basic "));
+
+    let result = match encoded {
+        Some(encoded) => match state.login(encoded).await {
+            Ok(session) => state.issue_token(session).await,
+            Err(e) => Err(e),
+        },
+        None => Err(KvError::NotAuthenticated),
+    };
+
+    to_response(result)
+}
+
+async fn revoke_token(State(state): State<GatewayState>, headers: HeaderMap) -> Response {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "));
+
+    let result = match token {
+        Some(token) => match state.tokens.lock().await.remove(token.trim()) {
+            Some(_) => Ok("OK".to_string()),
+            None => Err(KvError::AuthFailed),
+        },
+        None => Err(KvError::NotAuthenticated),
+    };
+
+    to_response(result)
+}
+
+async fn get_key(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+) -> Response {
+    state.handle(&headers, "GET", vec![key]).await
+}
+
+async fn set_key(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+    Json(body): Json<SetValueBody>,
+) -> Response {
+    let mut args = vec![key, body.value];
+    if let Some(data_type) = body.data_type {
+        args.push(data_type);
+    }
+    state.handle(&headers, "SET", args).await
+}
+
+async fn del_key(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+) -> Response {
+    state.handle(&headers, "DEL", vec![key]).await
+}
+
+async fn list_root_keys(State(state): State<GatewayState>, headers: HeaderMap) -> Response {
+    state
+        .handle(&headers, "LIST_KEYS", vec![".".to_string()])
+        .await
+}
+
+async fn create_store(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Path(path): Path<String>,
+) -> Response {
+    state.handle(&headers, "CREATE_STORE", vec![path]).await
+}
+
+async fn list_keys(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Path(path): Path<String>,
+) -> Response {
+    state.handle(&headers, "LIST_KEYS", vec![path]).await
+}
+
+async fn create_user(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Json(body): Json<CreateUserBody>,
+) -> Response {
+    let mut args = vec![body.username, body.password];
+    args.extend(body.permissions);
+    state.handle(&headers, "CREATE_USER", args).await
+}
+
+async fn get_user(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Path(username): Path<String>,
+) -> Response {
+    state.handle(&headers, "GET_USER", vec![username]).await
+}
+
+async fn delete_user(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Path(username): Path<String>,
+) -> Response {
+    state.handle(&headers, "DELETE_USER", vec![username]).await
+}
+
+async fn grant(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Path(username): Path<String>,
+    Json(body): Json<PermissionsBody>,
+) -> Response {
+    let mut args = vec![username];
+    args.extend(body.permissions);
+    state.handle(&headers, "GRANT", args).await
+}
+
+async fn revoke(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Path(username): Path<String>,
+    Json(body): Json<PermissionsBody>,
+) -> Response {
+    let mut args = vec![username];
+    args.extend(body.permissions);
+    state.handle(&headers, "REVOKE", args).await
+}
+
+fn router(state: GatewayState) -> Router {
+    Router::new()
+        .route("/v1/auth/token", post(create_token).delete(revoke_token))
+        .route("/v1/keys/:key", get(get_key).put(set_key).delete(del_key))
+        .route("/v1/stores", get(list_root_keys))
+        .route("/v1/stores/:path", post(create_store))
+        .route("/v1/stores/:path/keys", get(list_keys))
+        .route("/v1/users", post(create_user))
+        .route("/v1/users/:username", get(get_user).delete(delete_user))
+        .route("/v1/users/:username/grant", post(grant))
+        .route("/v1/users/:username/revoke", post(revoke))
+        .with_state(state)
+}
+
+pub async fn serve_http(
+    listener: TcpListener,
+    data: Arc<Mutex<Store>>,
+    auth: Arc<Mutex<AuthStore>>,
+    config: Arc<Mutex<Config>>,
+    shutdown: ShutdownHandle,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let state = GatewayState {
+        data,
+        auth,
+        config,
+        tokens: Arc::new(Mutex::new(HashMap::new())),
+    };
+
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(async move { shutdown.cancelled().await })
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod http_gateway_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_basic_credentials() {
+        let encoded = STANDARD.encode("admin:Pass:word4");
+
+        assert_eq!(
+            parse_basic_credentials(&encoded).unwrap(),
+            ("admin".to_string(), "Pass:word4".to_string())
+        );
+        assert!(parse_basic_credentials("not base64!").is_err());
+        assert!(parse_basic_credentials(&STANDARD.encode("admin")).is_err());
+    }
+
+    #[test]
+    fn test_status_code() {
+        assert_eq!(status_code(ErrorCode::NOT_FOUND), StatusCode::NOT_FOUND);
+        assert_eq!(status_code(ErrorCode::NO_PERMISSION), StatusCode::FORBIDDEN);
+        assert_eq!(
+            status_code(ErrorCode::NOT_AUTHENTICATED),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(status_code(ErrorCode::BAD_ARGS), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let token = generate_token();
+
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_token());
+    }
+}
@@ -1,3 +1,16 @@
 mod client_handler;
+mod codec;
+mod executor;
+mod http_gateway;
+mod reply;
+mod resp_codec;
+mod resp_handler;
+mod tls;
 
 pub use client_handler::ClientHandler;
+pub use codec::*;
+pub use http_gateway::serve_http;
+pub use reply::Reply;
+pub use resp_codec::{RespCodec, RespValue};
+pub use resp_handler::RespHandler;
+pub use tls::{accept_connection, MaybeTlsStream, TlsListener};
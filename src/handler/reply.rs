@@ -0,0 +1,101 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::{ErrorCode, KvError};
+
+// Every reply starts with its status: `OK <payload>` on success or
+// `ERR <code> <message>` on failure.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Reply {
+    Ok(String),
+    Err(KvError),
+}
+
+impl From<Result<String, KvError>> for Reply {
+    fn from(result: Result<String, KvError>) -> Self {
+        match result {
+            Ok(payload) => Reply::Ok(payload),
+            Err(e) => Reply::Err(e),
+        }
+    }
+}
+
+impl From<Reply> for Result<String, KvError> {
+    fn from(reply: Reply) -> Self {
+        match reply {
+            Reply::Ok(payload) => Ok(payload),
+            Reply::Err(e) => Err(e),
+        }
+    }
+}
+
+impl Display for Reply {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Reply::Ok(payload) => write!(f, "OK {}", payload),
+            Reply::Err(e) => write!(f, "ERR {} {}", e.code(), e),
+        }
+    }
+}
+
+impl FromStr for Reply {
+    type Err = KvError;
+
+    fn from_str(s: &str) -> Result<Self, KvError> {
+        let invalid_reply = || KvError::Protocol(format!("Invalid reply: {}", s));
+
+        let (status, rest) = s.split_once(' ').ok_or_else(invalid_reply)?;
+
+        match status {
+            "OK" => Ok(Reply::Ok(rest.to_string())),
+            "ERR" => {
+                let (code, message) = rest.split_once(' ').unwrap_or((rest, ""));
+                let code = ErrorCode::from_str(code).map_err(|_| invalid_reply())?;
+                Ok(Reply::Err(KvError::from_code(code, message.to_string())))
+            }
+            _ => Err(invalid_reply()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod reply_tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_to_string() {
+        assert_eq!(Reply::Ok("OK".to_string()).to_string(), "OK OK");
+        assert_eq!(Reply::Ok("".to_string()).to_string(), "OK ");
+        assert_eq!(
+            Reply::Err(KvError::key_not_found()).to_string(),
+            "ERR NOT_FOUND Key not found"
+        );
+        assert_eq!(
+            Reply::Err(KvError::NoPermission).to_string(),
+            "ERR NO_PERMISSION User does not have permission"
+        );
+    }
+
+    #[test]
+    fn test_reply_from_str() {
+        assert_eq!(
+            Reply::from_str("OK Key not found").unwrap(),
+            Reply::Ok("Key not found".to_string())
+        );
+        assert_eq!(Reply::from_str("OK ").unwrap(), Reply::Ok("".to_string()));
+        assert_eq!(
+            Reply::from_str("ERR NOT_FOUND Key not found").unwrap(),
+            Reply::Err(KvError::key_not_found())
+        );
+        assert!(Reply::from_str("OK").is_err());
+        assert!(Reply::from_str("ERR UNKNOWN_CODE message").is_err());
+        assert!(Reply::from_str("value").is_err());
+    }
+
+    #[test]
+    fn test_reply_round_trip() {
+        let reply = Reply::Err(KvError::WrongType("Invalid data type".to_string()));
+
+        assert_eq!(Reply::from_str(&reply.to_string()).unwrap(), reply);
+    }
+}
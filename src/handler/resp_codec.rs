@@ -0,0 +1,357 @@
+use bytes::{Buf, BufMut, BytesMut};
+use std::io::{Error, ErrorKind};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::commands::tokenize;
+
+const CRLF: &[u8] = b"\r\n";
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum RespValue {
+    SimpleString(String),
+    Error(String),
+    Integer(i64),
+    BulkString(String),
+    Null,
+    Array(Vec<RespValue>),
+    Map(Vec<(RespValue, RespValue)>),
+}
+
+// Decodes RESP arrays of bulk strings (and inline commands, as sent by
+// `redis-cli` or `telnet`) into their elements and encodes `RespValue`
+// replies for the protocol version negotiated with `HELLO`.
+pub struct RespCodec {
+    max_frame_size: usize,
+    protocol: u8,
+}
+
+impl RespCodec {
+    pub fn new(max_frame_size: usize) -> Self {
+        Self {
+            max_frame_size,
+            protocol: 2,
+        }
+    }
+
+    pub fn set_protocol(&mut self, protocol: u8) {
+        self.protocol = protocol;
+    }
+
+    fn frame_too_large(&self) -> Error {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Frame too large! Maximum size is {} bytes",
+                self.max_frame_size
+            ),
+        )
+    }
+
+    fn encode_value(&self, value: &RespValue, dst: &mut BytesMut) {
+        match value {
+            RespValue::SimpleString(s) => {
+                dst.put_u8(b'+');
+                dst.put_slice(s.as_bytes());
+                dst.put_slice(CRLF);
+            }
+            RespValue::Error(e) => {
+                dst.put_u8(b'-');
+                dst.put_slice(e.replace(['\r', '\n'], " ").as_bytes());
+                dst.put_slice(CRLF);
+            }
+            RespValue::Integer(i) => {
+                dst.put_slice(format!(":{}\r\n", i).as_bytes());
+            }
+            RespValue::BulkString(s) => {
+                dst.put_slice(format!("${}\r\n", s.len()).as_bytes());
+                dst.put_slice(s.as_bytes());
+                dst.put_slice(CRLF);
+            }
+            RespValue::Null => {
+                if self.protocol >= 3 {
+                    dst.put_slice(b"_\r\n");
+                } else {
+                    dst.put_slice(b"$-1\r\n");
+                }
+            }
+            RespValue::Array(values) => {
+                dst.put_slice(format!("*{}\r\n", values.len()).as_bytes());
+                for value in values {
+                    self.encode_value(value, dst);
+                }
+            }
+            RespValue::Map(entries) => {
+                if self.protocol >= 3 {
+                    dst.put_slice(format!("%{}\r\n", entries.len()).as_bytes());
+                } else {
+                    dst.put_slice(format!("*{}\r\n", entries.len() * 2).as_bytes());
+                }
+                for (key, value) in entries {
+                    self.encode_value(key, dst);
+                    self.encode_value(value, dst);
+                }
+            }
+        }
+    }
+}
+
+fn read_line(src: &[u8], start: usize) -> Option<(&[u8], usize)> {
+    let end = src[start..].windows(2).position(|w| w == CRLF)? + start;
+    Some((&src[start..end], end + 2))
+}
+
+fn parse_length(line: &[u8]) -> Result<i64, Error> {
+    String::from_utf8_lossy(line)
+        .parse::<i64>()
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid RESP length"))
+}
+
+// Returns the parsed command and the number of bytes it occupied, or `None`
+// if the frame is not complete yet.
+fn parse_array(src: &[u8], max_length: usize) -> Result<Option<(Vec<String>, usize)>, Error> {
+    let (header, mut position) = match read_line(src, 1) {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+
+    let count = parse_length(header)?;
+    let mut parts = Vec::new();
+
+    for _ in 0..count.max(0) {
+        let (line, next) = match read_line(src, position) {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+
+        if line.first() != Some(&b'$') {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Expected RESP bulk string",
+            ));
+        }
+
+        let length = parse_length(&line[1..])?;
+        if length < 0 || length as u64 > max_length as u64 {
+            return Err(Error::new(ErrorKind::InvalidData, "Invalid RESP length"));
+        }
+
+        let end = next + length as usize;
+        if src.len() < end + CRLF.len() {
+            return Ok(None);
+        }
+        if &src[end..end + CRLF.len()] != CRLF {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Expected RESP bulk string terminator",
+            ));
+        }
+
+        parts.push(String::from_utf8_lossy(&src[next..end]).to_string());
+        position = end + CRLF.len();
+    }
+
+    Ok(Some((parts, position)))
+}
+
+fn parse_inline(src: &[u8]) -> Result<Option<(Vec<String>, usize)>, Error> {
+    let end = match src.iter().position(|b| *b == b'\n') {
+        Some(end) => end,
+        None => return Ok(None),
+    };
+
+    let line = String::from_utf8_lossy(&src[..end]);
+    let parts = tokenize(line.trim_end_matches('\r'))
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+
+    Ok(Some((parts, end + 1)))
+}
+
+impl Decoder for RespCodec {
+    type Item = Vec<String>;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Vec<String>>, Error> {
+        loop {
+            if src.is_empty() {
+                return Ok(None);
+            }
+
+            let parsed = if src[0] == b'*' {
+                parse_array(src, self.max_frame_size)?
+            } else {
+                parse_inline(src)?
+            };
+
+            let (parts, length) = match parsed {
+                Some(parsed) => parsed,
+                None => {
+                    if src.len() > self.max_frame_size {
+                        return Err(self.frame_too_large());
+                    }
+                    return Ok(None);
+                }
+            };
+
+            if length > self.max_frame_size {
+                return Err(self.frame_too_large());
+            }
+
+            src.advance(length);
+
+            if !parts.is_empty() {
+                return Ok(Some(parts));
+            }
+        }
+    }
+}
+
+impl Encoder<RespValue> for RespCodec {
+    type Error = Error;
+
+    fn encode(&mut self, value: RespValue, dst: &mut BytesMut) -> Result<(), Error> {
+        self.encode_value(&value, dst);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod resp_codec_tests {
+    use super::*;
+
+    fn encode(codec: &mut RespCodec, value: RespValue) -> String {
+        let mut buf = BytesMut::new();
+        codec.encode(value, &mut buf).unwrap();
+        String::from_utf8_lossy(&buf).to_string()
+    }
+
+    #[test]
+    fn test_decode_array() {
+        let mut codec = RespCodec::new(1024);
+        let mut buf = BytesMut::from("*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$11\r\nhello world\r\n");
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(vec![
+                "SET".to_string(),
+                "key".to_string(),
+                "hello world".to_string()
+            ])
+        );
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_decode_partial_array() {
+        let mut codec = RespCodec::new(1024);
+        let mut buf = BytesMut::from("*2\r\n$3\r\nGET\r\n$3\r\nke");
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+
+        buf.extend_from_slice(b"y\r\n*1\r\n$4\r\nPING\r\n");
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(vec!["GET".to_string(), "key".to_string()])
+        );
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(vec!["PING".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_decode_binary_safe_bulk() {
+        let mut codec = RespCodec::new(1024);
+        let mut buf = BytesMut::from("*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$4\r\na\r\nb\r\n");
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(vec![
+                "SET".to_string(),
+                "k".to_string(),
+                "a\r\nb".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_decode_inline() {
+        let mut codec = RespCodec::new(1024);
+        let mut buf = BytesMut::from("SET key \"a b\"\r\n\r\nGET key\n");
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(vec![
+                "SET".to_string(),
+                "key".to_string(),
+                "a b".to_string()
+            ])
+        );
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(vec!["GET".to_string(), "key".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let mut codec = RespCodec::new(1024);
+        let mut buf = BytesMut::from("*1\r\n:1\r\n");
+
+        assert!(codec.decode(&mut buf).is_err());
+
+        let mut codec = RespCodec::new(8);
+        let mut buf = BytesMut::from("*1\r\n$20\r\n");
+
+        assert!(codec.decode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_encode_values() {
+        let mut codec = RespCodec::new(1024);
+
+        assert_eq!(
+            encode(&mut codec, RespValue::SimpleString("OK".to_string())),
+            "+OK\r\n"
+        );
+        assert_eq!(
+            encode(
+                &mut codec,
+                RespValue::Error("NOT_FOUND Key\nnot found".to_string())
+            ),
+            "-NOT_FOUND Key not found\r\n"
+        );
+        assert_eq!(encode(&mut codec, RespValue::Integer(3)), ":3\r\n");
+        assert_eq!(
+            encode(&mut codec, RespValue::BulkString("a\r\nb".to_string())),
+            "$4\r\na\r\nb\r\n"
+        );
+        assert_eq!(
+            encode(
+                &mut codec,
+                RespValue::Array(vec![RespValue::BulkString("a".to_string())])
+            ),
+            "*1\r\n$1\r\na\r\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_protocol_versions() {
+        let mut codec = RespCodec::new(1024);
+        let map = RespValue::Map(vec![(
+            RespValue::BulkString("proto".to_string()),
+            RespValue::Integer(2),
+        )]);
+
+        assert_eq!(encode(&mut codec, RespValue::Null), "$-1\r\n");
+        assert_eq!(
+            encode(&mut codec, map.clone()),
+            "*2\r\n$5\r\nproto\r\n:2\r\n"
+        );
+
+        codec.set_protocol(3);
+
+        assert_eq!(encode(&mut codec, RespValue::Null), "_\r\n");
+        assert_eq!(encode(&mut codec, map), "%1\r\n$5\r\nproto\r\n:2\r\n");
+    }
+}
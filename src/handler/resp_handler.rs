@@ -0,0 +1,353 @@
+use crate::auth::AuthStore;
+use crate::commands::{Command, CommandNames};
+use crate::config::Config;
+use crate::data::{DataManager, Payload, Store};
+use crate::error::KvError;
+use crate::session::Session;
+use crate::shutdown::ShutdownHandle;
+use futures::{SinkExt, StreamExt};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::Mutex;
+use tokio_util::codec::{Decoder, Framed};
+
+use super::{
+    executor::execute_command,
+    resp_codec::{RespCodec, RespValue},
+};
+
+pub struct RespHandler<S> {
+    socket: S,
+    data: Arc<Mutex<Store>>,
+    auth: Arc<Mutex<AuthStore>>,
+    config: Arc<Mutex<Config>>,
+    shutdown: ShutdownHandle,
+}
+
+fn error_reply(error: &KvError) -> RespValue {
+    RespValue::Error(format!("{} {}", error.code(), error))
+}
+
+fn command_reply(name: &CommandNames, result: Result<Payload, KvError>) -> RespValue {
+    match (name, result) {
+        (
+            CommandNames::GET
+            | CommandNames::LPOP
+            | CommandNames::RPOP
+            | CommandNames::LINDEX
+            | CommandNames::BLPOP
+            | CommandNames::ZSCORE
+            | CommandNames::ZRANK
+            | CommandNames::JSON_GET,
+            Err(KvError::NotFound(_)),
+        ) => RespValue::Null,
+        (_, Err(e)) => error_reply(&e),
+        (_, Ok(Payload::List(items))) => array_reply(items),
+        (
+            CommandNames::GET
+            | CommandNames::GET_USER
+            | CommandNames::INCRBYFLOAT
+            | CommandNames::LPOP
+            | CommandNames::RPOP
+            | CommandNames::LINDEX
+            | CommandNames::ZINCRBY
+            | CommandNames::ZSCORE
+            | CommandNames::JSON_GET,
+            Ok(payload),
+        ) => RespValue::BulkString(payload.to_string()),
+        (
+            CommandNames::TTL
+            | CommandNames::CAS
+            | CommandNames::INCR
+            | CommandNames::DECR
+            | CommandNames::INCRBY
+            | CommandNames::LPUSH
+            | CommandNames::RPUSH
+            | CommandNames::LLEN
+            | CommandNames::LREM
+            | CommandNames::SADD
+            | CommandNames::SREM
+            | CommandNames::SCARD
+            | CommandNames::ZADD
+            | CommandNames::ZRANK
+            | CommandNames::ZREM
+            | CommandNames::JSON_DEL
+            | CommandNames::JSON_ARRAPPEND,
+            Ok(payload),
+        ) => {
+            let payload = payload.to_string();
+            match payload.parse() {
+                Ok(ttl) => RespValue::Integer(ttl),
+                Err(_) => RespValue::SimpleString(payload),
+            }
+        }
+        (_, Ok(payload)) => RespValue::SimpleString(payload.to_string()),
+    }
+}
+
+// Every element is a bulk string of its own, so elements can hold any byte,
+// line breaks included.
+fn array_reply(items: Vec<Payload>) -> RespValue {
+    RespValue::Array(
+        items
+            .into_iter()
+            .map(|item| match item {
+                Payload::Value(value) => RespValue::BulkString(value),
+                Payload::List(items) => array_reply(items),
+            })
+            .collect(),
+    )
+}
+
+impl<S> RespHandler<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    pub fn new(
+        socket: S,
+        data: Arc<Mutex<Store>>,
+        auth: Arc<Mutex<AuthStore>>,
+        config: Arc<Mutex<Config>>,
+        shutdown: ShutdownHandle,
+    ) -> Self {
+        Self {
+            socket,
+            data,
+            auth,
+            config,
+            shutdown,
+        }
+    }
+
+    async fn hello(
+        framed: &mut Framed<S, RespCodec>,
+        data_manager: &mut DataManager,
+        session: &mut Session,
+        args: Vec<String>,
+    ) -> RespValue {
+        let mut args = args.into_iter();
+
+        let protocol = match args.next() {
+            Some(version) => match version.parse::<u8>() {
+                Ok(version @ 2..=3) => version,
+                _ => return RespValue::Error("NOPROTO unsupported protocol version".to_string()),
+            },
+            None => 2,
+        };
+
+        while let Some(option) = args.next() {
+            if option.to_uppercase() != "AUTH" {
+                continue;
+            }
+
+            let credentials = args.by_ref().take(2).collect::<Vec<String>>();
+            let result = match Command::from_parts("AUTH", credentials) {
+                Ok(command) => execute_command(data_manager, session, command).await,
+                Err(e) => Err(e.into()),
+            };
+
+            if let Err(e) = result {
+                return error_reply(&e);
+            }
+        }
+
+        framed.codec_mut().set_protocol(protocol);
+
+        RespValue::Map(vec![
+            (
+                RespValue::BulkString("server".to_string()),
+                RespValue::BulkString("kvstore".to_string()),
+            ),
+            (
+                RespValue::BulkString("version".to_string()),
+                RespValue::BulkString(env!("CARGO_PKG_VERSION").to_string()),
+            ),
+            (
+                RespValue::BulkString("proto".to_string()),
+                RespValue::Integer(protocol as i64),
+            ),
+        ])
+    }
+
+    // Runs the command of one frame. QUIT has no reply of its own here, the
+    // caller acknowledges it and closes the connection.
+    async fn handle_frame(
+        framed: &mut Framed<S, RespCodec>,
+        data_manager: &mut DataManager,
+        session: &mut Session,
+        mut parts: Vec<String>,
+    ) -> Option<RespValue> {
+        let name = parts.remove(0).to_uppercase();
+
+        let reply = match name.as_str() {
+            "PING" => match parts.pop() {
+                Some(message) => RespValue::BulkString(message),
+                None => RespValue::SimpleString("PONG".to_string()),
+            },
+            "HELLO" => Self::hello(framed, data_manager, session, parts).await,
+            "COMMAND" => RespValue::Array(Vec::new()),
+            "QUIT" => return None,
+            _ => match Command::from_parts(&name, parts) {
+                Ok(command) => {
+                    let command_name = command.name.clone();
+                    let result = execute_command(data_manager, session, command).await;
+                    match result {
+                        Ok(payload) if session.in_transaction() => {
+                            RespValue::SimpleString(payload.to_string())
+                        }
+                        result => command_reply(&command_name, result),
+                    }
+                }
+                Err(e) => {
+                    session.fail_transaction();
+                    error_reply(&e.into())
+                }
+            },
+        };
+
+        Some(reply)
+    }
+
+    async fn handle_client(self, data: Arc<Mutex<Store>>, config: Arc<Mutex<Config>>) {
+        let max_frame_size = config.lock().await.get_max_frame_size();
+        let mut framed = Framed::new(self.socket, RespCodec::new(max_frame_size));
+        let mut session = Session::new();
+        let mut data_manager = DataManager::new(data, self.auth, config)
+            .await
+            .unwrap()
+            .with_shutdown(self.shutdown.clone());
+
+        loop {
+            let frame = tokio::select! {
+                frame = framed.next() => frame,
+                _ = self.shutdown.cancelled() => break,
+            };
+
+            let parts = match frame {
+                Some(Ok(parts)) => parts,
+                Some(Err(e)) => {
+                    eprintln!("failed to read from socket; err = {:?}", e);
+                    let _ = framed.send(error_reply(&e.into())).await;
+                    return;
+                }
+                None => return,
+            };
+
+            let reply =
+                match Self::handle_frame(&mut framed, &mut data_manager, &mut session, parts).await
+                {
+                    Some(reply) => reply,
+                    None => {
+                        let _ = framed.send(RespValue::SimpleString("OK".to_string())).await;
+                        return;
+                    }
+                };
+
+            if let Err(e) = framed.send(reply).await {
+                eprintln!("failed to write to socket; err = {:?}", e);
+                return;
+            }
+        }
+
+        // The server is shutting down: answer the commands of the batch that
+        // was already received before closing the connection.
+        let mut buffer = std::mem::take(framed.read_buffer_mut());
+        while let Ok(Some(parts)) = framed.codec_mut().decode(&mut buffer) {
+            let reply =
+                match Self::handle_frame(&mut framed, &mut data_manager, &mut session, parts).await
+                {
+                    Some(reply) => reply,
+                    None => {
+                        let _ = framed.send(RespValue::SimpleString("OK".to_string())).await;
+                        return;
+                    }
+                };
+
+            if framed.send(reply).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    pub async fn spawn_handler(self) {
+        let data = Arc::clone(&self.data);
+        let config = Arc::clone(&self.config);
+        let shutdown = self.shutdown.clone();
+        shutdown.spawn(async move {
+            self.handle_client(data, config).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod resp_handler_tests {
+    use super::*;
+
+    #[test]
+    fn test_command_reply() {
+        assert_eq!(
+            command_reply(&CommandNames::SET, Ok("OK".into())),
+            RespValue::SimpleString("OK".to_string())
+        );
+        assert_eq!(
+            command_reply(&CommandNames::GET, Ok("OK".into())),
+            RespValue::BulkString("OK".to_string())
+        );
+        assert_eq!(
+            command_reply(&CommandNames::GET, Err(KvError::key_not_found())),
+            RespValue::Null
+        );
+        assert_eq!(
+            command_reply(&CommandNames::DEL, Err(KvError::key_not_found())),
+            RespValue::Error("NOT_FOUND Key not found".to_string())
+        );
+        assert_eq!(
+            command_reply(
+                &CommandNames::LIST_KEYS,
+                Ok(Payload::list(["a".to_string(), "b".to_string()]))
+            ),
+            RespValue::Array(vec![
+                RespValue::BulkString("a".to_string()),
+                RespValue::BulkString("b".to_string())
+            ])
+        );
+        assert_eq!(
+            command_reply(&CommandNames::LIST_KEYS, Ok(Payload::list([]))),
+            RespValue::Array(vec![])
+        );
+        assert_eq!(
+            command_reply(&CommandNames::TTL, Ok("-1".into())),
+            RespValue::Integer(-1)
+        );
+    }
+
+    #[test]
+    fn test_command_reply_keeps_elements_apart() {
+        let items = vec![
+            "multi\nline".to_string(),
+            "ends with\r".to_string(),
+            "".to_string(),
+        ];
+
+        assert_eq!(
+            command_reply(&CommandNames::LRANGE, Ok(Payload::list(items.clone()))),
+            RespValue::Array(items.into_iter().map(RespValue::BulkString).collect())
+        );
+        assert_eq!(
+            command_reply(
+                &CommandNames::EXEC,
+                Ok(Payload::List(vec![
+                    "OK".into(),
+                    Payload::list(["a".to_string(), "b".to_string()])
+                ]))
+            ),
+            RespValue::Array(vec![
+                RespValue::BulkString("OK".to_string()),
+                RespValue::Array(vec![
+                    RespValue::BulkString("a".to_string()),
+                    RespValue::BulkString("b".to_string())
+                ])
+            ])
+        );
+    }
+}
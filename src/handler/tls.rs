@@ -0,0 +1,197 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufReader, Error, ErrorKind};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::{
+    self,
+    pki_types::{CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore,
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+
+use crate::config::TlsConfig;
+
+// First byte of a TLS record carrying a handshake message (the ClientHello).
+const TLS_HANDSHAKE: u8 = 0x16;
+
+// Time a client has to send its first byte and complete the handshake, so
+// that connections which stay silent do not hold a task forever.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub enum MaybeTlsStream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+// Accepts TLS connections with the configured certificate. Unless `tls_only`
+// is set, clients that start talking plaintext are still served on the same
+// port.
+pub struct TlsListener {
+    acceptor: TlsAcceptor,
+    tls_only: bool,
+}
+
+fn invalid_input<E: Display>(error: E) -> Error {
+    Error::new(ErrorKind::InvalidInput, error.to_string())
+}
+
+fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, Error>>()?;
+
+    if certs.is_empty() {
+        return Err(invalid_input(format!("No certificates found in {}", path)));
+    }
+
+    Ok(certs)
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKeyDer<'static>, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    rustls_pemfile::private_key(&mut reader)?
+        .ok_or_else(|| invalid_input(format!("No private key found in {}", path)))
+}
+
+impl TlsListener {
+    pub fn new(config: &TlsConfig) -> Result<Self, Error> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = rustls::ServerConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()
+            .map_err(invalid_input)?;
+
+        let builder = match &config.client_ca_path {
+            Some(path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(path)? {
+                    roots.add(cert).map_err(invalid_input)?;
+                }
+
+                let verifier =
+                    WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                        .build()
+                        .map_err(invalid_input)?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let server_config = builder
+            .with_single_cert(
+                load_certs(&config.cert_path)?,
+                load_private_key(&config.key_path)?,
+            )
+            .map_err(invalid_input)?;
+
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(server_config)),
+            tls_only: config.tls_only,
+        })
+    }
+
+    pub async fn accept(&self, socket: TcpStream) -> Result<MaybeTlsStream, Error> {
+        tokio::time::timeout(HANDSHAKE_TIMEOUT, self.handshake(socket))
+            .await
+            .map_err(|_| Error::new(ErrorKind::TimedOut, "TLS handshake timed out"))?
+    }
+
+    async fn handshake(&self, socket: TcpStream) -> Result<MaybeTlsStream, Error> {
+        if !self.tls_only {
+            let mut first_byte = [0u8; 1];
+            let read = socket.peek(&mut first_byte).await?;
+            if read == 0 || first_byte[0] != TLS_HANDSHAKE {
+                return Ok(MaybeTlsStream::Plain(socket));
+            }
+        }
+
+        let stream = self.acceptor.accept(socket).await?;
+        Ok(MaybeTlsStream::Tls(Box::new(stream)))
+    }
+}
+
+pub async fn accept_connection(
+    socket: TcpStream,
+    tls: Option<&TlsListener>,
+) -> Result<MaybeTlsStream, Error> {
+    match tls {
+        Some(tls) => tls.accept(socket).await,
+        None => Ok(MaybeTlsStream::Plain(socket)),
+    }
+}
+
+impl AsyncRead for MaybeTlsStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), Error>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            MaybeTlsStream::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for MaybeTlsStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, Error>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            MaybeTlsStream::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            MaybeTlsStream::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            MaybeTlsStream::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tls_tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_missing_certificate() {
+        let config = TlsConfig {
+            cert_path: "missing.pem".to_string(),
+            key_path: "missing.key".to_string(),
+            client_ca_path: None,
+            tls_only: false,
+        };
+
+        assert!(TlsListener::new(&config).is_err());
+    }
+
+    #[test]
+    fn test_empty_certificate() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "not a certificate").unwrap();
+        let path = file.path().to_string_lossy().to_string();
+
+        let error = load_certs(&path).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(load_private_key(&path).is_err());
+    }
+}
@@ -1,44 +1,374 @@
 pub mod auth;
+pub mod client;
 pub mod commands;
 pub mod config;
 pub mod data;
+pub mod error;
 pub mod handler;
 pub mod persistence;
 pub mod session;
+pub mod shutdown;
 
+use auth::AuthStore;
 use config::Config;
 use data::Store;
-use handler::ClientHandler;
+use handler::{accept_connection, serve_http, ClientHandler, RespHandler, TlsListener};
+use persistence::Persistence;
 use persistence::PersistenceType;
+use shutdown::ShutdownHandle;
 use std::sync::Arc;
+use std::time::Duration;
 
-use tokio::net::TcpListener;
+#[cfg(unix)]
+use config::UnixSocketConfig;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::Mutex;
 
+// How often the background reaper sweeps the store for expired keys.
+const REAPER_INTERVAL: Duration = Duration::from_secs(1);
+
+// Runs the server until SIGINT or SIGTERM is received.
 pub async fn start_server(config: Config) -> Result<(), Box<dyn std::error::Error>> {
-    let store = if config.persistence.get_type() == PersistenceType::JsonFile {
-        config.persistence.load_store()?
+    let shutdown = ShutdownHandle::new();
+    shutdown.listen_for_signals();
+
+    start_server_with_shutdown(config, shutdown).await
+}
+
+// Runs the server until `shutdown` is triggered. Listeners stop accepting new
+// connections, running handlers get `shutdown_timeout` to answer the commands
+// they already received and the store is saved one last time before
+// returning.
+pub async fn start_server_with_shutdown(
+    config: Config,
+    shutdown: ShutdownHandle,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (store, auth_store) = if config.persistence.get_type() == PersistenceType::JsonFile {
+        let mut store = config.persistence.load_store()?;
+        let mut auth_store = config.persistence.load_auth()?;
+        auth_store.migrate_legacy_users(&mut store)?;
+        (store, auth_store)
     } else {
-        Store::new(".".to_string())
+        (Store::new(".".to_string()), AuthStore::new())
     };
 
     let data = Arc::new(Mutex::new(store));
+    let auth = Arc::new(Mutex::new(auth_store));
+
+    let tls = match config.get_tls_config() {
+        Some(tls_config) => {
+            if tls_config.tls_only && config.get_http_address().is_some() {
+                return Err("The HTTP gateway can not be enabled in TLS-only mode".into());
+            }
+            Some(Arc::new(TlsListener::new(tls_config)?))
+        }
+        None => None,
+    };
 
     let listener = TcpListener::bind(config.get_server_address()).await?;
     println!("Key-Value Server is listening");
 
+    let resp_listener = match config.get_resp_address() {
+        Some(address) => {
+            let listener = TcpListener::bind(address).await?;
+            println!("RESP listener is listening");
+            Some(listener)
+        }
+        None => None,
+    };
+
+    let http_listener = match config.get_http_address() {
+        Some(address) => {
+            let listener = TcpListener::bind(address).await?;
+            println!("HTTP gateway is listening");
+            Some(listener)
+        }
+        None => None,
+    };
+
+    #[cfg(unix)]
+    let unix_listener = match config.get_unix_socket_config() {
+        Some(socket_config) => {
+            let listener = bind_unix_socket(socket_config)?;
+            println!("Unix socket listener is listening");
+            Some(listener)
+        }
+        None => None,
+    };
+
+    shutdown.spawn(reap_expired_keys(
+        Arc::clone(&data),
+        config.persistence.clone(),
+        shutdown.clone(),
+    ));
+
     let config = Arc::new(Mutex::new(config));
 
+    if let Some(resp_listener) = resp_listener {
+        tokio::spawn(accept_resp_clients(
+            resp_listener,
+            tls.clone(),
+            Arc::clone(&data),
+            Arc::clone(&auth),
+            Arc::clone(&config),
+            shutdown.clone(),
+        ));
+    }
+
+    if let Some(http_listener) = http_listener {
+        let data = Arc::clone(&data);
+        let auth = Arc::clone(&auth);
+        let config = Arc::clone(&config);
+        let http_shutdown = shutdown.clone();
+        shutdown.spawn(async move {
+            if let Err(e) = serve_http(http_listener, data, auth, config, http_shutdown).await {
+                eprintln!("HTTP gateway stopped; err = {:?}", e);
+            }
+        });
+    }
+
+    #[cfg(unix)]
+    if let Some(unix_listener) = unix_listener {
+        tokio::spawn(accept_unix_clients(
+            unix_listener,
+            Arc::clone(&data),
+            Arc::clone(&auth),
+            Arc::clone(&config),
+            shutdown.clone(),
+        ));
+    }
+
     loop {
-        let (socket, _) = listener.accept().await?;
+        let socket = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((socket, _)) => socket,
+                Err(e) => {
+                    shutdown.shutdown();
+                    return Err(e.into());
+                }
+            },
+            _ = shutdown.cancelled() => break,
+        };
 
-        println!("Accepted connection from: {}", socket.peer_addr()?);
+        match socket.peer_addr() {
+            Ok(address) => println!("Accepted connection from: {}", address),
+            Err(e) => eprintln!("failed to get peer address; err = {:?}", e),
+        }
 
         let shared_data = Arc::clone(&data);
 
+        let shared_auth = Arc::clone(&auth);
+
         let shared_config = Arc::clone(&config);
 
-        let client_handler = ClientHandler::new(socket, shared_data, shared_config);
+        spawn_client(
+            socket,
+            tls.clone(),
+            shared_data,
+            shared_auth,
+            shared_config,
+            shutdown.clone(),
+        );
+    }
+
+    drop(listener);
+    println!("Shutting down");
+
+    let shutdown_timeout = config.lock().await.get_shutdown_timeout();
+    if !shutdown.wait(shutdown_timeout).await {
+        eprintln!("Timed out waiting for connections to finish");
+    }
+
+    let config = config.lock().await;
+
+    if config.persistence.get_type() == PersistenceType::JsonFile {
+        config.persistence.save_store(&*data.lock().await)?;
+        config.persistence.save_auth(&*auth.lock().await)?;
+    }
+
+    #[cfg(unix)]
+    if let Some(socket_config) = config.get_unix_socket_config() {
+        let _ = std::fs::remove_file(&socket_config.path);
+    }
+
+    Ok(())
+}
+
+// Expired keys are dropped lazily when they are looked up; this task also
+// removes the ones nobody asks for again.
+async fn reap_expired_keys(
+    data: Arc<Mutex<Store>>,
+    persistence: Persistence,
+    shutdown: ShutdownHandle,
+) {
+    let mut interval = tokio::time::interval(REAPER_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = shutdown.cancelled() => return,
+        }
+
+        let mut data = data.lock().await;
+        let removed = data.remove_expired(data::now_millis());
+
+        if removed > 0 && persistence.get_type() == PersistenceType::JsonFile {
+            if let Err(e) = persistence.save_store(&data) {
+                eprintln!("failed to save store after expiring keys; err = {:?}", e);
+            }
+        }
+    }
+}
+
+fn spawn_client(
+    socket: TcpStream,
+    tls: Option<Arc<TlsListener>>,
+    data: Arc<Mutex<Store>>,
+    auth: Arc<Mutex<AuthStore>>,
+    config: Arc<Mutex<Config>>,
+    shutdown: ShutdownHandle,
+) {
+    let handshake_shutdown = shutdown.clone();
+    handshake_shutdown.spawn(async move {
+        let stream = tokio::select! {
+            stream = accept_connection(socket, tls.as_deref()) => stream,
+            _ = shutdown.cancelled() => return,
+        };
+
+        match stream {
+            Ok(stream) => {
+                let client_handler = ClientHandler::new(stream, data, auth, config, shutdown);
+
+                client_handler.spawn_handler().await;
+            }
+            Err(e) => eprintln!("failed to accept TLS connection; err = {:?}", e),
+        }
+    });
+}
+
+async fn accept_resp_clients(
+    listener: TcpListener,
+    tls: Option<Arc<TlsListener>>,
+    data: Arc<Mutex<Store>>,
+    auth: Arc<Mutex<AuthStore>>,
+    config: Arc<Mutex<Config>>,
+    shutdown: ShutdownHandle,
+) {
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = shutdown.cancelled() => return,
+        };
+
+        let socket = match accepted {
+            Ok((socket, _)) => socket,
+            Err(e) => {
+                eprintln!("failed to accept RESP connection; err = {:?}", e);
+                continue;
+            }
+        };
+
+        let tls = tls.clone();
+        let data = Arc::clone(&data);
+        let auth = Arc::clone(&auth);
+        let config = Arc::clone(&config);
+        let handler_shutdown = shutdown.clone();
+
+        shutdown.spawn(async move {
+            let stream = tokio::select! {
+                stream = accept_connection(socket, tls.as_deref()) => stream,
+                _ = handler_shutdown.cancelled() => return,
+            };
+
+            match stream {
+                Ok(stream) => {
+                    RespHandler::new(stream, data, auth, config, handler_shutdown)
+                        .spawn_handler()
+                        .await
+                }
+                Err(e) => eprintln!("failed to accept TLS connection; err = {:?}", e),
+            }
+        });
+    }
+}
+
+#[cfg(unix)]
+fn bind_unix_socket(socket_config: &UnixSocketConfig) -> Result<UnixListener, std::io::Error> {
+    use std::fs;
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use std::path::Path;
+
+    // A socket file left behind by a previous run would make `bind` fail.
+    if let Ok(metadata) = fs::symlink_metadata(&socket_config.path) {
+        if metadata.file_type().is_socket() {
+            fs::remove_file(&socket_config.path)?;
+        }
+    }
+
+    // `bind` creates the socket with the permissions of the umask, so it is
+    // bound inside a directory only the server can enter and moved into
+    // place once it has the configured permissions.
+    let path = Path::new(&socket_config.path);
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid Unix socket path: {}", socket_config.path),
+        )
+    })?;
+    let staging_dir = path.with_file_name(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    fs::DirBuilder::new().mode(0o700).create(&staging_dir)?;
+
+    let staging_path = staging_dir.join(file_name);
+    let result = UnixListener::bind(&staging_path).and_then(|listener| {
+        fs::set_permissions(
+            &staging_path,
+            fs::Permissions::from_mode(socket_config.permissions),
+        )?;
+        fs::rename(&staging_path, path)?;
+        Ok(listener)
+    });
+
+    let _ = fs::remove_file(&staging_path);
+    fs::remove_dir(&staging_dir)?;
+
+    result
+}
+
+#[cfg(unix)]
+async fn accept_unix_clients(
+    listener: UnixListener,
+    data: Arc<Mutex<Store>>,
+    auth: Arc<Mutex<AuthStore>>,
+    config: Arc<Mutex<Config>>,
+    shutdown: ShutdownHandle,
+) {
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = shutdown.cancelled() => return,
+        };
+
+        let socket = match accepted {
+            Ok((socket, _)) => socket,
+            Err(e) => {
+                eprintln!("failed to accept Unix socket connection; err = {:?}", e);
+                continue;
+            }
+        };
+
+        let client_handler = ClientHandler::new(
+            socket,
+            Arc::clone(&data),
+            Arc::clone(&auth),
+            Arc::clone(&config),
+            shutdown.clone(),
+        );
 
         client_handler.spawn_handler().await;
     }
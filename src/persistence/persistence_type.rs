@@ -1,8 +1,8 @@
-use std::str::FromStr;
+use std::{io::Write, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 
-use crate::data::Store;
+use crate::{auth::AuthStore, data::Store, error::KvError};
 
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum PersistenceType {
@@ -22,6 +22,31 @@ impl FromStr for PersistenceType {
     }
 }
 
+// Writes the file through a temporary one that is created readable by its
+// owner only and then renamed over it, so the contents are never readable by
+// others, not even before the permissions could be changed.
+fn write_private(path: &str, contents: &[u8]) -> std::io::Result<()> {
+    let temp_path = format!("{}.tmp", path);
+    // A leftover of an interrupted save may have other permissions.
+    match std::fs::remove_file(&temp_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(&temp_path, path)
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Persistence {
     persistence_type: PersistenceType,
@@ -51,34 +76,99 @@ impl Persistence {
         self.file_path.clone()
     }
 
-    pub fn save_store(&self, data: &Store) -> Result<(), String> {
+    // Users are saved next to the data, e.g. `data.json.auth` for
+    // `data.json`, so a copy of the data does not leak password hashes.
+    pub fn get_auth_path(&self) -> Option<String> {
+        self.file_path.as_ref().map(|path| format!("{}.auth", path))
+    }
+
+    pub fn save_auth(&self, auth: &AuthStore) -> Result<(), KvError> {
+        let path = match (&self.persistence_type, self.get_auth_path()) {
+            (PersistenceType::JsonFile, Some(path)) => path,
+            (PersistenceType::JsonFile, None) => {
+                return Err(KvError::Persistence("No file path provided".to_string()))
+            }
+            _ => return Err(KvError::Persistence("Invalid persistence type".to_string())),
+        };
+
+        let json = serde_json::to_string(auth).map_err(|e| KvError::Persistence(e.to_string()))?;
+        write_private(&path, json.as_bytes()).map_err(|e| KvError::Persistence(e.to_string()))
+    }
+
+    // A missing file means no users were saved yet.
+    pub fn load_auth(&self) -> Result<AuthStore, KvError> {
+        let path = match (&self.persistence_type, self.get_auth_path()) {
+            (PersistenceType::JsonFile, Some(path)) => path,
+            _ => return Ok(AuthStore::new()),
+        };
+
+        match std::fs::read(path) {
+            Ok(json) if json.is_empty() => Ok(AuthStore::new()),
+            Ok(json) => {
+                serde_json::from_slice(&json).map_err(|e| KvError::Persistence(e.to_string()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(AuthStore::new()),
+            Err(e) => Err(KvError::Persistence(e.to_string())),
+        }
+    }
+
+    pub fn save_store(&self, data: &Store) -> Result<(), KvError> {
         match self.persistence_type {
             PersistenceType::JsonFile => {
-                let json = serde_json::to_string(data).unwrap();
+                let json =
+                    serde_json::to_string(data).map_err(|e| KvError::Persistence(e.to_string()))?;
                 match self.file_path.clone() {
-                    Some(path) => std::fs::write(path, json).unwrap(),
-                    None => return Err("No file path provided".to_string()),
+                    Some(path) => std::fs::write(path, json)
+                        .map_err(|e| KvError::Persistence(e.to_string()))?,
+                    None => return Err(KvError::Persistence("No file path provided".to_string())),
                 }
             }
-            _ => return Err("Invalid persistence type".to_string()),
+            _ => return Err(KvError::Persistence("Invalid persistence type".to_string())),
         }
         Ok(())
     }
 
-    pub fn load_store(&self) -> Result<Store, String> {
+    pub fn load_store(&self) -> Result<Store, KvError> {
         match self.persistence_type {
             PersistenceType::JsonFile => match self.file_path.clone() {
                 Some(path) => {
-                    let json = std::fs::read(path).unwrap();
+                    let json =
+                        std::fs::read(path).map_err(|e| KvError::Persistence(e.to_string()))?;
                     if json.is_empty() {
                         return Ok(Store::new(".".to_string()));
                     }
-                    let store: Store = serde_json::from_slice(&json).unwrap();
+                    let store: Store = serde_json::from_slice(&json)
+                        .map_err(|e| KvError::Persistence(e.to_string()))?;
                     Ok(store)
                 }
-                None => Err("No file path provided".to_string()),
+                None => Err(KvError::Persistence("No file path provided".to_string())),
             },
             PersistenceType::InMemory => Ok(Store::new(".".to_string())),
         }
     }
 }
+
+#[cfg(test)]
+mod persistence_tests {
+    use super::*;
+
+    #[test]
+    fn test_save_auth_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.json").to_string_lossy().to_string();
+        let persistence = Persistence::new_json_file(path.clone());
+
+        std::fs::write(format!("{}.auth", path), "old").unwrap();
+        persistence.save_auth(&AuthStore::new()).unwrap();
+
+        assert!(persistence.load_auth().is_ok());
+        assert!(!std::path::Path::new(&format!("{}.auth.tmp", path)).exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = std::fs::metadata(format!("{}.auth", path)).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub mod session;
+mod transaction;
 pub use session::*;
+pub use transaction::*;
@@ -1,8 +1,14 @@
+use crate::commands::Command;
+
+use super::Transaction;
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Session {
     pub is_authenticated: bool,
     pub username: String,
-    pub incomplete_command: String,
+    // Set at login when the password has to be changed first.
+    pub must_change_password: bool,
+    pub transaction: Option<Transaction>,
 }
 
 impl Session {
@@ -10,26 +16,53 @@ impl Session {
         Self {
             is_authenticated: false,
             username: "".to_string(),
-            incomplete_command: "".to_string(),
+            must_change_password: false,
+            transaction: None,
         }
     }
 
     pub fn update(&mut self, new_session: Session) {
         self.is_authenticated = new_session.is_authenticated;
         self.username = new_session.username;
-        self.incomplete_command = new_session.incomplete_command;
+        self.must_change_password = new_session.must_change_password;
+        self.transaction = new_session.transaction;
     }
 
     pub fn set_authenticated(&mut self, username: &str) -> Session {
         self.is_authenticated = true;
         self.username = username.to_string();
+        self.must_change_password = false;
 
         self.clone()
     }
 
-    pub fn resume_incomplete_command(&mut self, command: &str) -> Session {
-        self.incomplete_command = command.to_string();
+    pub fn set_must_change_password(&mut self, must_change_password: bool) -> Session {
+        self.must_change_password = must_change_password;
 
         self.clone()
     }
+
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    pub fn begin_transaction(&mut self) {
+        self.transaction = Some(Transaction::default());
+    }
+
+    pub fn queue_command(&mut self, command: Command) {
+        if let Some(transaction) = &mut self.transaction {
+            transaction.commands.push(command);
+        }
+    }
+
+    pub fn fail_transaction(&mut self) {
+        if let Some(transaction) = &mut self.transaction {
+            transaction.failed = true;
+        }
+    }
+
+    pub fn end_transaction(&mut self) -> Option<Transaction> {
+        self.transaction.take()
+    }
 }
@@ -0,0 +1,9 @@
+use crate::commands::Command;
+
+// Commands queued between MULTI and EXEC. A command that is rejected while
+// queueing marks the whole transaction as failed so EXEC refuses to run it.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Transaction {
+    pub commands: Vec<Command>,
+    pub failed: bool,
+}
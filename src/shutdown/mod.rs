@@ -0,0 +1,3 @@
+mod shutdown_handle;
+
+pub use shutdown_handle::*;
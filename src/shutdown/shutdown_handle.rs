@@ -0,0 +1,109 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+
+// Shared by `start_server` and every task it spawns. Triggering it makes the
+// listeners stop accepting connections and asks running handlers to finish the
+// commands they already received.
+#[derive(Clone, Default)]
+pub struct ShutdownHandle {
+    token: CancellationToken,
+    tracker: TaskTracker,
+}
+
+impl ShutdownHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn shutdown(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+
+    // Spawns a task the server waits for before its final save.
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.tracker.spawn(task);
+    }
+
+    // Waits for the spawned tasks to finish. Returns `false` if some of them
+    // were still running after `timeout`.
+    pub async fn wait(&self, timeout: Duration) -> bool {
+        self.tracker.close();
+        tokio::time::timeout(timeout, self.tracker.wait())
+            .await
+            .is_ok()
+    }
+
+    // Triggers the shutdown on SIGINT or SIGTERM.
+    pub fn listen_for_signals(&self) {
+        let handle = self.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = wait_for_signal() => {
+                    println!("Received shutdown signal");
+                    handle.shutdown();
+                }
+                _ = handle.cancelled() => {}
+            }
+        });
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            eprintln!("failed to listen for SIGTERM; err = {:?}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        }
+    };
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod shutdown_handle_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_for_tasks() {
+        let shutdown = ShutdownHandle::new();
+
+        let handle = shutdown.clone();
+        shutdown.spawn(async move {
+            handle.cancelled().await;
+        });
+
+        assert!(!shutdown.wait(Duration::from_millis(50)).await);
+
+        shutdown.shutdown();
+
+        assert!(shutdown.is_shutdown());
+        assert!(shutdown.wait(Duration::from_millis(50)).await);
+    }
+}
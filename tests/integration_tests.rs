@@ -4,19 +4,42 @@ use std::io::{Read, Write};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
-    net::TcpStream,
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpStream, UnixStream},
 };
 
+use bytes::BytesMut;
 use lazy_static::lazy_static;
 use tempfile::NamedTempFile;
 
-use kvstore::{config::Config, persistence::Persistence, start_server};
+use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+use tempfile::TempDir;
+use tokio_rustls::{
+    client::TlsStream,
+    rustls::{
+        self,
+        pki_types::{PrivateKeyDer, ServerName},
+        RootCertStore,
+    },
+    TlsConnector,
+};
+
+use kvstore::{
+    auth::Permissions,
+    client::{Client, Pool},
+    config::{Config, TlsConfig},
+    data::DataTypes,
+    error::KvError,
+    handler::decode_reply,
+    persistence::Persistence,
+    shutdown::ShutdownHandle,
+    start_server_with_shutdown,
+};
 
 const ADDRESS: &str = "127.0.0.1";
 
 lazy_static! {
-    static ref PORT_TRACKER: Arc<Mutex<u16>> = Arc::new(Mutex::new(65500));
+    static ref PORT_TRACKER: Arc<Mutex<u16>> = Arc::new(Mutex::new(65400));
 }
 
 async fn get_next_port() -> u16 {
@@ -26,27 +49,106 @@ async fn get_next_port() -> u16 {
     current_port
 }
 
-async fn start_test_server(port: u16, file_path: Option<String>) -> tokio::task::JoinHandle<()> {
-    tokio::spawn(async move {
-        let mut config = Config::new();
+struct TestServer {
+    handle: tokio::task::JoinHandle<()>,
+    shutdown: ShutdownHandle,
+}
+
+impl TestServer {
+    async fn stop(self) {
+        self.shutdown.shutdown();
+        self.handle.await.unwrap();
+    }
+}
 
-        if let Some(path) = file_path {
-            config.add_persistence_config(Persistence::new_json_file(path));
-        }
+async fn start_test_server(port: u16, file_path: Option<String>) -> TestServer {
+    let mut config = Config::new();
+
+    if let Some(path) = file_path {
+        config.add_persistence_config(Persistence::new_json_file(path));
+    }
+
+    config.add_server_config(ADDRESS.to_string(), port);
+
+    start_test_server_with_config(config).await
+}
+
+async fn start_test_server_with_config(config: Config) -> TestServer {
+    let shutdown = ShutdownHandle::new();
+    let server_shutdown = shutdown.clone();
+
+    let handle = tokio::spawn(async move {
+        start_server_with_shutdown(config, server_shutdown)
+            .await
+            .unwrap();
+    });
+
+    TestServer { handle, shutdown }
+}
+
+// Starts a server listening on a Unix socket in a fresh temporary directory,
+// so tests don't have to reserve a TCP port. The TCP listener binds an
+// ephemeral port that is never used.
+async fn start_unix_test_server(file_path: Option<String>) -> (TestServer, TempDir, String) {
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir
+        .path()
+        .join("kvstore.sock")
+        .to_string_lossy()
+        .to_string();
+
+    let mut config = Config::new();
+
+    if let Some(path) = file_path {
+        config.add_persistence_config(Persistence::new_json_file(path));
+    }
+
+    config.add_server_config(ADDRESS.to_string(), 0);
+    config.add_unix_socket_config(socket_path.clone(), 0o600);
+
+    let server_handle = start_test_server_with_config(config).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    (server_handle, dir, socket_path)
+}
 
-        config.add_server_config(ADDRESS.to_string(), port);
+async fn read_replies<S: AsyncRead + Unpin>(client: &mut S, count: usize) -> Vec<String> {
+    let mut buf = BytesMut::new();
+    let mut replies = Vec::new();
+
+    while replies.len() < count {
+        match decode_reply(&mut buf).unwrap() {
+            Some(reply) => replies.push(reply),
+            None => {
+                if client.read_buf(&mut buf).await.unwrap() == 0 {
+                    break;
+                }
+            }
+        }
+    }
 
-        start_server(config).await.unwrap();
-    })
+    replies
 }
 
-async fn send_command(mut client: TcpStream, command: &str) -> (TcpStream, String) {
+// Sends the given commands and waits for one reply per `;` terminated command.
+// Replies are joined back into a `;` separated string.
+async fn send_command<S: AsyncRead + AsyncWrite + Unpin>(
+    mut client: S,
+    command: &str,
+) -> (S, String) {
     let _ = client.write_all(command.as_bytes()).await;
-    let mut buf = [0; 1024];
 
-    let n = client.read(&mut buf).await.unwrap();
+    let expected_replies = command.matches(';').count();
+
+    let replies = read_replies(&mut client, expected_replies).await;
 
-    (client, String::from_utf8_lossy(&buf[..n]).to_string())
+    let response = replies
+        .iter()
+        .map(|reply| format!("{};", reply))
+        .collect::<String>();
+
+    (client, response)
 }
 
 #[tokio::test]
@@ -62,21 +164,21 @@ async fn test_integration_simple_key_value_flow() {
         .unwrap();
 
     let (client, response) = send_command(client, "AUTH admin Password4;").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (client, response) = send_command(client, "SET key value;").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (client, response) = send_command(client, "GET key;").await;
-    assert_eq!(response, "value;");
+    assert_eq!(response, "OK value;");
 
     let (client, response) = send_command(client, "DEL key;").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (_, response) = send_command(client, "GET key;").await;
-    assert_eq!(response, "Key not found;");
+    assert_eq!(response, "ERR NOT_FOUND Key not found;");
 
-    server_handle.abort();
+    server_handle.stop().await;
 }
 
 #[tokio::test]
@@ -91,37 +193,37 @@ async fn test_integration_two_clients() {
         .unwrap();
 
     let (first_client, response) = send_command(first_client, "AUTH admin Password4;").await;
-    assert_eq!("OK;", response);
+    assert_eq!("OK OK;", response);
 
     let second_client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
         .await
         .unwrap();
 
     let (second_client, response) = send_command(second_client, "AUTH admin Password4;").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (first_client, response) = send_command(first_client, "SET key value;").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (second_client, response) = send_command(second_client, "GET key;").await;
-    assert_eq!(response, "value;");
+    assert_eq!(response, "OK value;");
 
     let (second_client, response) = send_command(second_client, "SET key value2;").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (first_client, response) = send_command(first_client, "GET key;").await;
-    assert_eq!(response, "value2;");
+    assert_eq!(response, "OK value2;");
 
     let (first_client, response) = send_command(first_client, "DEL key;").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (_, response) = send_command(first_client, "GET key;").await;
-    assert_eq!(response, "Key not found;");
+    assert_eq!(response, "ERR NOT_FOUND Key not found;");
 
     let (_, response) = send_command(second_client, "GET key;").await;
-    assert_eq!(response, "Key not found;");
+    assert_eq!(response, "ERR NOT_FOUND Key not found;");
 
-    server_handle.abort();
+    server_handle.stop().await;
 }
 
 #[tokio::test]
@@ -140,23 +242,23 @@ async fn test_integration_lot_clients() {
 
         let handle = tokio::spawn(async move {
             let (client, response) = send_command(client, "AUTH admin Password4;").await;
-            assert_eq!(response, "OK;");
+            assert_eq!(response, "OK OK;");
 
             let (client, response) = send_command(client, &format!("SET key{} value;", i)).await;
-            assert_eq!(response, "OK;", "SET command response mismatch");
+            assert_eq!(response, "OK OK;", "SET command response mismatch");
 
             let (client, response) = send_command(client, &format!("GET key{};", i)).await;
             assert_eq!(
-                response, "value;",
+                response, "OK value;",
                 "GET command (after SET) response mismatch"
             );
 
             let (client, response) = send_command(client, &format!("DEL key{};", i)).await;
-            assert_eq!(response, "OK;", "DEL command response mismatch");
+            assert_eq!(response, "OK OK;", "DEL command response mismatch");
 
             let (_, response) = send_command(client, &format!("GET key{};", i)).await;
             assert_eq!(
-                response, "Key not found;",
+                response, "ERR NOT_FOUND Key not found;",
                 "GET command (after DEL) response mismatch"
             );
         });
@@ -172,14 +274,14 @@ async fn test_integration_lot_clients() {
         );
     }
 
-    server_handle.abort();
+    server_handle.stop().await;
 }
 
 #[tokio::test]
 async fn test_integration_batch_commands() {
     let port = get_next_port().await;
 
-    let _ = start_test_server(port, None).await;
+    let server_handle = start_test_server(port, None).await;
 
     tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
 
@@ -188,21 +290,23 @@ async fn test_integration_batch_commands() {
         .unwrap();
 
     let (client, response) = send_command(client, "AUTH admin Password4;").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (client, response) =
         send_command(client, "SET key1 value1;SET key2 value2;SET key3 value3;").await;
-    assert_eq!(response, "OK;OK;OK;");
+    assert_eq!(response, "OK OK;OK OK;OK OK;");
 
     let (_, response) = send_command(client, "GET key1;GET key2;GET key3;").await;
-    assert_eq!(response, "value1;value2;value3;");
+    assert_eq!(response, "OK value1;OK value2;OK value3;");
+
+    server_handle.stop().await;
 }
 
 #[tokio::test]
 async fn test_integration_incomplete_command() {
     let port = get_next_port().await;
 
-    let _ = start_test_server(port, None).await;
+    let server_handle = start_test_server(port, None).await;
 
     tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
 
@@ -211,22 +315,24 @@ async fn test_integration_incomplete_command() {
         .unwrap();
 
     let (client, response) = send_command(client, "AUTH admin Password4;").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (client, response) = send_command(client, "SET key value").await;
-    assert_eq!(response, " ");
+    assert_eq!(response, "");
 
     let (client, response) = send_command(client, ";").await;
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (client, response) = send_command(client, "GET key;").await;
-    assert_eq!(response, "value;");
+    assert_eq!(response, "OK value;");
 
     let (client, response) = send_command(client, "SET ").await;
-    assert_eq!(response, " ");
+    assert_eq!(response, "");
 
     let (_, response) = send_command(client, "key1 value1; SET key2 value2;").await;
-    assert_eq!(response, "OK;OK;");
+    assert_eq!(response, "OK OK;OK OK;");
+
+    server_handle.stop().await;
 }
 
 #[tokio::test]
@@ -237,7 +343,7 @@ async fn test_integration_persistence_save_to_json() {
 
     let file_path = temp_file.path().to_str().expect("No path").to_string();
 
-    let _ = start_test_server(port, Some(file_path.clone())).await;
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
 
     tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
 
@@ -247,23 +353,23 @@ async fn test_integration_persistence_save_to_json() {
 
     let (client, response) = send_command(client, "AUTH admin Password4;").await;
 
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (client, response) = send_command(client, "SET test_key test_value;").await;
 
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (client, response) = send_command(client, "CREATE_STORE users;").await;
 
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (client, response) = send_command(client, "CREATE_STORE users:john_doe;").await;
 
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (_, response) = send_command(client, "SET users:john_doe:age 42 INT;").await;
 
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let mut buf = String::new();
 
@@ -272,7 +378,57 @@ async fn test_integration_persistence_save_to_json() {
         .expect("Failed to read from file");
 
     assert!(buf.contains("\"stores\":{\"john_doe\":{\"name\":\"john_doe\""));
-    assert!(buf.contains("\"data\":{\"age\":{\"value\":\"42\",\"data_type\":\"INT\"}"));
+    // Versions are counted across the whole store, so `age` is the second
+    // value written.
+    assert!(
+        buf.contains("\"data\":{\"age\":{\"value\":\"42\",\"data_type\":\"INT\",\"version\":2}")
+    );
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_persistence_skips_saving_after_reads() {
+    let port = get_next_port().await;
+
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+
+    let file_path = temp_file.path().to_str().expect("No path").to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (client, response) = send_command(client, "AUTH admin Password4;").await;
+
+    assert_eq!(response, "OK OK;");
+
+    let (client, response) = send_command(client, "SET test_key test_value;").await;
+
+    assert_eq!(response, "OK OK;");
+
+    std::fs::write(&file_path, "").expect("Failed to truncate file");
+
+    let (client, response) = send_command(client, "GET test_key;").await;
+
+    assert_eq!(response, "OK test_value;");
+
+    // Reads leave the file alone, the next write saves the store again.
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "");
+
+    let (_, response) = send_command(client, "SET other_key other_value;").await;
+
+    assert_eq!(response, "OK OK;");
+
+    assert!(std::fs::read_to_string(&file_path)
+        .unwrap()
+        .contains("other_key"));
+
+    server_handle.stop().await;
 }
 
 #[tokio::test]
@@ -289,7 +445,7 @@ async fn test_integration_persistence_load_from_json() {
 
     let file_path = temp_file.path().to_str().expect("No path").to_string();
 
-    let _ = start_test_server(port, Some(file_path.clone())).await;
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
 
     tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
 
@@ -299,17 +455,1648 @@ async fn test_integration_persistence_load_from_json() {
 
     let (client, response) = send_command(client, "AUTH admin Password4;").await;
 
-    assert_eq!(response, "OK;");
+    assert_eq!(response, "OK OK;");
 
     let (client, response) = send_command(client, "GET test_key;").await;
 
-    assert_eq!(response, "test_value;");
+    assert_eq!(response, "OK test_value;");
 
     let (client, response) = send_command(client, "GET users:john_doe:age;").await;
 
-    assert_eq!(response, "42;");
+    assert_eq!(response, "OK 42;");
 
     let (_, response) = send_command(client, "GET users:john_doe:age;").await;
 
-    assert_eq!(response, "42;");
+    assert_eq!(response, "OK 42;");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_reply_status() {
+    let port = get_next_port().await;
+
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (client, response) = send_command(client, "GET key;").await;
+    assert_eq!(response, "ERR NOT_AUTHENTICATED User not authenticated;");
+
+    let (client, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (client, response) = send_command(client, r#"SET key "Key not found";"#).await;
+    assert_eq!(response, "OK OK;");
+
+    let (client, response) = send_command(client, "GET key;").await;
+    assert_eq!(response, "OK Key not found;");
+
+    let (client, response) = send_command(client, "SET key value INT;").await;
+    assert_eq!(response, "ERR BAD_ARGS Invalid data type;");
+
+    let (_, response) = send_command(client, "UNKNOWN key;").await;
+    assert_eq!(response, "ERR BAD_ARGS Invalid command;");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_quoted_values() {
+    let port = get_next_port().await;
+
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (client, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (client, response) = send_command(client, r#"SET key "hello world";"#).await;
+    assert_eq!(response, "OK OK;");
+
+    let (mut client, response) = send_command(client, "GET key;").await;
+    assert_eq!(response, "OK hello world;");
+
+    client
+        .write_all(br#"SET key "a;b \"c\"";GET key;"#)
+        .await
+        .unwrap();
+    let replies = read_replies(&mut client, 2).await;
+    assert_eq!(replies, vec!["OK OK", "OK a;b \"c\""]);
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_large_value() {
+    let port = get_next_port().await;
+
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (client, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let value = "v".repeat(100_000);
+
+    let (client, response) = send_command(client, &format!("SET key {};", value)).await;
+    assert_eq!(response, "OK OK;");
+
+    let (_, response) = send_command(client, "GET key;").await;
+    assert_eq!(response, format!("OK {};", value));
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_frame_too_large() {
+    let port = get_next_port().await;
+
+    let mut config = Config::new();
+    config.add_server_config(ADDRESS.to_string(), port);
+    config.add_max_frame_size(64);
+
+    let server_handle = start_test_server_with_config(config).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (client, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (mut client, response) =
+        send_command(client, &format!("SET key {};", "v".repeat(128))).await;
+    assert_eq!(
+        response,
+        "ERR PROTOCOL Frame too large! Maximum size is 64 bytes;"
+    );
+
+    let mut buf = [0; 16];
+    assert_eq!(client.read(&mut buf).await.unwrap(), 0);
+
+    server_handle.stop().await;
+}
+
+async fn send_resp_command(client: &mut TcpStream, parts: &[&str], expected: &str) {
+    let mut command = format!("*{}\r\n", parts.len());
+    for part in parts {
+        command.push_str(&format!("${}\r\n{}\r\n", part.len(), part));
+    }
+
+    client.write_all(command.as_bytes()).await.unwrap();
+
+    let mut buf = vec![0; expected.len()];
+    client.read_exact(&mut buf).await.unwrap();
+
+    assert_eq!(String::from_utf8_lossy(&buf), expected);
+}
+
+#[tokio::test]
+async fn test_integration_resp_listener() {
+    let port = get_next_port().await;
+    let resp_port = get_next_port().await;
+
+    let mut config = Config::new();
+    config.add_server_config(ADDRESS.to_string(), port);
+    config.add_resp_config(resp_port);
+
+    let server_handle = start_test_server_with_config(config).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = TcpStream::connect(format!("{}:{}", ADDRESS, resp_port))
+        .await
+        .unwrap();
+
+    send_resp_command(&mut client, &["PING"], "+PONG\r\n").await;
+    send_resp_command(
+        &mut client,
+        &["GET", "key"],
+        "-NOT_AUTHENTICATED User not authenticated\r\n",
+    )
+    .await;
+    send_resp_command(&mut client, &["AUTH", "admin", "Password4"], "+OK\r\n").await;
+    send_resp_command(&mut client, &["set", "key", "hello world"], "+OK\r\n").await;
+    send_resp_command(&mut client, &["GET", "key"], "$11\r\nhello world\r\n").await;
+    send_resp_command(&mut client, &["SET", "key", "1", "INT"], "+OK\r\n").await;
+    send_resp_command(&mut client, &["DEL", "key"], "+OK\r\n").await;
+    send_resp_command(&mut client, &["GET", "key"], "$-1\r\n").await;
+    send_resp_command(&mut client, &["CREATE_STORE", "users"], "+OK\r\n").await;
+    send_resp_command(&mut client, &["CREATE_STORE", "users:john"], "+OK\r\n").await;
+    send_resp_command(&mut client, &["LIST_KEYS", "users"], "*1\r\n$4\r\njohn\r\n").await;
+    send_resp_command(&mut client, &["RPUSH", "list", "a\nb", "c\r", ""], ":3\r\n").await;
+    send_resp_command(
+        &mut client,
+        &["LRANGE", "list", "0", "-1"],
+        "*3\r\n$3\r\na\nb\r\n$2\r\nc\r\r\n$0\r\n\r\n",
+    )
+    .await;
+    send_resp_command(
+        &mut client,
+        &["DEL", "missing"],
+        "-NOT_FOUND Key not found\r\n",
+    )
+    .await;
+    send_resp_command(&mut client, &["UNKNOWN"], "-BAD_ARGS Invalid command\r\n").await;
+
+    send_resp_command(&mut client, &["HELLO", "3"], "%3\r\n").await;
+
+    let mut client = TcpStream::connect(format!("{}:{}", ADDRESS, resp_port))
+        .await
+        .unwrap();
+
+    send_resp_command(
+        &mut client,
+        &["HELLO", "3", "AUTH", "admin", "Password4"],
+        "%3\r\n$6\r\nserver\r\n$7\r\nkvstore\r\n",
+    )
+    .await;
+
+    server_handle.stop().await;
+}
+
+// Sends a single HTTP/1.1 request and returns the status code and body.
+async fn send_http_request(
+    port: u16,
+    method: &str,
+    path: &str,
+    auth: Option<&str>,
+    body: Option<&str>,
+) -> (u16, serde_json::Value) {
+    let mut client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+        method, path, ADDRESS
+    );
+    if let Some(auth) = auth {
+        request.push_str(&format!("Authorization: {}\r\n", auth));
+    }
+    let body = body.unwrap_or("");
+    if !body.is_empty() {
+        request.push_str("Content-Type: application/json\r\n");
+    }
+    request.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+
+    client.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    client.read_to_string(&mut response).await.unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse::<u16>().unwrap();
+
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[tokio::test]
+async fn test_integration_http_gateway() {
+    let port = get_next_port().await;
+    let http_port = get_next_port().await;
+
+    let mut config = Config::new();
+    config.add_server_config(ADDRESS.to_string(), port);
+    config.add_http_config(http_port);
+
+    let server_handle = start_test_server_with_config(config).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    // "admin:Password4"
+    let basic = Some("Basic YWRtaW46UGFzc3dvcmQ0");
+
+    let (status, body) = send_http_request(http_port, "GET", "/v1/keys/key", None, None).await;
+    assert_eq!(status, 401);
+    assert_eq!(body["code"], "NOT_AUTHENTICATED");
+
+    let (status, body) = send_http_request(
+        http_port,
+        "PUT",
+        "/v1/keys/key",
+        basic,
+        Some(r#"{"value":"hello world"}"#),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["payload"], "OK");
+
+    let (status, body) = send_http_request(http_port, "POST", "/v1/auth/token", basic, None).await;
+    assert_eq!(status, 200);
+    let bearer = format!("Bearer {}", body["payload"].as_str().unwrap());
+
+    let (status, body) =
+        send_http_request(http_port, "GET", "/v1/keys/key", Some(&bearer), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["payload"], "hello world");
+
+    let (status, body) = send_http_request(
+        http_port,
+        "PUT",
+        "/v1/keys/key",
+        Some(&bearer),
+        Some(r#"{"value":"abc","type":"INT"}"#),
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert_eq!(body["code"], "BAD_ARGS");
+
+    let (status, _) = send_http_request(http_port, "POST", "/v1/stores/users", basic, None).await;
+    assert_eq!(status, 200);
+
+    let (status, body) =
+        send_http_request(http_port, "POST", "/v1/stores/users", basic, None).await;
+    assert_eq!(status, 409);
+    assert_eq!(body["code"], "ALREADY_EXISTS");
+
+    let (status, _) =
+        send_http_request(http_port, "POST", "/v1/stores/users:john", basic, None).await;
+    assert_eq!(status, 200);
+
+    let (status, _) = send_http_request(
+        http_port,
+        "PUT",
+        "/v1/keys/users:john:age",
+        basic,
+        Some(r#"{"value":"42","type":"INT"}"#),
+    )
+    .await;
+    assert_eq!(status, 200);
+
+    let (status, body) =
+        send_http_request(http_port, "GET", "/v1/stores/users/keys", basic, None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["payload"], "john");
+
+    let (status, _) = send_http_request(
+        http_port,
+        "POST",
+        "/v1/users",
+        basic,
+        Some(r#"{"username":"reader","password":"Password4","permissions":["GET"]}"#),
+    )
+    .await;
+    assert_eq!(status, 200);
+
+    // "reader:Password4"
+    let reader = Some("Basic cmVhZGVyOlBhc3N3b3JkNA==");
+
+    let (status, body) = send_http_request(http_port, "DELETE", "/v1/keys/key", reader, None).await;
+    assert_eq!(status, 403);
+    assert_eq!(body["code"], "NO_PERMISSION");
+
+    let (status, _) = send_http_request(http_port, "DELETE", "/v1/keys/key", basic, None).await;
+    assert_eq!(status, 200);
+
+    let (status, body) = send_http_request(http_port, "GET", "/v1/keys/key", reader, None).await;
+    assert_eq!(status, 404);
+    assert_eq!(body["code"], "NOT_FOUND");
+
+    let (status, _) =
+        send_http_request(http_port, "DELETE", "/v1/auth/token", Some(&bearer), None).await;
+    assert_eq!(status, 200);
+
+    let (status, body) =
+        send_http_request(http_port, "GET", "/v1/keys/key", Some(&bearer), None).await;
+    assert_eq!(status, 401);
+    assert_eq!(body["code"], "AUTH_FAILED");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_http_gateway_token_password_change() {
+    let port = get_next_port().await;
+    let http_port = get_next_port().await;
+
+    let mut config = Config::new();
+    config.add_server_config(ADDRESS.to_string(), port);
+    config.add_http_config(http_port);
+
+    let server_handle = start_test_server_with_config(config).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut admin = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    admin.auth("admin", "Password4").await.unwrap();
+    admin
+        .create_user("reader", "Password4", &[Permissions::GET])
+        .await
+        .unwrap();
+
+    // "reader:Password4"
+    let reader = Some("Basic cmVhZGVyOlBhc3N3b3JkNA==");
+
+    let (status, body) = send_http_request(http_port, "POST", "/v1/auth/token", reader, None).await;
+    assert_eq!(status, 200);
+    let bearer = format!("Bearer {}", body["payload"].as_str().unwrap());
+
+    let (status, _) =
+        send_http_request(http_port, "GET", "/v1/keys/key", Some(&bearer), None).await;
+    assert_eq!(status, 404);
+
+    // Resetting the password invalidates the token, and no new one is issued
+    // until the user has chosen a new password.
+    admin
+        .set_password("reader", "Password5", true)
+        .await
+        .unwrap();
+
+    let (status, body) =
+        send_http_request(http_port, "GET", "/v1/keys/key", Some(&bearer), None).await;
+    assert_eq!(status, 401);
+    assert_eq!(body["code"], "AUTH_FAILED");
+
+    // "reader:Password5"
+    let reader = Some("Basic cmVhZGVyOlBhc3N3b3JkNQ==");
+
+    let (status, body) = send_http_request(http_port, "POST", "/v1/auth/token", reader, None).await;
+    assert_eq!(status, 403);
+    assert_eq!(body["code"], "PASSWORD_CHANGE_REQUIRED");
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("reader", "Password5").await.unwrap();
+    client
+        .change_password("Password5", "Password6")
+        .await
+        .unwrap();
+
+    // "reader:Password6"
+    let reader = Some("Basic cmVhZGVyOlBhc3N3b3JkNg==");
+
+    let (status, body) = send_http_request(http_port, "POST", "/v1/auth/token", reader, None).await;
+    assert_eq!(status, 200);
+    let bearer = format!("Bearer {}", body["payload"].as_str().unwrap());
+
+    let (status, _) =
+        send_http_request(http_port, "GET", "/v1/keys/key", Some(&bearer), None).await;
+    assert_eq!(status, 404);
+
+    // Changing the password invalidates the token as well.
+    client
+        .change_password("Password6", "Password7")
+        .await
+        .unwrap();
+
+    let (status, body) =
+        send_http_request(http_port, "GET", "/v1/keys/key", Some(&bearer), None).await;
+    assert_eq!(status, 401);
+    assert_eq!(body["code"], "AUTH_FAILED");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_http_gateway_token_expires() {
+    let port = get_next_port().await;
+    let http_port = get_next_port().await;
+
+    let mut config = Config::new();
+    config.add_server_config(ADDRESS.to_string(), port);
+    config.add_http_config(http_port);
+    config.add_token_ttl(1);
+
+    let server_handle = start_test_server_with_config(config).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    // "admin:Password4"
+    let basic = Some("Basic YWRtaW46UGFzc3dvcmQ0");
+
+    let (status, body) = send_http_request(http_port, "POST", "/v1/auth/token", basic, None).await;
+    assert_eq!(status, 200);
+    let bearer = format!("Bearer {}", body["payload"].as_str().unwrap());
+
+    let (status, _) =
+        send_http_request(http_port, "GET", "/v1/keys/key", Some(&bearer), None).await;
+    assert_eq!(status, 404);
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+
+    let (status, body) =
+        send_http_request(http_port, "GET", "/v1/keys/key", Some(&bearer), None).await;
+    assert_eq!(status, 401);
+    assert_eq!(body["code"], "AUTH_FAILED");
+
+    // A new token is issued as usual once the old one expired.
+    let (status, body) = send_http_request(http_port, "POST", "/v1/auth/token", basic, None).await;
+    assert_eq!(status, 200);
+    let bearer = format!("Bearer {}", body["payload"].as_str().unwrap());
+
+    let (status, _) =
+        send_http_request(http_port, "GET", "/v1/keys/key", Some(&bearer), None).await;
+    assert_eq!(status, 404);
+
+    server_handle.stop().await;
+}
+
+// Self-signed CA with a server certificate for `localhost` and a client
+// certificate, written to a temporary directory for the server to load.
+struct TlsFixture {
+    dir: TempDir,
+    ca_pem: String,
+    client_cert_pem: String,
+    client_key_der: Vec<u8>,
+}
+
+fn generate_tls_fixture() -> TlsFixture {
+    let dir = tempfile::tempdir().unwrap();
+
+    let ca_key = KeyPair::generate().unwrap();
+    let mut ca_params = CertificateParams::new(Vec::new()).unwrap();
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+
+    let server_key = KeyPair::generate().unwrap();
+    let server_cert = CertificateParams::new(vec!["localhost".to_string()])
+        .unwrap()
+        .signed_by(&server_key, &ca_cert, &ca_key)
+        .unwrap();
+
+    let client_key = KeyPair::generate().unwrap();
+    let client_cert = CertificateParams::new(vec!["client".to_string()])
+        .unwrap()
+        .signed_by(&client_key, &ca_cert, &ca_key)
+        .unwrap();
+
+    std::fs::write(dir.path().join("ca.pem"), ca_cert.pem()).unwrap();
+    std::fs::write(dir.path().join("server.pem"), server_cert.pem()).unwrap();
+    std::fs::write(dir.path().join("server.key"), server_key.serialize_pem()).unwrap();
+
+    TlsFixture {
+        dir,
+        ca_pem: ca_cert.pem(),
+        client_cert_pem: client_cert.pem(),
+        client_key_der: client_key.serialize_der(),
+    }
+}
+
+impl TlsFixture {
+    fn path(&self, name: &str) -> String {
+        self.dir.path().join(name).to_string_lossy().to_string()
+    }
+
+    fn server_config(&self, verify_clients: bool, tls_only: bool) -> TlsConfig {
+        TlsConfig {
+            cert_path: self.path("server.pem"),
+            key_path: self.path("server.key"),
+            client_ca_path: verify_clients.then(|| self.path("ca.pem")),
+            tls_only,
+        }
+    }
+
+    async fn connect(&self, port: u16, with_client_cert: bool) -> TlsStream<TcpStream> {
+        let mut roots = RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut self.ca_pem.as_bytes()) {
+            roots.add(cert.unwrap()).unwrap();
+        }
+
+        let builder = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots);
+
+        let client_config = if with_client_cert {
+            let certs = rustls_pemfile::certs(&mut self.client_cert_pem.as_bytes())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let key = PrivateKeyDer::Pkcs8(self.client_key_der.clone().into());
+            builder.with_client_auth_cert(certs, key).unwrap()
+        } else {
+            builder.with_no_client_auth()
+        };
+
+        let socket = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+            .await
+            .unwrap();
+
+        TlsConnector::from(Arc::new(client_config))
+            .connect(ServerName::try_from("localhost").unwrap(), socket)
+            .await
+            .unwrap()
+    }
+}
+
+// Sends a command and returns whatever the server wrote back before closing
+// the connection.
+async fn send_rejected_command<S: AsyncRead + AsyncWrite + Unpin>(
+    mut client: S,
+    command: &str,
+) -> String {
+    let _ = client.write_all(command.as_bytes()).await;
+
+    let mut buf = Vec::new();
+    let _ = client.read_to_end(&mut buf).await;
+
+    String::from_utf8_lossy(&buf).to_string()
+}
+
+async fn start_tls_test_server(port: u16, tls: TlsConfig) -> TestServer {
+    let mut config = Config::new();
+    config.add_server_config(ADDRESS.to_string(), port);
+    config.add_tls_config(tls);
+
+    let server_handle = start_test_server_with_config(config).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    server_handle
+}
+
+#[tokio::test]
+async fn test_integration_tls() {
+    let fixture = generate_tls_fixture();
+    let port = get_next_port().await;
+    let server_handle = start_tls_test_server(port, fixture.server_config(false, false)).await;
+
+    let client = fixture.connect(port, false).await;
+
+    let (client, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (client, response) = send_command(client, "SET key value;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (_, response) = send_command(client, "GET key;").await;
+    assert_eq!(response, "OK value;");
+
+    // Plaintext clients are still served when TLS is optional.
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (_, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_tls_only() {
+    let fixture = generate_tls_fixture();
+    let port = get_next_port().await;
+    let server_handle = start_tls_test_server(port, fixture.server_config(false, true)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let response = send_rejected_command(client, "AUTH admin Password4;").await;
+    assert!(!response.contains("OK"));
+
+    let client = fixture.connect(port, false).await;
+
+    let (_, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_tls_client_certificate() {
+    let fixture = generate_tls_fixture();
+    let port = get_next_port().await;
+    let server_handle = start_tls_test_server(port, fixture.server_config(true, true)).await;
+
+    let client = fixture.connect(port, false).await;
+
+    let response = send_rejected_command(client, "AUTH admin Password4;").await;
+    assert!(!response.contains("OK"));
+
+    let client = fixture.connect(port, true).await;
+
+    let (_, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_unix_socket() {
+    let (server_handle, dir, socket_path) = start_unix_test_server(None).await;
+
+    let permissions = std::fs::metadata(&socket_path).unwrap().permissions();
+    assert_eq!(
+        std::os::unix::fs::PermissionsExt::mode(&permissions) & 0o777,
+        0o600
+    );
+
+    // The directory the socket was bound in is gone again.
+    let entries: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(entries, vec!["kvstore.sock"]);
+
+    let first_client = UnixStream::connect(&socket_path).await.unwrap();
+    let second_client = UnixStream::connect(&socket_path).await.unwrap();
+
+    let (first_client, response) = send_command(first_client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (second_client, response) = send_command(second_client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (_, response) = send_command(first_client, "SET key value;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (_, response) = send_command(second_client, "GET key;").await;
+    assert_eq!(response, "OK value;");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_unix_socket_persistence() {
+    let file = NamedTempFile::new().unwrap();
+    let file_path = file.path().to_str().unwrap().to_string();
+
+    let (server_handle, _dir, socket_path) = start_unix_test_server(Some(file_path.clone())).await;
+
+    let client = UnixStream::connect(&socket_path).await.unwrap();
+
+    let (client, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (_, response) = send_command(client, "SET key value;").await;
+    assert_eq!(response, "OK OK;");
+
+    server_handle.stop().await;
+
+    let (server_handle, _dir, socket_path) = start_unix_test_server(Some(file_path)).await;
+
+    let client = UnixStream::connect(&socket_path).await.unwrap();
+
+    let (client, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (_, response) = send_command(client, "GET key;").await;
+    assert_eq!(response, "OK value;");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_graceful_shutdown() {
+    let port = get_next_port().await;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let file_path = temp_file.path().to_str().unwrap().to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (client, response) = send_command(client, "AUTH admin Password4;").await;
+    assert_eq!(response, "OK OK;");
+
+    let (mut client, response) = send_command(client, "SET key value;").await;
+    assert_eq!(response, "OK OK;");
+
+    // Wipe the per-command save so only the final save can restore the data.
+    std::fs::write(&file_path, "").unwrap();
+
+    server_handle.stop().await;
+
+    let mut buf = Vec::new();
+    assert_eq!(client.read_to_end(&mut buf).await.unwrap(), 0);
+
+    assert!(TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .is_err());
+
+    let saved = std::fs::read_to_string(&file_path).unwrap();
+    assert!(saved.contains("\"key\":{\"value\":\"value\""));
+}
+
+#[tokio::test]
+async fn test_integration_resp_graceful_shutdown() {
+    let port = get_next_port().await;
+    let resp_port = get_next_port().await;
+
+    let mut config = Config::new();
+    config.add_server_config(ADDRESS.to_string(), port);
+    config.add_resp_config(resp_port);
+
+    let server_handle = start_test_server_with_config(config).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = TcpStream::connect(format!("{}:{}", ADDRESS, resp_port))
+        .await
+        .unwrap();
+
+    send_resp_command(&mut client, &["AUTH", "admin", "Password4"], "+OK\r\n").await;
+
+    // The BLPOP keeps the handler busy until the server stops, the commands
+    // behind it are already buffered by then and still have to be answered.
+    let mut batch = String::new();
+    for command in [
+        &["BLPOP", "list", "0"][..],
+        &["SET", "key", "value"],
+        &["GET", "key"],
+        &["INCR", "counter"],
+        &["INCR", "counter"],
+        &["PING"],
+    ] {
+        batch.push_str(&format!("*{}\r\n", command.len()));
+        for part in command {
+            batch.push_str(&format!("${}\r\n{}\r\n", part.len(), part));
+        }
+    }
+    client.write_all(batch.as_bytes()).await.unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    server_handle.stop().await;
+
+    let mut buf = Vec::new();
+    client.read_to_end(&mut buf).await.unwrap();
+
+    assert_eq!(
+        String::from_utf8_lossy(&buf),
+        "$-1\r\n+OK\r\n$5\r\nvalue\r\n:1\r\n:2\r\n+PONG\r\n"
+    );
+}
+
+#[tokio::test]
+async fn test_integration_client() {
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    assert_eq!(client.get("key").await, Err(KvError::NotAuthenticated));
+    assert_eq!(
+        client.auth("admin", "WrongPassword4").await,
+        Err(KvError::AuthFailed)
+    );
+
+    client.auth("admin", "Password4").await.unwrap();
+
+    client
+        .set("key", "hello; \"world\"", DataTypes::STRING)
+        .await
+        .unwrap();
+    assert_eq!(client.get("key").await.unwrap(), "hello; \"world\"");
+
+    assert!(matches!(
+        client.set("key", "abc", DataTypes::INT).await,
+        Err(KvError::BadArgs(_))
+    ));
+
+    client.del("key").await.unwrap();
+    assert_eq!(client.get("key").await, Err(KvError::key_not_found()));
+
+    client.create_store("users").await.unwrap();
+    client.create_store("users:john").await.unwrap();
+    client
+        .set("users:john:age", "42", DataTypes::INT)
+        .await
+        .unwrap();
+    assert_eq!(client.list_keys("users").await.unwrap(), vec!["john"]);
+
+    client
+        .create_user("reader", "Password4", &[Permissions::GET])
+        .await
+        .unwrap();
+    client
+        .grant("reader", &[Permissions::USER_ADMIN, Permissions::SET])
+        .await
+        .unwrap();
+    client
+        .revoke("reader", &[Permissions::USER_ADMIN, Permissions::SET])
+        .await
+        .unwrap();
+    assert!(client.get_user("reader").await.unwrap().contains("reader"));
+
+    let mut reader = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    reader.auth("reader", "Password4").await.unwrap();
+
+    assert_eq!(reader.get("users:john:age").await.unwrap(), "42");
+    assert_eq!(
+        reader.set("key", "value", DataTypes::STRING).await,
+        Err(KvError::NoPermission)
+    );
+
+    client.delete_user("reader").await.unwrap();
+    assert!(matches!(
+        client.get_user("reader").await,
+        Err(KvError::NotFound(_))
+    ));
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_client_reconnect() {
+    let port = get_next_port().await;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let file_path = temp_file.path().to_str().unwrap().to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+    client.set("key", "value", DataTypes::STRING).await.unwrap();
+
+    server_handle.stop().await;
+
+    let server_handle = start_test_server(port, Some(file_path)).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    // The first request notices the closed connection, reconnects and
+    // authenticates again before retrying.
+    assert_eq!(client.get("key").await.unwrap(), "value");
+    assert!(client.is_connected());
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_client_reconnect_after_password_change() {
+    let port = get_next_port().await;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let file_path = temp_file.path().to_str().unwrap().to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+    client.set("key", "value", DataTypes::STRING).await.unwrap();
+    client
+        .change_password("Password4", "Password5")
+        .await
+        .unwrap();
+
+    server_handle.stop().await;
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    // The reconnect authenticates with the new password.
+    assert_eq!(client.get("key").await.unwrap(), "value");
+
+    server_handle.stop().await;
+    std::fs::remove_file(format!("{}.auth", file_path)).unwrap();
+}
+
+#[tokio::test]
+async fn test_integration_client_compare_and_set() {
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let address = format!("{}:{}", ADDRESS, port);
+    let mut first = Client::connect(&address).await.unwrap();
+    first.auth("admin", "Password4").await.unwrap();
+    let mut second = Client::connect(&address).await.unwrap();
+    second.auth("admin", "Password4").await.unwrap();
+
+    assert_eq!(
+        first.cas("config", 0, "a=1", DataTypes::STRING).await,
+        Ok(1)
+    );
+
+    let (value, version) = first.get_with_version("config").await.unwrap();
+    assert_eq!((value.as_str(), version), ("a=1", 1));
+    let (_, other_version) = second.get_with_version("config").await.unwrap();
+
+    // Both clients read version 1; only the first update wins.
+    assert_eq!(
+        first.cas("config", version, "a=2", DataTypes::STRING).await,
+        Ok(2)
+    );
+    assert!(matches!(
+        second
+            .cas("config", other_version, "a=3", DataTypes::STRING)
+            .await,
+        Err(KvError::VersionMismatch(_))
+    ));
+
+    assert_eq!(second.get("config").await.unwrap(), "a=2");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_client_counters() {
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let address = format!("{}:{}", ADDRESS, port);
+    let mut tasks = Vec::new();
+    for _ in 0..4 {
+        let address = address.clone();
+        tasks.push(tokio::spawn(async move {
+            let mut client = Client::connect(&address).await.unwrap();
+            client.auth("admin", "Password4").await.unwrap();
+            for _ in 0..25 {
+                client.incr_by("hits", 1).await.unwrap();
+            }
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let mut client = Client::connect(&address).await.unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    // No increment is lost between concurrent clients.
+    assert_eq!(client.incr_by("hits", -100).await, Ok(0));
+    assert_eq!(client.incr_by_float("ratio", 0.5).await, Ok(0.5));
+
+    client
+        .set("enabled", "false", DataTypes::BOOL)
+        .await
+        .unwrap();
+    assert_eq!(client.toggle("enabled").await, Ok(true));
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_lists() {
+    let port = get_next_port().await;
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path().to_str().expect("No path").to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let address = format!("{}:{}", ADDRESS, port);
+    let mut client = Client::connect(&address).await.unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    assert_eq!(client.rpush("queue", &["a", "b"]).await, Ok(2));
+    assert_eq!(client.lpush("queue", &["z"]).await, Ok(3));
+    assert_eq!(client.rpop("queue").await, Ok("b".to_string()));
+
+    // A BLPOP waiting on an empty list gets the element pushed by another
+    // client.
+    let waiter = tokio::spawn({
+        let address = address.clone();
+        async move {
+            let mut client = Client::connect(&address).await.unwrap();
+            client.auth("admin", "Password4").await.unwrap();
+            client.blpop(&["jobs"], 5).await
+        }
+    });
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    client.rpush("jobs", &["job-1"]).await.unwrap();
+    assert_eq!(
+        waiter.await.unwrap(),
+        Ok(("jobs".to_string(), "job-1".to_string()))
+    );
+
+    server_handle.stop().await;
+
+    // The list survives a restart.
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, Some(file_path)).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    assert_eq!(
+        client.lrange("queue", 0, -1).await,
+        Ok(vec!["z".to_string(), "a".to_string()])
+    );
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_sets() {
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+    client.create_store("groups").await.unwrap();
+
+    assert_eq!(client.sadd("groups:admins", &["alice", "bob"]).await, Ok(2));
+    assert_eq!(client.sadd("editors", &["bob", "carol"]).await, Ok(2));
+    assert_eq!(client.sismember("groups:admins", "alice").await, Ok(true));
+    assert_eq!(client.srem("groups:admins", &["alice"]).await, Ok(1));
+    assert_eq!(
+        client.smembers("groups:admins").await,
+        Ok(vec!["bob".to_string()])
+    );
+    assert_eq!(
+        client
+            .execute("SUNION", &["groups:admins", "editors"])
+            .await,
+        Ok("bob\ncarol".to_string())
+    );
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_sorted_sets() {
+    let port = get_next_port().await;
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path().to_str().expect("No path").to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    assert_eq!(
+        client
+            .zadd("board", &[(10.0, "alice"), (7.5, "bob"), (3.0, "carol")])
+            .await,
+        Ok(3)
+    );
+    assert_eq!(client.zincrby("board", 5.0, "carol").await, Ok(8.0));
+    assert_eq!(client.zrank("board", "carol").await, Ok(1));
+
+    server_handle.stop().await;
+
+    // The scores survive a restart.
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, Some(file_path)).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    assert_eq!(client.zscore("board", "carol").await, Ok(8.0));
+    assert_eq!(
+        client.zrange("board", 0, -1).await,
+        Ok(vec![
+            "bob".to_string(),
+            "carol".to_string(),
+            "alice".to_string()
+        ])
+    );
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_json_documents() {
+    let port = get_next_port().await;
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path().to_str().expect("No path").to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    client
+        .json_set("user", "$", r#"{"name": "alice", "tags": []}"#)
+        .await
+        .unwrap();
+    client.json_set("user", "$.age", "30").await.unwrap();
+    assert_eq!(
+        client.json_arrappend("user", "$.tags", &[r#""a""#]).await,
+        Ok(1)
+    );
+    assert_eq!(client.json_del("user", "$.age").await, Ok(true));
+
+    server_handle.stop().await;
+
+    // The document is persisted as JSON rather than as an escaped string.
+    let snapshot = std::fs::read_to_string(&file_path).unwrap();
+    assert!(snapshot.contains(r#""json":{"name":"alice","tags":["a"]}"#));
+
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, Some(file_path)).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    assert_eq!(
+        client.json_get("user", "$.tags").await,
+        Ok(r#"["a"]"#.to_string())
+    );
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_bytes() {
+    let port = get_next_port().await;
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path().to_str().expect("No path").to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    // Bytes that are not valid UTF-8.
+    let payload = vec![0x00, 0xff, 0xfe, b';', b'\n'];
+    client.set_bytes("blob", &payload).await.unwrap();
+    assert_eq!(client.get_bytes("blob").await, Ok(payload.clone()));
+
+    server_handle.stop().await;
+
+    // The bytes are persisted as a base64 string.
+    let snapshot = std::fs::read_to_string(&file_path).unwrap();
+    assert!(snapshot.contains(r#""bytes":"AP/+Owo=""#));
+
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, Some(file_path)).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    assert_eq!(client.get_bytes("blob").await, Ok(payload));
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_users_persisted_apart_from_data() {
+    let port = get_next_port().await;
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path().to_str().expect("No path").to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    client
+        .create_user("alice", "Password4", &[Permissions::GET])
+        .await
+        .unwrap();
+    assert!(client.get("_auth:users:admin:password").await.is_err());
+
+    server_handle.stop().await;
+
+    // Password hashes never end up in the data snapshot.
+    let snapshot = std::fs::read_to_string(&file_path).unwrap();
+    assert!(!snapshot.contains("password"));
+    let auth_path = format!("{}.auth", file_path);
+    assert!(std::fs::read_to_string(&auth_path)
+        .unwrap()
+        .contains("alice"));
+
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, Some(file_path)).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("alice", "Password4").await.unwrap();
+
+    server_handle.stop().await;
+    std::fs::remove_file(auth_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_integration_scoped_permissions() {
+    let port = get_next_port().await;
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path().to_str().expect("No path").to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    client.create_store("orders").await.unwrap();
+    client
+        .set("orders:1", "a", DataTypes::STRING)
+        .await
+        .unwrap();
+    client
+        .set("customers", "b", DataTypes::STRING)
+        .await
+        .unwrap();
+    client.create_user("bob", "Password4", &[]).await.unwrap();
+    client
+        .grant_on("bob", &[Permissions::GET], "orders:*")
+        .await
+        .unwrap();
+
+    server_handle.stop().await;
+
+    // The rules are kept with the user across restarts.
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("bob", "Password4").await.unwrap();
+
+    assert_eq!(client.get("orders:1").await, Ok("a".to_string()));
+    assert_eq!(client.get("customers").await, Err(KvError::NoPermission));
+
+    server_handle.stop().await;
+    std::fs::remove_file(format!("{}.auth", file_path)).unwrap();
+}
+
+#[tokio::test]
+async fn test_integration_roles() {
+    let port = get_next_port().await;
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path().to_str().expect("No path").to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    client.set("key", "value", DataTypes::STRING).await.unwrap();
+    client.create_user("bob", "Password4", &[]).await.unwrap();
+    client
+        .create_role("reader", &[Permissions::GET])
+        .await
+        .unwrap();
+    client.assign_role("bob", "reader").await.unwrap();
+
+    server_handle.stop().await;
+
+    // Roles and their assignments are kept across restarts.
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+    assert_eq!(
+        client.list_roles().await,
+        Ok(vec!["Role: reader Permissions: GET".to_string()])
+    );
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("bob", "Password4").await.unwrap();
+    assert_eq!(client.get("key").await, Ok("value".to_string()));
+
+    server_handle.stop().await;
+    std::fs::remove_file(format!("{}.auth", file_path)).unwrap();
+}
+
+#[tokio::test]
+async fn test_integration_password_change() {
+    let port = get_next_port().await;
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path().to_str().expect("No path").to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut client = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    client.auth("admin", "Password4").await.unwrap();
+
+    client.set("key", "value", DataTypes::STRING).await.unwrap();
+    client
+        .create_user("bob", "Password4", &[Permissions::GET])
+        .await
+        .unwrap();
+    client.set_password("bob", "Password5", true).await.unwrap();
+
+    // Nothing but PASSWORD works until bob has chosen a new password.
+    let mut bob = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    bob.auth("bob", "Password5").await.unwrap();
+    assert_eq!(bob.get("key").await, Err(KvError::PasswordChangeRequired));
+    bob.change_password("Password5", "Password6").await.unwrap();
+    assert_eq!(bob.get("key").await, Ok("value".to_string()));
+
+    server_handle.stop().await;
+
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let mut bob = Client::connect(&format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+    assert_eq!(bob.auth("bob", "Password5").await, Err(KvError::AuthFailed));
+    bob.auth("bob", "Password6").await.unwrap();
+    assert_eq!(bob.get("key").await, Ok("value".to_string()));
+
+    server_handle.stop().await;
+    std::fs::remove_file(format!("{}.auth", file_path)).unwrap();
+}
+
+#[tokio::test]
+async fn test_integration_client_pool() {
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let pool = Arc::new(
+        Pool::new(&format!("{}:{}", ADDRESS, port), 2).with_credentials("admin", "Password4"),
+    );
+
+    let mut handles = Vec::new();
+    for i in 0..6 {
+        let pool = Arc::clone(&pool);
+        handles.push(tokio::spawn(async move {
+            let mut client = pool.get().await.unwrap();
+            let key = format!("key{}", i);
+            client.set(&key, "value", DataTypes::STRING).await.unwrap();
+            assert_eq!(client.get(&key).await.unwrap(), "value");
+        }));
+    }
+
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(pool.idle_count(), 2);
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_key_expiration() {
+    let port = get_next_port().await;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let file_path = temp_file.path().to_str().unwrap().to_string();
+
+    let server_handle = start_test_server(port, Some(file_path.clone())).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (client, response) = send_command(
+        client,
+        "AUTH admin Password4;SET short value EX 1;SET long value EX 100;CREATE_STORE cache;EXPIRE cache 1;",
+    )
+    .await;
+
+    assert_eq!(response, "OK OK;OK OK;OK OK;OK OK;OK OK;");
+
+    // Nobody reads the short-lived keys again, so the reaper has to drop
+    // them and save the store.
+    tokio::time::sleep(tokio::time::Duration::from_millis(2500)).await;
+
+    // Only the versions of the expired keys are kept.
+    let json = std::fs::read_to_string(&file_path).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(json["data"].get("short").is_none());
+    assert!(json["stores"].get("cache").is_none());
+    assert!(json["data"].get("long").is_some());
+
+    drop(client);
+    server_handle.stop().await;
+
+    let server_handle = start_test_server(port, Some(file_path)).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (_, response) =
+        send_command(client, "AUTH admin Password4;GET long;TTL long;GET short;").await;
+
+    let replies = response.split(';').collect::<Vec<&str>>();
+    assert_eq!(&replies[..2], &["OK OK", "OK value"]);
+    let ttl = replies[2]
+        .strip_prefix("OK ")
+        .unwrap()
+        .parse::<u64>()
+        .unwrap();
+    assert!(ttl > 90 && ttl <= 100);
+    assert_eq!(replies[3], "ERR NOT_FOUND Key not found");
+
+    server_handle.stop().await;
+}
+
+#[tokio::test]
+async fn test_integration_transactions() {
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let client = TcpStream::connect(format!("{}:{}", ADDRESS, port))
+        .await
+        .unwrap();
+
+    let (client, response) = send_command(
+        client,
+        "AUTH admin Password4;MULTI;SET a 1 INT;SET b 2 INT;EXEC;",
+    )
+    .await;
+
+    assert_eq!(response, "OK OK;OK OK;OK QUEUED;OK QUEUED;OK OK\nOK;");
+
+    let (client, response) =
+        send_command(client, "MULTI;SET a 5 INT;DEL missing;EXEC;GET a;").await;
+
+    assert_eq!(
+        response,
+        "OK OK;OK QUEUED;OK QUEUED;ERR NOT_FOUND Key not found;OK 1;"
+    );
+
+    // A command that does not parse aborts the whole transaction.
+    let (_, response) = send_command(client, "MULTI;SET a x INT;SET b 7 INT;EXEC;GET b;").await;
+
+    let replies = response.split(';').collect::<Vec<&str>>();
+    assert!(replies[1].starts_with("ERR BAD_ARGS"));
+    assert!(replies[3].starts_with("ERR BAD_ARGS"));
+    assert_eq!(replies[4], "OK 2");
+
+    server_handle.stop().await;
+}
+
+async fn run_cli(port: u16, args: &[&str]) -> std::process::Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_kvstore-cli"))
+        .args(["--port", &port.to_string()])
+        .args(["--user", "admin", "--password", "Password4"])
+        .args(args)
+        .output()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_integration_cli() {
+    let port = get_next_port().await;
+    let server_handle = start_test_server(port, None).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+    let output = run_cli(port, &["SET", "key", "hello world", "STRING"]).await;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "OK\n");
+
+    let output = run_cli(port, &["GET", "key"]).await;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "\"hello world\"\n");
+
+    let output = run_cli(port, &["GET", "missing"]).await;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("(error) NOT_FOUND"));
+
+    let mut script = NamedTempFile::new().unwrap();
+    writeln!(
+        script,
+        "# create a store\nCREATE_STORE users;\nCREATE_STORE users:john;\nLIST_KEYS users"
+    )
+    .unwrap();
+    let script_path = script.path().to_str().unwrap().to_string();
+
+    let output = run_cli(port, &["--file", &script_path]).await;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "OK\nOK\n1) john\n");
+
+    server_handle.stop().await;
 }